@@ -0,0 +1,115 @@
+#![feature(test)]
+extern crate test;
+
+#[allow(dead_code)]
+#[path = "../src/matcher.rs"]
+mod matcher;
+#[path = "../src/scenarios.rs"]
+mod scenarios;
+
+use rand::seq::SliceRandom;
+use test::Bencher;
+
+// Teams of ten, like a company draft.
+fn teams(members: usize) -> Vec<u32> {
+    (0..members).map(|index| (index / 10) as u32).collect()
+}
+
+// The previous approach, collecting the candidates of every giver anew.
+fn naive(teams: &[u32]) -> Option<Vec<usize>> {
+    let mut used = Vec::new();
+    let mut recipients = Vec::new();
+    for giver in 0..teams.len() {
+        let candidates = (0..teams.len())
+            .filter(|other| teams[*other] != teams[giver] && !used.contains(other))
+            .collect::<Vec<usize>>();
+        let recipient = *candidates.choose(&mut rand::thread_rng())?;
+        used.push(recipient);
+        recipients.push(recipient);
+    }
+    Some(recipients)
+}
+
+#[bench]
+fn naive_300(b: &mut Bencher) {
+    let teams = teams(300);
+    b.iter(|| naive(&teams));
+}
+
+#[bench]
+fn matcher_300(b: &mut Bencher) {
+    let teams = teams(300);
+    b.iter(|| matcher::draw(&teams, &mut rand::thread_rng()));
+}
+
+#[bench]
+fn naive_3000(b: &mut Bencher) {
+    let teams = teams(3000);
+    b.iter(|| naive(&teams));
+}
+
+#[bench]
+fn matcher_3000(b: &mut Bencher) {
+    let teams = teams(3000);
+    b.iter(|| matcher::draw(&teams, &mut rand::thread_rng()));
+}
+
+// The default `max_draw_attempts`, no scenario may need more.
+const MAX_ATTEMPTS: u32 = 100;
+
+// The attempts the draw needs, like `Draft::draw_pool_counted` counts them:
+// 0 if the members are turned down before drawing, `None` if it gave up.
+fn attempts(teams: &[u32]) -> Option<u32> {
+    if matcher::infeasible_team(teams).is_some() {
+        return Some(0);
+    }
+    (1..=MAX_ATTEMPTS).find(|_| matcher::draw(teams, &mut rand::thread_rng()).is_ok())
+}
+
+// A pathological member set of `scenarios`, drawn within the bound, or
+// turned down right away if it can't be drawn.
+fn scenario(b: &mut Bencher, name: &str) {
+    let scenario = scenarios::scenarios()
+        .into_iter()
+        .find(|scenario| scenario.name == name)
+        .expect("known scenario");
+    b.iter(|| {
+        let attempts = attempts(&scenario.teams);
+        match attempts {
+            Some(0) => assert!(!scenario.drawable, "a drawable scenario was turned down"),
+            Some(_) => assert!(scenario.drawable, "an infeasible scenario was drawn"),
+            None => panic!("no draw within {} attempts", MAX_ATTEMPTS),
+        }
+        attempts
+    });
+}
+
+#[bench]
+fn scenario_single_member(b: &mut Bencher) {
+    scenario(b, "single_member");
+}
+
+#[bench]
+fn scenario_single_member_teams(b: &mut Bencher) {
+    scenario(b, "single_member_teams");
+}
+
+#[bench]
+fn scenario_excluded_member(b: &mut Bencher) {
+    scenario(b, "excluded_member");
+}
+
+#[bench]
+fn scenario_one_team(b: &mut Bencher) {
+    scenario(b, "one_team");
+}
+
+#[bench]
+fn scenario_two_halves(b: &mut Bencher) {
+    scenario(b, "two_halves");
+}
+
+#[bench]
+fn scenario_thousands(b: &mut Bencher) {
+    scenario(b, "thousands");
+}
@@ -0,0 +1,6 @@
+// The stylesheet is generated by `npm run css-build`; make sure the
+// directory exists so the assets can be embedded even without it.
+fn main() {
+    std::fs::create_dir_all("css").unwrap();
+    println!("cargo:rerun-if-changed=css");
+}
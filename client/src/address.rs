@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+// Where the member's gift is mailed to, none to remove it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct AddressRequest {
+    pub address: Option<String>,
+}
@@ -0,0 +1,41 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+
+// A draft as the admin lists it, the trashed ones with their purge date.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DraftRow {
+    pub id: usize,
+    pub title: String,
+    pub slug: Option<String>,
+    pub date: DateTime<FixedOffset>,
+    pub created: DateTime<Utc>,
+    pub members: usize,
+    pub drawn: bool,
+    pub upcoming: bool,
+    pub bytes: u64,
+    pub size: String,
+    pub purge: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Storage {
+    pub drafts: usize,
+    pub trashed: usize,
+    // Size of all drafts as JSON.
+    pub bytes: u64,
+    pub data_file: Option<String>,
+    pub data_file_bytes: Option<u64>,
+    // Whether the data file (or its directory, before the first save) can
+    // be written.
+    pub writable: bool,
+    pub media_dir: String,
+    pub media_files: usize,
+    pub media_bytes: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Cleanup {
+    pub trashed: usize,
+    pub purged: usize,
+    pub continued: usize,
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", content = "name", rename_all = "snake_case")]
+pub enum Actor {
+    Organizer,
+    Participant(String),
+    Admin,
+    System,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    Created,
+    Cloned { from: usize },
+    MembersAdded { names: Vec<String> },
+    Drawn,
+    Redrawn,
+    // Tickets taken from a draw outside of the site, see `external`.
+    Assigned,
+    TicketViewed { name: String },
+    RetentionExtended { until: DateTime<Utc> },
+    Trashed,
+    Restored,
+    Swapped { first: String, second: String },
+    TicketRedrawn { name: String, changed: usize },
+    // Approved for a member without naming them, see `reassign`.
+    Reassigned { changed: usize },
+    PollCreated { question: String },
+    CommentDeleted { name: String },
+    Revealed,
+    GiftReceived { name: String },
+    InvitationAccepted { name: String },
+    InvitationDeclined { name: String },
+    // See `organizers`.
+    OrganizerAdded { email: String },
+    OrganizerRemoved { email: String },
+    OwnershipTransferred { email: String },
+}
+
+impl Action {
+    // The group of the action in the timeline.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Action::Created | Action::Cloned { .. } => "created",
+            Action::MembersAdded { .. }
+            | Action::InvitationAccepted { .. }
+            | Action::InvitationDeclined { .. } => "members",
+            Action::Drawn
+            | Action::Redrawn
+            | Action::Assigned
+            | Action::Swapped { .. }
+            | Action::TicketRedrawn { .. }
+            | Action::Reassigned { .. }
+            | Action::Revealed => "draw",
+            Action::TicketViewed { .. } | Action::GiftReceived { .. } => "ticket",
+            Action::PollCreated { .. } => "poll",
+            Action::CommentDeleted { .. } => "comment",
+            Action::RetentionExtended { .. } | Action::Trashed | Action::Restored => "retention",
+            Action::OrganizerAdded { .. }
+            | Action::OrganizerRemoved { .. }
+            | Action::OwnershipTransferred { .. } => "organizers",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub time: DateTime<Utc>,
+    pub actor: Actor,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+impl Entry {
+    pub fn new(actor: Actor, action: Action) -> Entry {
+        Entry {
+            time: Utc::now(),
+            actor,
+            action,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+pub fn default_penalty() -> u32 {
+    1
+}
+
+// A pair the organizer would rather not see drawn, e.g. colleagues on the
+// same floor. Unlike teams it is given up when no draw avoids it, a draw
+// with the lowest total penalty is taken then.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Avoid {
+    pub giver: String,
+    pub recipient: String,
+    #[serde(default = "default_penalty")]
+    pub penalty: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Overview {
+    pub avoided: Vec<Avoid>,
+    // Drawn anyway in the last draw.
+    pub violated: Vec<Avoid>,
+}
@@ -0,0 +1,80 @@
+use crate::elephant;
+use crate::language::Language;
+use crate::mail::Templates;
+use crate::pool::Pool;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MemberDefinition {
+    pub name: String,
+    pub team: u32,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub pool: Option<String>,
+    // `de` or `en`, else the member follows their browser.
+    #[serde(default)]
+    pub language: Option<Language>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Definition {
+    pub title: String,
+    // `2021-12-24` or `2021-12-24T18:00`, in `timezone`.
+    pub date: String,
+    pub timezone: Option<Tz>,
+    #[serde(default)]
+    pub mode: elephant::Mode,
+    #[serde(default)]
+    pub pools: Vec<Pool>,
+    #[serde(default)]
+    pub budget: Option<u32>,
+    #[serde(default)]
+    pub team_budgets: BTreeMap<u32, u32>,
+    #[serde(default)]
+    pub emails: Templates,
+    #[serde(default)]
+    pub passphrases: bool,
+    #[serde(default)]
+    pub blind: bool,
+    // Invites the members with an email instead of drawing right away.
+    #[serde(default)]
+    pub invite: bool,
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub members: Vec<MemberDefinition>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    // Nothing is created unless every draft is valid.
+    #[default]
+    AllOrNothing,
+    // Every valid draft is created, the others are reported.
+    BestEffort,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct BulkRequest {
+    #[serde(default)]
+    pub mode: Mode,
+    pub drafts: Vec<Definition>,
+}
+
+// One result per definition, in the order they were sent.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq)]
+pub struct ItemResult {
+    pub index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organizer_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -0,0 +1,917 @@
+use crate::{
+    address, admin, archive, audit, avoid, bulk, comment, deadline, delivery, draft, invite,
+    language, mail, organizers, outbox, passphrase, poll, preset, preview, push, reassign, series,
+    share, simulate, spending, stats, suggest, theme, verify,
+};
+use chrono::{DateTime, Utc};
+use hyper::body::Bytes;
+use hyper::client::HttpConnector;
+use hyper::header::CONTENT_TYPE;
+use hyper::{Body, Method, Request, StatusCode};
+use hyper_tls::HttpsConnector;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Everything but the unreserved characters of RFC 3986 is encoded, so a
+// name or token stays a single path segment or query value.
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+#[derive(Debug)]
+pub enum Error {
+    // The server answered with an error status, and the body it sent along.
+    Status(u16, String),
+    // The server couldn't be reached or answered something unexpected.
+    Transport(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Status(status, body) if body.is_empty() => write!(f, "status {}", status),
+            Error::Status(status, body) => write!(f, "status {}: {}", status, body),
+            Error::Transport(e) => f.write_str(e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<hyper::Error> for Error {
+    fn from(e: hyper::Error) -> Error {
+        Error::Transport(e.to_string())
+    }
+}
+
+impl From<hyper::http::Error> for Error {
+    fn from(e: hyper::http::Error) -> Error {
+        Error::Transport(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Error {
+        Error::Transport(e.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelFormat {
+    Pdf,
+    Csv,
+}
+
+enum Payload {
+    Empty,
+    Json(Vec<u8>),
+    Form(String),
+}
+
+fn encode(value: &str) -> String {
+    utf8_percent_encode(value, COMPONENT).to_string()
+}
+
+// A path of percent-encoded segments, e.g. a member token given as one.
+fn path(segments: &[&str]) -> String {
+    segments
+        .iter()
+        .map(|segment| format!("/{}", encode(segment)))
+        .collect()
+}
+
+fn draft_path(id: usize, segments: &[&str]) -> String {
+    format!("/api/draft/{}{}", id, path(segments))
+}
+
+fn form(fields: &[(&str, String)]) -> String {
+    fields
+        .iter()
+        .map(|(field, value)| format!("{}={}", field, encode(value)))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+// The body of a successful response, and with `unprocessable` of a 422.
+fn checked(status: StatusCode, body: Bytes, unprocessable: bool) -> Result<Bytes> {
+    if status.is_success() || (unprocessable && status == StatusCode::UNPROCESSABLE_ENTITY) {
+        return Ok(body);
+    }
+    let body = String::from_utf8_lossy(&body).into_owned();
+    Err(Error::Status(status.as_u16(), body))
+}
+
+fn json<T: Serialize + ?Sized>(body: &T) -> Result<Payload> {
+    Ok(Payload::Json(serde_json::to_vec(body)?))
+}
+
+pub struct Client {
+    base_url: String,
+    organizer_token: Option<String>,
+    admin_token: Option<String>,
+    http: hyper::Client<HttpsConnector<HttpConnector>>,
+}
+
+impl Client {
+    // `base_url` without the trailing slash, e.g. `https://wichteln.example`.
+    // Runs on a tokio runtime, like hyper.
+    pub fn new(base_url: impl Into<String>) -> Client {
+        Client {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            organizer_token: None,
+            admin_token: None,
+            http: hyper::Client::builder().build(HttpsConnector::new()),
+        }
+    }
+
+    // Sent as `X-Organizer-Token` with every request, for the organizer
+    // endpoints.
+    pub fn with_organizer_token(mut self, token: impl Into<String>) -> Client {
+        self.organizer_token = Some(token.into());
+        self
+    }
+
+    // Sent as `X-Admin-Token` with every request, for the admin endpoints.
+    pub fn with_admin_token(mut self, token: impl Into<String>) -> Client {
+        self.admin_token = Some(token.into());
+        self
+    }
+
+    fn request(
+        &self,
+        method: Method,
+        path: &str,
+        idempotency_key: Option<&str>,
+        payload: Payload,
+    ) -> Result<Request<Body>> {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(format!("{}{}", self.base_url, path));
+        if let Some(token) = &self.organizer_token {
+            request = request.header("X-Organizer-Token", token.as_str());
+        }
+        if let Some(token) = &self.admin_token {
+            request = request.header("X-Admin-Token", token.as_str());
+        }
+        if let Some(key) = idempotency_key {
+            request = request.header("Idempotency-Key", key);
+        }
+        let body = match payload {
+            Payload::Empty => Body::empty(),
+            Payload::Json(body) => {
+                request = request.header(CONTENT_TYPE, "application/json");
+                Body::from(body)
+            }
+            Payload::Form(body) => {
+                request = request.header(CONTENT_TYPE, "application/x-www-form-urlencoded");
+                Body::from(body)
+            }
+        };
+        Ok(request.body(body)?)
+    }
+
+    async fn exchange(
+        &self,
+        method: Method,
+        path: &str,
+        idempotency_key: Option<&str>,
+        payload: Payload,
+    ) -> Result<(StatusCode, Bytes)> {
+        let request = self.request(method, path, idempotency_key, payload)?;
+        let response = self.http.request(request).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        Ok((status, body))
+    }
+
+    // The body of a successful response.
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        idempotency_key: Option<&str>,
+        payload: Payload,
+    ) -> Result<Bytes> {
+        let (status, body) = self
+            .exchange(method, path, idempotency_key, payload)
+            .await?;
+        checked(status, body, false)
+    }
+
+    async fn read<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        idempotency_key: Option<&str>,
+        payload: Payload,
+    ) -> Result<T> {
+        let body = self.send(method, path, idempotency_key, payload).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.read(Method::GET, path, None, Payload::Empty).await
+    }
+
+    async fn call(&self, method: Method, path: &str, payload: Payload) -> Result<()> {
+        self.send(method, path, None, payload).await?;
+        Ok(())
+    }
+
+    // Drafts
+
+    pub async fn drafts(&self) -> Result<Vec<draft::Draft>> {
+        self.get("/api/draft").await
+    }
+
+    // Every draft with only the given top-level fields, e.g. `title`.
+    pub async fn draft_fields(&self, fields: &[&str]) -> Result<Vec<Map<String, Value>>> {
+        let fields = fields
+            .iter()
+            .map(|field| encode(field))
+            .collect::<Vec<String>>();
+        self.get(&format!("/api/draft?fields={}", fields.join(",")))
+            .await
+    }
+
+    // Creates and, unless it waits for passphrases or invitations, draws a
+    // draft. Answers the token the organizer endpoints take.
+    pub async fn create_draft(
+        &self,
+        draft: &draft::NewDraft,
+        idempotency_key: Option<&str>,
+    ) -> Result<draft::CreatedDraft> {
+        let payload = Payload::Form(form(&draft.form()));
+        self.read(Method::POST, "/api/draft", idempotency_key, payload)
+            .await
+    }
+
+    // Answers a result per definition. Unlike the other endpoints, the
+    // results also come with 422, when none of the drafts was created.
+    pub async fn create_drafts(
+        &self,
+        request: &bulk::BulkRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<Vec<bulk::ItemResult>> {
+        let (status, body) = self
+            .exchange(
+                Method::POST,
+                "/api/draft/bulk",
+                idempotency_key,
+                json(request)?,
+            )
+            .await?;
+        let body = checked(status, body, true)?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    // By id or slug, none if there is no such draft.
+    pub async fn draft(&self, draft: &str) -> Result<Option<draft::Draft>> {
+        self.get(&path(&["api", "draft", draft])).await
+    }
+
+    pub async fn clone_draft(
+        &self,
+        id: usize,
+        request: &draft::CloneRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<draft::CreatedDraft> {
+        let path = draft_path(id, &["clone"]);
+        self.read(Method::POST, &path, idempotency_key, json(request)?)
+            .await
+    }
+
+    // Whether the draw succeeded, none if the draft can't be drawn (anymore).
+    pub async fn draw(&self, id: usize, idempotency_key: Option<&str>) -> Result<Option<bool>> {
+        let path = draft_path(id, &["draw"]);
+        self.read(Method::POST, &path, idempotency_key, Payload::Empty)
+            .await
+    }
+
+    pub async fn swap(
+        &self,
+        id: usize,
+        request: &draft::SwapRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<()> {
+        let path = draft_path(id, &["swap"]);
+        self.send(Method::POST, &path, idempotency_key, json(request)?)
+            .await?;
+        Ok(())
+    }
+
+    // Draws a new recipient for the member, answering the names of the
+    // givers whose tickets changed.
+    pub async fn redraw(
+        &self,
+        id: usize,
+        name: &str,
+        idempotency_key: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let path = draft_path(id, &["ticket", name, "redraw"]);
+        self.read(Method::POST, &path, idempotency_key, Payload::Empty)
+            .await
+    }
+
+    // Keeps the draft for another retention period, answering until when.
+    pub async fn extend(&self, id: usize) -> Result<DateTime<Utc>> {
+        let path = draft_path(id, &["extend"]);
+        self.read(Method::POST, &path, None, Payload::Empty).await
+    }
+
+    // Restores a deleted draft, for its organizer or the admin.
+    pub async fn restore_draft(&self, id: usize) -> Result<()> {
+        self.call(Method::POST, &draft_path(id, &["restore"]), Payload::Empty)
+            .await
+    }
+
+    pub async fn audit(&self, id: usize) -> Result<Vec<audit::Entry>> {
+        self.get(&draft_path(id, &["audit"])).await
+    }
+
+    // The file as downloaded, with the tickets if `assignments`.
+    pub async fn export(
+        &self,
+        id: usize,
+        format: ExportFormat,
+        assignments: bool,
+    ) -> Result<Vec<u8>> {
+        let format = match format {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        };
+        let path = format!(
+            "{}?format={}&assignments={}",
+            draft_path(id, &["export"]),
+            format,
+            assignments
+        );
+        let body = self.send(Method::GET, &path, None, Payload::Empty).await?;
+        Ok(body.to_vec())
+    }
+
+    // The JSON export, with the tickets if `assignments`.
+    pub async fn export_draft(&self, id: usize, assignments: bool) -> Result<draft::Draft> {
+        let body = self.export(id, ExportFormat::Json, assignments).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    // Takes the tickets of a draw made elsewhere, by giver and recipient.
+    // Answers 422 with a `verify::Report` in the body if they don't work.
+    pub async fn import_assignments(
+        &self,
+        id: usize,
+        assignments: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        let path = draft_path(id, &["assignments"]);
+        self.call(Method::POST, &path, json(assignments)?).await
+    }
+
+    pub async fn verify(&self, id: usize) -> Result<verify::Report> {
+        self.get(&draft_path(id, &["verify"])).await
+    }
+
+    // Draws the draft `runs` times without keeping the tickets.
+    pub async fn simulate(&self, id: usize, runs: Option<u32>) -> Result<simulate::Simulation> {
+        let mut path = draft_path(id, &["simulate"]);
+        if let Some(runs) = runs {
+            path = format!("{}?runs={}", path, runs);
+        }
+        self.read(Method::POST, &path, None, Payload::Empty).await
+    }
+
+    // Members of the organizer's earlier drafts whose name contains `query`.
+    pub async fn suggest(&self, query: &str) -> Result<Vec<suggest::Suggestion>> {
+        self.get(&format!("/api/members/suggest?q={}", encode(query)))
+            .await
+    }
+
+    pub async fn templates(&self) -> Result<Vec<preset::Preset>> {
+        self.get("/api/templates").await
+    }
+
+    pub async fn themes(&self) -> Result<Vec<theme::Theme>> {
+        self.get("/api/themes").await
+    }
+
+    pub async fn set_theme(&self, id: usize, request: &theme::ThemeRequest) -> Result<()> {
+        let path = draft_path(id, &["theme"]);
+        self.call(Method::PUT, &path, json(request)?).await
+    }
+
+    pub async fn set_deadline(&self, id: usize, request: &deadline::DeadlineRequest) -> Result<()> {
+        let path = draft_path(id, &["deadline"]);
+        self.call(Method::POST, &path, json(request)?).await
+    }
+
+    pub async fn stats(&self) -> Result<stats::Stats> {
+        self.get("/api/stats").await
+    }
+
+    // Tickets, by the member's token
+
+    // The recipient of the member with the token. Members with an email
+    // address open their ticket by login link only, so it is none for them
+    // once the server sends emails.
+    pub async fn ticket(&self, id: usize, token: &str) -> Result<Option<String>> {
+        self.get(&draft_path(id, &["ticket", token])).await
+    }
+
+    pub async fn locked_ticket(&self, id: usize, token: &str) -> Result<passphrase::LockedTicket> {
+        self.get(&draft_path(id, &["ticket", token, "locked"]))
+            .await
+    }
+
+    // Keys can be replaced until the draw, e.g. after a forgotten passphrase.
+    pub async fn set_keys(&self, id: usize, token: &str, keys: &passphrase::Keys) -> Result<()> {
+        let path = draft_path(id, &["ticket", token, "keys"]);
+        self.call(Method::PUT, &path, json(keys)?).await
+    }
+
+    pub async fn subscribe_push(
+        &self,
+        id: usize,
+        token: &str,
+        subscription: &push::Subscription,
+    ) -> Result<()> {
+        let path = draft_path(id, &["ticket", token, "push"]);
+        self.call(Method::POST, &path, json(subscription)?).await
+    }
+
+    pub async fn confirm_received(&self, id: usize, token: &str) -> Result<()> {
+        let path = draft_path(id, &["ticket", token, "received"]);
+        self.call(Method::POST, &path, Payload::Empty).await
+    }
+
+    pub async fn set_address(
+        &self,
+        id: usize,
+        token: &str,
+        request: &address::AddressRequest,
+    ) -> Result<()> {
+        let path = draft_path(id, &["ticket", token, "address"]);
+        self.call(Method::PUT, &path, json(request)?).await
+    }
+
+    pub async fn set_language(
+        &self,
+        id: usize,
+        token: &str,
+        request: &language::LanguageRequest,
+    ) -> Result<()> {
+        let path = draft_path(id, &["ticket", token, "language"]);
+        self.call(Method::PUT, &path, json(request)?).await
+    }
+
+    pub async fn record_spent(
+        &self,
+        id: usize,
+        token: &str,
+        spent: &spending::Spent,
+    ) -> Result<()> {
+        let path = draft_path(id, &["ticket", token, "spent"]);
+        self.call(Method::PUT, &path, json(spent)?).await
+    }
+
+    pub async fn delete_avatar(&self, id: usize, token: &str) -> Result<()> {
+        let path = draft_path(id, &["ticket", token, "avatar"]);
+        self.call(Method::DELETE, &path, Payload::Empty).await
+    }
+
+    // Asks the organizer for another recipient.
+    pub async fn ask_reassign(
+        &self,
+        id: usize,
+        token: &str,
+        request: &reassign::Ask,
+    ) -> Result<()> {
+        let path = draft_path(id, &["ticket", token, "reassign"]);
+        self.call(Method::POST, &path, json(request)?).await
+    }
+
+    // Answers the invitation with the key from its link.
+    pub async fn respond(&self, id: usize, key: &str, answer: &invite::Answer) -> Result<()> {
+        let path = draft_path(id, &["invite", key]);
+        self.call(Method::POST, &path, json(answer)?).await
+    }
+
+    // Polls and comments
+
+    // Answers the index of the new poll.
+    pub async fn create_poll(&self, id: usize, poll: &poll::NewPoll) -> Result<usize> {
+        let path = draft_path(id, &["poll"]);
+        self.read(Method::POST, &path, None, json(poll)?).await
+    }
+
+    pub async fn polls(&self, id: usize) -> Result<Vec<poll::PollView>> {
+        self.get(&draft_path(id, &["poll"])).await
+    }
+
+    pub async fn vote(&self, id: usize, poll: usize, token: &str, vote: &poll::Vote) -> Result<()> {
+        let path = draft_path(id, &["poll", &poll.to_string(), "vote", token]);
+        self.call(Method::POST, &path, json(vote)?).await
+    }
+
+    // Answers the id of the new comment.
+    pub async fn comment(&self, id: usize, comment: &comment::NewComment) -> Result<usize> {
+        let path = draft_path(id, &["comment"]);
+        self.read(Method::POST, &path, None, json(comment)?).await
+    }
+
+    pub async fn delete_comment(&self, id: usize, comment: usize) -> Result<()> {
+        let path = draft_path(id, &["comment", &comment.to_string()]);
+        self.call(Method::DELETE, &path, Payload::Empty).await
+    }
+
+    // The organizer's settings
+
+    pub async fn avoided(&self, id: usize) -> Result<avoid::Overview> {
+        self.get(&draft_path(id, &["avoid"])).await
+    }
+
+    pub async fn set_avoided(&self, id: usize, avoided: &[avoid::Avoid]) -> Result<()> {
+        let path = draft_path(id, &["avoid"]);
+        self.call(Method::PUT, &path, json(avoided)?).await
+    }
+
+    pub async fn save_emails(&self, id: usize, templates: &mail::Templates) -> Result<()> {
+        let path = draft_path(id, &["emails"]);
+        self.call(Method::PUT, &path, json(templates)?).await
+    }
+
+    // The emails of the member by name, as they would be sent.
+    pub async fn preview_emails(&self, id: usize, name: &str) -> Result<mail::Emails> {
+        self.get(&draft_path(id, &["emails", name])).await
+    }
+
+    pub async fn preview_notifications(&self, id: usize) -> Result<Vec<preview::Preview>> {
+        self.get(&draft_path(id, &["notifications", "preview"]))
+            .await
+    }
+
+    // The deliveries of every member by name, the latest first.
+    pub async fn deliveries(&self, id: usize) -> Result<BTreeMap<String, Vec<delivery::Delivery>>> {
+        self.get(&draft_path(id, &["deliveries"])).await
+    }
+
+    // Sends the member their ticket again.
+    pub async fn retry_delivery(&self, id: usize, name: &str) -> Result<()> {
+        let path = draft_path(id, &["deliveries", name, "retry"]);
+        self.call(Method::POST, &path, Payload::Empty).await
+    }
+
+    pub async fn enable_spending(&self, id: usize, setting: &spending::Setting) -> Result<()> {
+        let path = draft_path(id, &["spending"]);
+        self.call(Method::PUT, &path, json(setting)?).await
+    }
+
+    pub async fn spending(&self, id: usize) -> Result<spending::Summary> {
+        self.get(&draft_path(id, &["spending"])).await
+    }
+
+    pub async fn reassign_requests(&self, id: usize) -> Result<Vec<reassign::View>> {
+        self.get(&draft_path(id, &["reassign"])).await
+    }
+
+    pub async fn approve_reassign(&self, id: usize, request: u32) -> Result<()> {
+        let path = draft_path(id, &["reassign", &request.to_string(), "approve"]);
+        self.call(Method::POST, &path, Payload::Empty).await
+    }
+
+    pub async fn deny_reassign(&self, id: usize, request: u32) -> Result<()> {
+        let path = draft_path(id, &["reassign", &request.to_string(), "deny"]);
+        self.call(Method::POST, &path, Payload::Empty).await
+    }
+
+    pub async fn share(&self, id: usize) -> Result<share::Shared> {
+        let path = draft_path(id, &["share"]);
+        self.read(Method::POST, &path, None, Payload::Empty).await
+    }
+
+    pub async fn unshare(&self, id: usize) -> Result<()> {
+        self.call(Method::DELETE, &draft_path(id, &["share"]), Payload::Empty)
+            .await
+    }
+
+    // Address labels of a mailed exchange, as downloaded.
+    pub async fn labels(&self, id: usize, format: LabelFormat) -> Result<Vec<u8>> {
+        let format = match format {
+            LabelFormat::Pdf => "pdf",
+            LabelFormat::Csv => "csv",
+        };
+        let path = format!("{}?format={}", draft_path(id, &["labels"]), format);
+        let body = self.send(Method::GET, &path, None, Payload::Empty).await?;
+        Ok(body.to_vec())
+    }
+
+    // Reveals who gave to whom to everyone, once the draft is archived.
+    pub async fn reveal(&self, id: usize) -> Result<Vec<archive::Pair>> {
+        let path = draft_path(id, &["reveal"]);
+        self.read(Method::POST, &path, None, Payload::Empty).await
+    }
+
+    pub async fn assignments(&self, id: usize) -> Result<Vec<archive::Pair>> {
+        self.get(&draft_path(id, &["assignments"])).await
+    }
+
+    // Makes the draft the first edition of a new series.
+    pub async fn start_series(&self, id: usize, request: &series::SeriesRequest) -> Result<()> {
+        let path = draft_path(id, &["series"]);
+        self.call(Method::POST, &path, json(request)?).await
+    }
+
+    pub async fn series_stats(&self, series: &str) -> Result<series::Stats> {
+        self.get(&path(&["api", "series", series, "stats"])).await
+    }
+
+    // Organizers
+
+    pub async fn organizers(&self, id: usize) -> Result<Vec<organizers::View>> {
+        self.get(&draft_path(id, &["organizers"])).await
+    }
+
+    // Invites a co-organizer by email, or with `transfer` the new owner.
+    pub async fn invite(
+        &self,
+        id: usize,
+        invite: &organizers::Invite,
+    ) -> Result<organizers::Invited> {
+        let path = draft_path(id, &["organizers"]);
+        self.read(Method::POST, &path, None, json(invite)?).await
+    }
+
+    pub async fn remove_organizer(&self, id: usize, organizer: u32) -> Result<()> {
+        let path = draft_path(id, &["organizers", &organizer.to_string()]);
+        self.call(Method::DELETE, &path, Payload::Empty).await
+    }
+
+    // Accepts an invitation with the code from its link.
+    pub async fn accept(&self, id: usize, code: &str) -> Result<organizers::Accepted> {
+        let path = draft_path(id, &["organizers", code]);
+        self.read(Method::POST, &path, None, Payload::Empty).await
+    }
+
+    // The admin's endpoints. Backups are in the server's own format, which
+    // only `restore` needs to understand.
+
+    pub async fn delete_draft(&self, id: usize) -> Result<()> {
+        self.call(Method::DELETE, &draft_path(id, &[]), Payload::Empty)
+            .await
+    }
+
+    pub async fn admin_drafts(&self) -> Result<Vec<admin::DraftRow>> {
+        self.get("/api/admin/drafts").await
+    }
+
+    pub async fn storage(&self) -> Result<admin::Storage> {
+        self.get("/api/admin/storage").await
+    }
+
+    pub async fn cleanup(&self) -> Result<admin::Cleanup> {
+        self.read(Method::POST, "/api/admin/cleanup", None, Payload::Empty)
+            .await
+    }
+
+    // The active drafts.
+    pub async fn backup(&self) -> Result<Option<Value>> {
+        self.get("/api/backup").await
+    }
+
+    // Every draft, the trash included.
+    pub async fn export_all(&self) -> Result<Value> {
+        self.get("/api/admin/export").await
+    }
+
+    // Replaces every draft with those of the backup, answering how many
+    // are active.
+    pub async fn restore(&self, backup: &Value) -> Result<usize> {
+        self.read(Method::POST, "/api/restore", None, json(backup)?)
+            .await
+    }
+
+    pub async fn outbox(&self) -> Result<Vec<outbox::Entry>> {
+        self.get("/api/admin/outbox").await
+    }
+
+    pub async fn retry_job(&self, job: u64) -> Result<()> {
+        let path = path(&["api", "admin", "outbox", &job.to_string(), "retry"]);
+        self.call(Method::POST, &path, Payload::Empty).await
+    }
+
+    pub async fn discard_job(&self, job: u64) -> Result<()> {
+        let path = path(&["api", "admin", "outbox", &job.to_string()]);
+        self.call(Method::DELETE, &path, Payload::Empty).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{audit, spending, verify};
+
+    // The method, target, headers and body of a request as sent.
+    fn sent(request: Request<Body>) -> (String, String, Vec<(String, String)>, String) {
+        let (parts, body) = request.into_parts();
+        let mut runtime = tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .build()
+            .expect("runtime");
+        let body = runtime.block_on(hyper::body::to_bytes(body)).expect("body");
+        let headers = parts
+            .headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_str().unwrap().to_string()))
+            .collect();
+        (
+            parts.method.to_string(),
+            parts.uri.to_string(),
+            headers,
+            String::from_utf8(body.to_vec()).unwrap(),
+        )
+    }
+
+    fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn encodes_path_segments() {
+        assert_eq!(
+            draft_path(3, &["ticket", "a/b c?ä", "locked"]),
+            "/api/draft/3/ticket/a%2Fb%20c%3F%C3%A4/locked"
+        );
+        assert_eq!(draft_path(3, &[]), "/api/draft/3");
+        assert_eq!(path(&["api", "series", "x-1_a.b~"]), "/api/series/x-1_a.b~");
+        assert_eq!(encode("Anna & Bo"), "Anna%20%26%20Bo");
+    }
+
+    #[test]
+    fn sends_the_tokens_and_json() {
+        let client = Client::new("http://localhost:8000/")
+            .with_organizer_token("organizer")
+            .with_admin_token("admin");
+        let swap = draft::SwapRequest {
+            first: "Anna".to_string(),
+            second: "Bo".to_string(),
+        };
+        let request = client
+            .request(
+                Method::POST,
+                &draft_path(4, &["swap"]),
+                Some("key-1"),
+                json(&swap).unwrap(),
+            )
+            .unwrap();
+        let (method, uri, headers, body) = sent(request);
+        assert_eq!(method, "POST");
+        assert_eq!(uri, "http://localhost:8000/api/draft/4/swap");
+        assert_eq!(header(&headers, "x-organizer-token"), Some("organizer"));
+        assert_eq!(header(&headers, "x-admin-token"), Some("admin"));
+        assert_eq!(header(&headers, "idempotency-key"), Some("key-1"));
+        assert_eq!(header(&headers, "content-type"), Some("application/json"));
+        let body: draft::SwapRequest = serde_json::from_str(&body).unwrap();
+        assert_eq!(body, swap);
+    }
+
+    #[test]
+    fn sends_a_new_draft_as_a_form() {
+        let client = Client::new("http://localhost:8000");
+        let mut draft = draft::NewDraft::new("Büro & Co", "2021-12-24", chrono_tz::Europe::Berlin);
+        draft.members.push(draft::Member::new("Anna", 1));
+        draft.members.push(draft::Member::new("Bo", 2));
+        draft.passphrases = true;
+        let payload = Payload::Form(form(&draft.form()));
+        let request = client
+            .request(Method::POST, "/api/draft", None, payload)
+            .unwrap();
+        let (_, _, headers, body) = sent(request);
+        assert_eq!(header(&headers, "x-organizer-token"), None);
+        assert_eq!(header(&headers, "idempotency-key"), None);
+        assert_eq!(
+            header(&headers, "content-type"),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(
+            body,
+            "title=B%C3%BCro%20%26%20Co&date=2021-12-24&timezone=Europe%2FBerlin\
+             &name=Anna&team=1&name=Bo&team=2&passphrases=on"
+        );
+    }
+
+    #[test]
+    fn reports_the_status() {
+        let body = Bytes::from_static(b"not yours");
+        match checked(StatusCode::FORBIDDEN, body.clone(), false) {
+            Err(Error::Status(403, body)) => assert_eq!(body, "not yours"),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(checked(StatusCode::UNPROCESSABLE_ENTITY, body.clone(), false).is_err());
+        // The results of a bulk request that created nothing.
+        assert!(checked(StatusCode::UNPROCESSABLE_ENTITY, body.clone(), true).is_ok());
+        assert!(checked(StatusCode::NO_CONTENT, body, false).is_ok());
+    }
+
+    fn round_trip<T>(value: &T)
+    where
+        T: Serialize + DeserializeOwned + PartialEq + fmt::Debug,
+    {
+        let json = serde_json::to_string(value).unwrap();
+        assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value);
+    }
+
+    #[test]
+    fn round_trips_the_types() {
+        let mut member = draft::Member::new("Anna", 1);
+        member.pool = Some("Kids".to_string());
+        round_trip(&draft::Draft {
+            title: "Büro".to_string(),
+            slug: Some("buero".to_string()),
+            mode: crate::elephant::Mode::SecretSanta,
+            pools: Vec::new(),
+            budget: Some(20),
+            team_budgets: BTreeMap::new(),
+            passphrases: false,
+            blind: true,
+            theme: None,
+            revealed: false,
+            draw_at: None,
+            date: "2021-12-24T18:00:00+01:00".parse().unwrap(),
+            timezone: chrono_tz::Europe::Berlin,
+            members: vec![member],
+            created: Utc::now(),
+            drawn: true,
+        });
+        round_trip(&verify::Report::new(vec![verify::Violation::SameTeam {
+            giver: "Anna".to_string(),
+            recipient: "Bo".to_string(),
+            team: 2,
+        }]));
+        round_trip(&audit::Entry::new(
+            audit::Actor::Participant("Anna".to_string()),
+            audit::Action::Swapped {
+                first: "Anna".to_string(),
+                second: "Bo".to_string(),
+            },
+        ));
+        round_trip(&spending::Summary {
+            members: 4,
+            recorded: 1,
+            figures: None,
+        });
+        round_trip(&bulk::BulkRequest {
+            mode: bulk::Mode::BestEffort,
+            drafts: vec![bulk::Definition {
+                title: "Familie".to_string(),
+                date: "2021-12-24".to_string(),
+                timezone: None,
+                mode: Default::default(),
+                pools: Vec::new(),
+                budget: None,
+                team_budgets: BTreeMap::new(),
+                emails: Default::default(),
+                passphrases: false,
+                blind: false,
+                invite: true,
+                theme: Some("winter".to_string()),
+                members: vec![bulk::MemberDefinition {
+                    name: "Anna".to_string(),
+                    team: 1,
+                    email: None,
+                    pool: None,
+                    language: Some(language::Language::En),
+                }],
+            }],
+        });
+    }
+
+    #[test]
+    fn reads_the_bulk_results() {
+        let results: Vec<bulk::ItemResult> =
+            serde_json::from_str(r#"[{"index":0,"error":"no members"},{"index":1}]"#).unwrap();
+        assert_eq!(results[0].error.as_deref(), Some("no members"));
+        assert_eq!(
+            results[1],
+            bulk::ItemResult {
+                index: 1,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct NewComment {
+    pub name: String,
+    pub message: String,
+}
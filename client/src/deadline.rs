@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct DeadlineRequest {
+    // `YYYY-MM-DDTHH:MM` in the draft's timezone, none to remove it.
+    pub deadline: Option<String>,
+}
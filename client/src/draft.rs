@@ -0,0 +1,154 @@
+use crate::elephant;
+use crate::pool::Pool;
+use chrono::{DateTime, FixedOffset, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Member {
+    pub name: String,
+    pub team: u32,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<String>,
+    // Only set in an export of the assignments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ticket: Option<String>,
+}
+
+impl Member {
+    pub fn new(name: impl Into<String>, team: u32) -> Member {
+        Member {
+            name: name.into(),
+            team,
+            email: None,
+            pool: None,
+            ticket: None,
+        }
+    }
+}
+
+// A draft as anyone may see it, without tokens and tickets. The members
+// are ordered by name.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Draft {
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    #[serde(default)]
+    pub mode: elephant::Mode,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pools: Vec<Pool>,
+    // Spending limits in whole euros, for everyone and for single teams.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<u32>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub team_budgets: BTreeMap<u32, u32>,
+    #[serde(default)]
+    pub passphrases: bool,
+    #[serde(default)]
+    pub blind: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub revealed: bool,
+    // Members can add themselves until then, then the draft is drawn.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draw_at: Option<DateTime<Utc>>,
+    pub date: DateTime<FixedOffset>,
+    pub timezone: Tz,
+    pub members: Vec<Member>,
+    pub created: DateTime<Utc>,
+    // Not part of an export, whose tickets tell instead.
+    #[serde(default)]
+    pub drawn: bool,
+}
+
+// What `POST /api/draft` takes, sent like the form of the start page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewDraft {
+    pub title: String,
+    // `2021-12-24` or `2021-12-24T18:00`, in `timezone`.
+    pub date: String,
+    pub timezone: Tz,
+    pub members: Vec<Member>,
+    pub budget: Option<u32>,
+    pub passphrases: bool,
+    pub blind: bool,
+    // Invites the members by email before drawing.
+    pub invite: bool,
+}
+
+impl NewDraft {
+    pub fn new(title: impl Into<String>, date: impl Into<String>, timezone: Tz) -> NewDraft {
+        NewDraft {
+            title: title.into(),
+            date: date.into(),
+            timezone,
+            members: Vec::new(),
+            budget: None,
+            passphrases: false,
+            blind: false,
+            invite: false,
+        }
+    }
+
+    // The fields of the form, in the order the server reads them.
+    pub fn form(&self) -> Vec<(&'static str, String)> {
+        let mut form = vec![
+            ("title", self.title.clone()),
+            ("date", self.date.clone()),
+            ("timezone", self.timezone.name().to_string()),
+        ];
+        // The team, pool and email belong to the name before them.
+        for member in &self.members {
+            form.push(("name", member.name.clone()));
+            form.push(("team", member.team.to_string()));
+            if let Some(pool) = &member.pool {
+                form.push(("pool", pool.clone()));
+            }
+            if let Some(email) = &member.email {
+                form.push(("email", email.clone()));
+            }
+        }
+        if let Some(budget) = self.budget {
+            form.push(("budget", budget.to_string()));
+        }
+        for (field, on) in &[
+            ("passphrases", self.passphrases),
+            ("blind", self.blind),
+            ("invite", self.invite),
+        ] {
+            if *on {
+                form.push((*field, "on".to_string()));
+            }
+        }
+        form
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CreatedDraft {
+    pub id: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    pub organizer_token: String,
+}
+
+// A new draft with the same members on another date, drawn anew.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CloneRequest {
+    pub date: String,
+    // The timezone of the cloned draft if none.
+    #[serde(default)]
+    pub timezone: Option<Tz>,
+}
+
+// The givers whose tickets are exchanged, by name.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SwapRequest {
+    pub first: String,
+    pub second: String,
+}
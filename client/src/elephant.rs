@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    // Every member draws a recipient.
+    #[default]
+    SecretSanta,
+    // Members bring a gift each and take turns picking or stealing one.
+    WhiteElephant,
+}
+
+impl Mode {
+    pub fn from_form(value: &str) -> Option<Mode> {
+        match value {
+            "secret_santa" => Some(Mode::SecretSanta),
+            "white_elephant" => Some(Mode::WhiteElephant),
+            _ => None,
+        }
+    }
+}
+
+// Steal rules of a white elephant game, shown along with the turn order.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Rules {
+    // How often a gift can be stolen before it is safe.
+    pub max_steals: u32,
+    // Whether a gift can be stolen right back from whoever just stole it.
+    pub steal_back: bool,
+    // Whether the first player may swap with anyone after the last turn,
+    // making up for having had nothing to steal.
+    pub final_swap: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            max_steals: 3,
+            steal_back: false,
+            final_swap: true,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+// A member's answer to the invitation, with what they wish for.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct Answer {
+    pub accept: bool,
+    pub wishlist: Option<String>,
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+// The languages of the pages and messages.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    De,
+    En,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct LanguageRequest {
+    // A language code like `de` or `en`, none for the organizer's.
+    pub language: Option<String>,
+}
@@ -0,0 +1,43 @@
+// The JSON API under `/api`. The modules hold the types the server takes
+// and answers, named after the server modules they belong to; the server
+// uses them itself, so the two can't drift apart. With the `client`
+// feature, on by default, `Client` calls every endpoint asynchronously,
+// for bots and command line tools.
+pub mod address;
+pub mod admin;
+pub mod archive;
+pub mod audit;
+pub mod avoid;
+pub mod bulk;
+pub mod comment;
+pub mod deadline;
+pub mod delivery;
+pub mod draft;
+pub mod elephant;
+pub mod invite;
+pub mod language;
+pub mod mail;
+pub mod organizers;
+pub mod outbox;
+pub mod passphrase;
+pub mod poll;
+pub mod pool;
+pub mod preset;
+pub mod preview;
+pub mod push;
+pub mod reassign;
+pub mod series;
+pub mod share;
+pub mod simulate;
+pub mod spending;
+pub mod stats;
+pub mod suggest;
+pub mod theme;
+pub mod verify;
+
+#[cfg(feature = "client")]
+mod client;
+
+#[cfg(feature = "client")]
+pub use client::{Client, Error, ExportFormat, LabelFormat, Result};
+pub use draft::{CreatedDraft, Draft, Member, NewDraft};
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Message {
+    pub subject: String,
+    pub body: String,
+}
+
+// The organizer's own wording of the notification emails, written as Tera
+// templates with the variables `giver`, `ticket_url` and `budget`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct Templates {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ticket: Option<Message>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminder: Option<Message>,
+}
+
+impl Templates {
+    pub fn is_empty(&self) -> bool {
+        self.ticket.is_none() && self.reminder.is_none()
+    }
+}
+
+// The emails of one member as they would be sent.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Emails {
+    pub ticket: Message,
+    pub reminder: Message,
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    // Everything the owner can do, except managing the organizers.
+    CoOrganizer,
+    // Takes over the draft, the previous owner loses access.
+    Owner,
+}
+
+// An organizer or invitation as the organizers see it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct View {
+    pub id: u32,
+    pub email: String,
+    pub role: Role,
+    pub pending: bool,
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Invited {
+    pub id: u32,
+    // For passing it on when no email could be sent.
+    pub url: String,
+    pub sent: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct Invite {
+    pub email: String,
+    // Transfers the ownership instead of adding a co-organizer.
+    #[serde(default)]
+    pub transfer: bool,
+}
+
+impl Invite {
+    pub fn role(&self) -> Role {
+        if self.transfer {
+            Role::Owner
+        } else {
+            Role::CoOrganizer
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Accepted {
+    pub organizer_token: String,
+}
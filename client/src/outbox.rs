@@ -0,0 +1,21 @@
+use crate::delivery::Channel;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// A job as listed to the admin, without the message, whose links would
+// open the tickets.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub id: u64,
+    pub channel: Channel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    pub attempts: u32,
+    pub created: DateTime<Utc>,
+    pub due: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub dead: bool,
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+// The P-256 key pair a member's browser generated in the zero-knowledge
+// mode. The private key is encrypted with a key derived from the member's
+// passphrase (PBKDF2, AES-GCM) and never leaves the browser in plain.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Keys {
+    // Uncompressed SEC1 point, base64 like the fields below.
+    pub public_key: String,
+    pub wrapped_key: String,
+    pub salt: String,
+    pub iv: String,
+}
+
+// A ticket encrypted for the public key of its giver: AES-GCM with the
+// SHA-256 hash of an ECDH secret between the giver's key and a key only
+// used for this ticket.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Locked {
+    pub ephemeral_key: String,
+    pub iv: String,
+    pub ciphertext: String,
+}
+
+// Everything a browser needs to open the ticket with the passphrase.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct LockedTicket {
+    pub keys: Keys,
+    pub ticket: Locked,
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OptionView {
+    pub text: String,
+    pub votes: usize,
+    pub chosen: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PollView {
+    pub index: usize,
+    pub question: String,
+    pub options: Vec<OptionView>,
+    pub votes: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NewPoll {
+    pub question: String,
+    pub options: Vec<String>,
+}
+
+// The index of the option voted for.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct Vote {
+    pub option: usize,
+}
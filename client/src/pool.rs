@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+// A group of members within a draft, e.g. the kids, drawn among
+// themselves. Members without a pool form the draft's main pool.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Pool {
+    pub name: String,
+    // Spending limit in whole euros.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<u32>,
+}
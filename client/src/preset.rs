@@ -0,0 +1,28 @@
+use crate::elephant;
+use crate::pool::Pool;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// One row of the member list, with the name left for the organizer.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Row {
+    pub team: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<String>,
+}
+
+// A common setup to start a draft from. Members of the same team don't
+// draw each other, so the teams carry the exclusions, e.g. couples.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Preset {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub mode: elephant::Mode,
+    pub members: Vec<Row>,
+    pub pools: Vec<Pool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<u32>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub team_budgets: BTreeMap<u32, u32>,
+}
@@ -0,0 +1,17 @@
+use crate::delivery::{Channel, Kind};
+use serde::{Deserialize, Serialize};
+
+// A notification as it would go out, without sending it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Preview {
+    pub name: String,
+    pub channel: Channel,
+    pub kind: Kind,
+    // The email address, none for push notifications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    pub subject: String,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment: Option<String>,
+}
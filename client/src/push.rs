@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+// As serialized by `PushSubscription.toJSON()` in the browser.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Subscription {
+    pub endpoint: String,
+    pub keys: Keys,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Keys {
+    pub p256dh: String,
+    pub auth: String,
+}
@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// A pending request as the organizer sees it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct View {
+    pub id: u32,
+    pub reason: String,
+    pub created: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct Ask {
+    pub reason: String,
+}
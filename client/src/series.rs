@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct SeriesRequest {
+    pub name: String,
+    // Months between two editions, a year if unset.
+    pub interval: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PairCount {
+    pub giver: String,
+    pub recipient: String,
+    pub count: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NeverDrawn {
+    pub giver: String,
+    pub recipients: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub editions: u32,
+    // How often each pair was drawn, the most frequent first.
+    pub pairs: Vec<PairCount>,
+    // The members of the latest edition each giver could draw but never
+    // did.
+    pub never: Vec<NeverDrawn>,
+    // Share of the givers' draws that went to someone they hadn't had
+    // before, as far as that was possible: 1 if nobody got a recipient a
+    // second time while others were left.
+    pub fairness: f64,
+}
@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+// The read-only link to the draft.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Shared {
+    pub url: String,
+}
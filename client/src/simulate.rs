@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+// A constraint that got in the way of the draws, e.g. a team whose
+// members were left without a recipient.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Blocker {
+    // `pool`: the pool can't be drawn at all, one team is too large.
+    // `team`: draws ended in a dead end at a member of the team.
+    // `history`: draws gave a member someone from a past edition.
+    pub constraint: String,
+    pub subject: String,
+    pub count: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Simulation {
+    pub runs: u32,
+    pub succeeded: u32,
+    pub success_rate: f64,
+    // Dead ends per run before a draw of every pool was found.
+    pub average_retries: f64,
+    pub blocked_by: Vec<Blocker>,
+}
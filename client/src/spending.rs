@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Figures {
+    pub total: u32,
+    pub average: u32,
+    pub median: u32,
+    pub lowest: u32,
+    pub highest: u32,
+    // Gifts above their budget, and below half of it.
+    pub over_budget: usize,
+    pub under_budget: usize,
+}
+
+// What the members spent, without saying who spent what. The figures are
+// left out while too few recorded an amount.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub members: usize,
+    pub recorded: usize,
+    #[serde(flatten)]
+    pub figures: Option<Figures>,
+}
+
+// What the member spent on their gift, in whole euros.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct Spent {
+    pub amount: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct Setting {
+    pub enabled: bool,
+}
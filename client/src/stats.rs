@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub drafts: usize,
+    pub members: usize,
+    pub drawn: usize,
+    pub open: usize,
+    pub upcoming_this_month: usize,
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+// A look for the pages of a draft.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub id: String,
+    // Christmas imagery, as opposed to a look fitting any season.
+    pub festive: bool,
+    pub primary: String,
+    pub light: String,
+    // Behind the pages, over the banner image if there is one.
+    pub background: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banner: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "rocket", derive(rocket::FromForm))]
+pub struct ThemeRequest {
+    // None for the default look.
+    pub theme: Option<String>,
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+// Something wrong with the tickets of a drawn draft.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Violation {
+    NoRecipient {
+        giver: String,
+    },
+    // Only from imported assignments, see `external`.
+    UnknownGiver {
+        giver: String,
+    },
+    UnknownRecipient {
+        giver: String,
+        recipient: String,
+    },
+    SelfGift {
+        giver: String,
+    },
+    SameTeam {
+        giver: String,
+        recipient: String,
+        team: u32,
+    },
+    OtherPool {
+        giver: String,
+        recipient: String,
+    },
+    NoGiver {
+        name: String,
+    },
+    SeveralGivers {
+        name: String,
+        givers: usize,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Report {
+    pub valid: bool,
+    pub violations: Vec<Violation>,
+}
+
+impl Report {
+    pub fn new(violations: Vec<Violation>) -> Report {
+        Report {
+            valid: violations.is_empty(),
+            violations,
+        }
+    }
+}
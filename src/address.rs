@@ -0,0 +1,225 @@
+use crate::i18n::Locale;
+use crate::{crypto, elephant, export, sanitize, Draft, Drafts, Member, Organizer};
+use printpdf::{BuiltinFont, Line, Mm, PdfDocument, Point};
+use rocket::http::{ContentType, Status};
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use std::collections::HashMap;
+use std::io::BufWriter;
+
+pub use api::address::AddressRequest;
+
+const MAX_ADDRESS: usize = 300;
+const MAX_LINES: usize = 6;
+
+// A4 sheets of 3 × 8 labels of 70 × 37 mm, the common size without
+// margins.
+const PAGE_WIDTH: f64 = 210.0;
+const PAGE_HEIGHT: f64 = 297.0;
+const LABEL_WIDTH: f64 = 70.0;
+const LABEL_HEIGHT: f64 = 37.125;
+const COLUMNS: usize = 3;
+const ROWS: usize = 8;
+
+// The shipping address of the member, stored sealed like the tickets.
+pub fn of(member: &Member) -> Option<String> {
+    member.address.as_deref().and_then(crypto::open)
+}
+
+// The parts of the ticket page for addresses: the member's own on their
+// link, and the one of their recipient.
+pub fn context(draft: &Draft, key: &str, context: &mut HashMap<&str, String>) {
+    let member = match draft.member_by_token(key) {
+        Some(member) => member,
+        None => return,
+    };
+    if !draft.is_archived() {
+        context.insert("address_key", key.to_string());
+        context.insert("address", of(member).unwrap_or_default());
+    }
+    let recipient = member
+        .recipient()
+        .and_then(|name| draft.find_member(&name))
+        .and_then(of);
+    if let Some(address) = recipient {
+        context.insert("recipient_address", address);
+    }
+}
+
+// Sets the address of the member with the token, none to remove it.
+fn set(id: usize, key: &str, address: Option<&str>, drafts: &Drafts) -> Result<(), Status> {
+    let address = address
+        .map(sanitize::text)
+        .filter(|address| !address.is_empty());
+    if let Some(address) = &address {
+        if address.chars().count() > MAX_ADDRESS || address.lines().count() > MAX_LINES {
+            return Err(Status::UnprocessableEntity);
+        }
+    }
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    let member = draft
+        .member_by_token(key)
+        .cloned()
+        .ok_or(Status::NotFound)?;
+    if draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    let mut member = draft.members.take(&member).ok_or(Status::NotFound)?;
+    member.address = address.map(|address| crypto::seal(&address));
+    draft.members.insert(member);
+    Ok(())
+}
+
+#[put(
+    "/api/draft/<id>/ticket/<key>/address",
+    format = "json",
+    data = "<request>"
+)]
+pub fn api_set(
+    id: usize,
+    key: String,
+    request: Json<AddressRequest>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    set(id, &key, request.address.as_deref(), &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/ticket/<key>/address", data = "<request>")]
+pub fn set_address(
+    id: usize,
+    key: String,
+    request: Form<AddressRequest>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    set(id, &key, request.address.as_deref(), &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}/ticket/{}", id, key)))
+}
+
+// One label per giver: whom the parcel goes to and where, with the giver's
+// name apart so the organizer knows whom to hand it to.
+struct Label {
+    giver: String,
+    recipient: String,
+    address: Option<String>,
+}
+
+fn labels(draft: &Draft) -> Vec<Label> {
+    let mut labels = draft
+        .members
+        .iter()
+        .filter_map(|giver| {
+            let recipient = draft.find_member(&giver.recipient()?)?;
+            Some(Label {
+                giver: giver.name.clone(),
+                recipient: recipient.name.clone(),
+                address: of(recipient),
+            })
+        })
+        .collect::<Vec<Label>>();
+    labels.sort_by(|a, b| a.giver.cmp(&b.giver));
+    labels
+}
+
+fn csv(labels: &[Label]) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&["giver", "recipient", "address"])?;
+    for label in labels {
+        writer.write_record(&[
+            label.giver.as_str(),
+            label.recipient.as_str(),
+            label.address.as_deref().unwrap_or(""),
+        ])?;
+    }
+    match writer.into_inner() {
+        Ok(body) => Ok(body),
+        Err(e) => Err(e.into_error().into()),
+    }
+}
+
+// Labels without an address are left out, the CSV lists them.
+fn pdf(draft: &Draft, labels: &[Label], locale: Locale) -> Result<Vec<u8>, printpdf::Error> {
+    let kind = locale.text("label_kind");
+    let (doc, first_page, first_layer) =
+        PdfDocument::new(draft.title.as_str(), Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), kind);
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+    let mut layer = doc.get_page(first_page).get_layer(first_layer);
+    let labels = labels
+        .iter()
+        .filter_map(|label| Some((label, label.address.as_ref()?)));
+    for (index, (label, address)) in labels.enumerate() {
+        let slot = index % (COLUMNS * ROWS);
+        if index > 0 && slot == 0 {
+            let (page, next) = doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), kind);
+            layer = doc.get_page(page).get_layer(next);
+        }
+        let x = (slot % COLUMNS) as f64 * LABEL_WIDTH + 5.0;
+        let top = PAGE_HEIGHT - (slot / COLUMNS) as f64 * LABEL_HEIGHT;
+
+        layer.use_text(
+            locale.text("label_giver").replace("{name}", &label.giver),
+            7,
+            Mm(x),
+            Mm(top - 5.0),
+            &font,
+        );
+        layer.add_shape(Line {
+            points: vec![
+                (Point::new(Mm(x), Mm(top - 7.0)), false),
+                (Point::new(Mm(x + LABEL_WIDTH - 10.0), Mm(top - 7.0)), false),
+            ],
+            is_closed: false,
+            has_fill: false,
+            has_stroke: true,
+            is_clipping_path: false,
+        });
+        layer.use_text(label.recipient.as_str(), 11, Mm(x), Mm(top - 13.0), &bold);
+        for (line, text) in address.lines().take(MAX_LINES).enumerate() {
+            layer.use_text(text, 9, Mm(x), Mm(top - 17.5 - line as f64 * 3.8), &font);
+        }
+    }
+
+    let mut buffer = BufWriter::new(Vec::new());
+    doc.save(&mut buffer)?;
+    buffer
+        .into_inner()
+        .map_err(|e| printpdf::Error::from(e.into_error()))
+}
+
+// Address labels for mailed exchanges. They show who gives to whom, so
+// like the cards only the organizer gets them.
+#[get("/api/draft/<id>/labels?<format>")]
+pub fn api_labels(
+    id: usize,
+    format: Option<String>,
+    locale: Locale,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<export::Download, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) || draft.blind {
+        return Err(Status::Forbidden);
+    }
+    if !draft.is_drawn() || draft.passphrases || draft.mode == elephant::Mode::WhiteElephant {
+        return Err(Status::Conflict);
+    }
+    let labels = labels(&draft);
+    match format.as_deref().unwrap_or("pdf") {
+        "pdf" => Ok(export::Download {
+            filename: export::filename(&draft, "labels.pdf"),
+            content_type: ContentType::PDF,
+            body: pdf(&draft, &labels, locale).map_err(|_| Status::InternalServerError)?,
+        }),
+        "csv" => Ok(export::Download {
+            filename: export::filename(&draft, "labels.csv"),
+            content_type: ContentType::CSV,
+            body: csv(&labels).map_err(|_| Status::InternalServerError)?,
+        }),
+        _ => Err(Status::BadRequest),
+    }
+}
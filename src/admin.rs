@@ -0,0 +1,265 @@
+use crate::config::AppConfig;
+use crate::i18n::{self, Locale};
+use crate::{audit, backup, health, outbox, retention, series, Admin, Drafts, ADMIN_COOKIE};
+use chrono::Duration;
+use rocket::http::{Cookie, Cookies, Status};
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use rocket_contrib::templates::Template;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+pub use api::admin::{Cleanup, DraftRow, Storage};
+
+#[derive(FromForm)]
+pub struct Login {
+    token: String,
+}
+
+#[derive(Serialize)]
+struct Dashboard {
+    drafts: Vec<DraftRow>,
+    trash: Vec<DraftRow>,
+    members: usize,
+    drawn: usize,
+    size: String,
+    data_file: Option<String>,
+    data_file_size: Option<String>,
+    // Notifications given up, to send again or discard.
+    outbox: Vec<outbox::Entry>,
+    pending: usize,
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+// All drafts including the trash, the oldest first, and their size in
+// the data file.
+fn rows(config: &AppConfig, drafts: &Drafts) -> Vec<DraftRow> {
+    let mut rows = drafts
+        .all()
+        .iter()
+        .map(|(id, entry)| {
+            let draft = entry.read();
+            let bytes = serde_json::to_vec(&*draft).map_or(0, |json| json.len() as u64);
+            DraftRow {
+                id: *id,
+                title: draft.title.clone(),
+                slug: draft.slug.clone(),
+                date: draft.date,
+                created: draft.created,
+                members: draft.members.len(),
+                drawn: draft.is_drawn(),
+                upcoming: draft.is_upcoming(),
+                bytes,
+                size: format_size(bytes),
+                purge: draft
+                    .trashed
+                    .map(|date| date + Duration::days(config.trash_days)),
+            }
+        })
+        .collect::<Vec<DraftRow>>();
+    rows.sort_by_key(|row| row.created);
+    rows
+}
+
+#[get("/admin")]
+pub fn dashboard(
+    _admin: Admin,
+    locale: Locale,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Template {
+    let rows = rows(&config, &drafts);
+    let total = rows.iter().map(|row| row.bytes).sum();
+    let (trash, rows): (Vec<DraftRow>, Vec<DraftRow>) =
+        rows.into_iter().partition(|row| row.purge.is_some());
+    let data_file_size = config
+        .data_file
+        .as_ref()
+        .and_then(|path| fs::metadata(path).ok())
+        .map(|metadata| format_size(metadata.len()));
+    let (outbox, pending): (Vec<outbox::Entry>, Vec<outbox::Entry>) =
+        outbox::entries().into_iter().partition(|entry| entry.dead);
+    i18n::render(
+        "admin",
+        locale,
+        Dashboard {
+            members: rows.iter().map(|row| row.members).sum(),
+            drawn: rows.iter().filter(|row| row.drawn).count(),
+            drafts: rows,
+            trash,
+            size: format_size(total),
+            data_file: config.data_file.clone(),
+            data_file_size,
+            outbox,
+            pending: pending.len(),
+        },
+    )
+}
+
+#[get("/admin", rank = 2)]
+pub fn show_login(locale: Locale) -> Template {
+    let context: HashMap<&str, &str> = HashMap::new();
+    i18n::render("admin_login", locale, context)
+}
+
+#[post("/admin/login", data = "<login>")]
+pub fn login(
+    login: Form<Login>,
+    locale: Locale,
+    config: State<AppConfig>,
+    mut cookies: Cookies,
+) -> Result<Redirect, Template> {
+    match &config.admin_token {
+        Some(token) if *token == login.token => {
+            cookies.add(
+                Cookie::build(ADMIN_COOKIE, login.into_inner().token)
+                    .path("/")
+                    .http_only(true)
+                    .finish(),
+            );
+            Ok(Redirect::to(uri!(dashboard)))
+        }
+        _ => {
+            tracing::warn!("failed admin login");
+            let mut context = HashMap::new();
+            context.insert("error", locale.text("admin_login_error"));
+            Err(i18n::render("admin_login", locale, context))
+        }
+    }
+}
+
+#[post("/admin/logout")]
+pub fn logout(mut cookies: Cookies) -> Redirect {
+    cookies.remove(Cookie::build(ADMIN_COOKIE, "").path("/").finish());
+    Redirect::to(uri!(show_login))
+}
+
+fn delete(id: usize, drafts: &Drafts) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    entry.write().trash(audit::Actor::Admin);
+    tracing::info!(id, "draft moved to the trash by admin");
+    Ok(())
+}
+
+#[post("/admin/draft/<id>/delete")]
+pub fn delete_draft(id: usize, _admin: Admin, drafts: State<Drafts>) -> Result<Redirect, Status> {
+    delete(id, &drafts)?;
+    Ok(Redirect::to(uri!(dashboard)))
+}
+
+#[post("/admin/draft/<id>/restore")]
+pub fn restore_draft(
+    id: usize,
+    _admin: Admin,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    let entry = drafts.get_any(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if draft.trashed.is_none() {
+        return Err(Status::NotFound);
+    }
+    draft.restore(audit::Actor::Admin, &config);
+    tracing::info!(id, "draft restored by admin");
+    Ok(Redirect::to(uri!(dashboard)))
+}
+
+#[delete("/api/draft/<id>")]
+pub fn api_delete_draft(id: usize, _admin: Admin, drafts: State<Drafts>) -> Result<Status, Status> {
+    delete(id, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[get("/api/admin/drafts")]
+pub fn api_drafts(
+    _admin: Admin,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Json<Vec<DraftRow>> {
+    Json(rows(&config, &drafts))
+}
+
+fn writable(path: &Path) -> bool {
+    let target = if path.exists() {
+        path
+    } else {
+        match path.parent() {
+            Some(dir) if dir != Path::new("") => dir,
+            _ => Path::new("."),
+        }
+    };
+    fs::metadata(target).map_or(false, |metadata| !metadata.permissions().readonly())
+}
+
+#[get("/api/admin/storage")]
+pub fn api_storage(
+    _admin: Admin,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Json<Storage> {
+    let rows = rows(&config, &drafts);
+    let (media_files, media_bytes) = fs::read_dir(&config.media_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok()?.metadata().ok())
+                .filter(|metadata| metadata.is_file())
+                .fold((0, 0), |(files, bytes), metadata| {
+                    (files + 1, bytes + metadata.len())
+                })
+        })
+        .unwrap_or((0, 0));
+    Json(Storage {
+        drafts: rows.iter().filter(|row| row.purge.is_none()).count(),
+        trashed: rows.iter().filter(|row| row.purge.is_some()).count(),
+        bytes: rows.iter().map(|row| row.bytes).sum(),
+        data_file: config.data_file.clone(),
+        data_file_bytes: config
+            .data_file
+            .as_ref()
+            .and_then(|path| fs::metadata(path).ok())
+            .map(|metadata| metadata.len()),
+        writable: match &config.data_file {
+            Some(path) => health::storage_ready(&config) && writable(Path::new(path)),
+            None => true,
+        },
+        media_dir: config.media_dir.clone(),
+        media_files,
+        media_bytes,
+    })
+}
+
+// Runs the retention sweep now instead of waiting for the next interval.
+#[post("/api/admin/cleanup")]
+pub fn api_cleanup(
+    _admin: Admin,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Json<Cleanup> {
+    let (trashed, purged) = retention::sweep(&drafts, &config);
+    let continued = series::continue_all(&drafts, &config);
+    tracing::info!(trashed, purged, continued, "cleanup forced by admin");
+    Json(Cleanup {
+        trashed,
+        purged,
+        continued,
+    })
+}
+
+// Everything, the trash included, in the format `POST /api/restore` takes.
+#[get("/api/admin/export")]
+pub fn api_export(_admin: Admin, drafts: State<Drafts>) -> Json<backup::Backup> {
+    Json(backup::Backup::new(drafts.snapshot()))
+}
@@ -0,0 +1,136 @@
+use crate::i18n::{self, Locale};
+use crate::{audit, Draft, DraftEntry, Drafts, Organizer};
+use rocket::http::Status;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use rocket_contrib::templates::Template;
+use serde::Serialize;
+
+pub use api::archive::Pair;
+
+// Who gave to whom, ordered by giver, once the organizer revealed it.
+pub fn pairs(draft: &Draft) -> Vec<Pair> {
+    if !draft.revealed {
+        return Vec::new();
+    }
+    all_pairs(draft)
+}
+
+fn all_pairs(draft: &Draft) -> Vec<Pair> {
+    let mut pairs = draft
+        .members
+        .iter()
+        .filter_map(|member| {
+            Some(Pair {
+                giver: member.name.clone(),
+                recipient: member.recipient()?,
+            })
+        })
+        .collect::<Vec<Pair>>();
+    pairs.sort_by(|a, b| a.giver.cmp(&b.giver));
+    pairs
+}
+
+// Only a past exchange can be revealed, and only if the server can read
+// its tickets.
+fn reveal(id: usize, organizer: &Organizer, drafts: &Drafts) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    if !draft.is_archived() || !draft.is_drawn() || draft.passphrases || draft.blind {
+        return Err(Status::Conflict);
+    }
+    if !draft.revealed {
+        draft.revealed = true;
+        draft.record(audit::Actor::Organizer, audit::Action::Revealed);
+    }
+    Ok(())
+}
+
+#[post("/api/draft/<id>/reveal")]
+pub fn api_reveal(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Json<Vec<Pair>>, Status> {
+    reveal(id, &organizer, &drafts)?;
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    Ok(Json(pairs(&draft)))
+}
+
+#[post("/draft/<id>/reveal")]
+pub fn reveal_draft(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    reveal(id, &organizer, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+// The whole assignment map, for everyone once it was revealed and for the
+// organizer once the exchange is over.
+fn assignments(id: usize, organizer: &Organizer, drafts: &Drafts) -> Result<RevealContext, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !draft.is_drawn() || draft.passphrases || draft.blind {
+        return Err(Status::Conflict);
+    }
+    if !draft.revealed && !(draft.is_archived() && organizer.owns(&draft)) {
+        return Err(Status::Forbidden);
+    }
+    Ok(RevealContext {
+        id,
+        title: draft.title.clone(),
+        pairs: all_pairs(&draft),
+    })
+}
+
+#[get("/api/draft/<id>/assignments")]
+pub fn api_assignments(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Json<Vec<Pair>>, Status> {
+    Ok(Json(assignments(id, &organizer, &drafts)?.pairs))
+}
+
+#[derive(Serialize)]
+struct RevealContext {
+    id: usize,
+    title: String,
+    pairs: Vec<Pair>,
+}
+
+#[get("/draft/<id>/reveal")]
+pub fn show_reveal(
+    id: usize,
+    locale: Locale,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Template, Status> {
+    let context = assignments(id, &organizer, &drafts)?;
+    Ok(i18n::render("reveal", locale, context))
+}
+
+#[derive(Serialize)]
+struct ArchiveContext {
+    drafts: Vec<DraftEntry>,
+}
+
+// Past drafts, the latest first.
+#[get("/archive")]
+pub fn show(locale: Locale, drafts: State<Drafts>) -> Template {
+    let mut entries = drafts
+        .active()
+        .iter()
+        .map(|(id, entry)| DraftEntry::new(*id, &entry.read()))
+        .filter(|entry| entry.draft.is_archived())
+        .collect::<Vec<DraftEntry>>();
+    entries.sort_by(|a, b| b.draft.date.cmp(&a.draft.date));
+    i18n::render("archive", locale, ArchiveContext { drafts: entries })
+}
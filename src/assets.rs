@@ -0,0 +1,65 @@
+use rocket::http::ContentType;
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use rust_embed::RustEmbed;
+use std::borrow::Cow;
+use std::fs;
+use std::io::{self, Cursor};
+use std::path::{Path, PathBuf};
+
+#[derive(RustEmbed)]
+#[folder = "templates/"]
+pub struct Templates;
+
+#[derive(RustEmbed)]
+#[folder = "img/"]
+pub struct Images;
+
+#[derive(RustEmbed)]
+#[folder = "css/"]
+pub struct Styles;
+
+pub struct Asset {
+    content_type: ContentType,
+    body: Cow<'static, [u8]>,
+}
+
+impl<'r> Responder<'r> for Asset {
+    fn respond_to(self, _: &Request) -> response::Result<'r> {
+        Response::build()
+            .header(self.content_type)
+            .sized_body(Cursor::new(self.body))
+            .ok()
+    }
+}
+
+// Files in the configured directory take precedence over the embedded ones,
+// so a deployment can still customize single assets.
+pub fn load<E: RustEmbed>(dir: &str, path: &PathBuf) -> Option<Asset> {
+    let content_type = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(ContentType::from_extension)
+        .unwrap_or(ContentType::Binary);
+    let file = Path::new(dir).join(path);
+    let body = if file.is_file() {
+        Cow::Owned(fs::read(file).ok()?)
+    } else {
+        E::get(path.to_str()?)?
+    };
+    Some(Asset { content_type, body })
+}
+
+// Rocket only loads templates from a directory, so without a templates
+// directory next to the binary the embedded ones are written to a
+// temporary directory which is then used instead.
+pub fn extract_templates() -> io::Result<PathBuf> {
+    let dir = std::env::temp_dir().join("weihnachts-wichtel-templates");
+    fs::create_dir_all(&dir)?;
+    for name in Templates::iter() {
+        if let Some(content) = Templates::get(&name) {
+            fs::write(dir.join(name.as_ref()), content)?;
+        }
+    }
+    Ok(dir)
+}
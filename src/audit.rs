@@ -0,0 +1,66 @@
+use crate::i18n::Locale;
+
+pub use api::audit::{Action, Actor, Entry};
+
+// The line of an entry in the timeline, in the organizer's language.
+pub fn describe(entry: &Entry, locale: Locale) -> String {
+    let actor = match &entry.actor {
+        Actor::Organizer => locale.text("audit_organizer").to_string(),
+        Actor::Participant(name) => name.clone(),
+        Actor::Admin => locale.text("audit_admin").to_string(),
+        Actor::System => locale.text("audit_system").to_string(),
+    };
+    let text = match &entry.action {
+        Action::Created => locale.text("audit_created").to_string(),
+        Action::Cloned { from } => locale
+            .text("audit_cloned")
+            .replace("{from}", &from.to_string()),
+        Action::MembersAdded { names } => locale
+            .text("audit_members_added")
+            .replace("{names}", &names.join(", ")),
+        Action::Drawn => locale.text("audit_drawn").to_string(),
+        Action::Redrawn => locale.text("audit_redrawn").to_string(),
+        Action::Assigned => locale.text("audit_assigned").to_string(),
+        Action::TicketViewed { name } => locale.text("audit_ticket_viewed").replace("{name}", name),
+        Action::RetentionExtended { until } => locale
+            .text("audit_retention_extended")
+            .replace("{until}", &locale.format_date(until, false)),
+        Action::Trashed => locale.text("audit_trashed").to_string(),
+        Action::Restored => locale.text("audit_restored").to_string(),
+        Action::Swapped { first, second } => locale
+            .text("audit_swapped")
+            .replace("{first}", first)
+            .replace("{second}", second),
+        Action::TicketRedrawn { name, changed } => locale
+            .text("audit_ticket_redrawn")
+            .replace("{name}", name)
+            .replace("{changed}", &changed.to_string()),
+        Action::Reassigned { changed } => locale
+            .text("audit_reassigned")
+            .replace("{changed}", &changed.to_string()),
+        Action::PollCreated { question } => locale
+            .text("audit_poll_created")
+            .replace("{question}", question),
+        Action::CommentDeleted { name } => {
+            locale.text("audit_comment_deleted").replace("{name}", name)
+        }
+        Action::Revealed => locale.text("audit_revealed").to_string(),
+        Action::GiftReceived { name } => locale.text("audit_gift_received").replace("{name}", name),
+        Action::InvitationAccepted { name } => locale
+            .text("audit_invitation_accepted")
+            .replace("{name}", name),
+        Action::InvitationDeclined { name } => locale
+            .text("audit_invitation_declined")
+            .replace("{name}", name),
+        Action::OrganizerAdded { email } => locale
+            .text("audit_organizer_added")
+            .replace("{email}", email),
+        Action::OrganizerRemoved { email } => locale
+            .text("audit_organizer_removed")
+            .replace("{email}", email),
+        Action::OwnershipTransferred { email } => locale
+            .text("audit_ownership_transferred")
+            .replace("{email}", email),
+    };
+    format!("{}: {}", actor, text)
+}
@@ -0,0 +1,248 @@
+use crate::config::AppConfig;
+use crate::{generate_token, Draft, Drafts, Member};
+use image::imageops::FilterType;
+use image::io::Reader;
+use image::ImageFormat;
+use lazy_static::lazy_static;
+use multipart::server::Multipart;
+use rocket::http::{ContentType, Status};
+use rocket::response::{NamedFile, Redirect};
+use rocket::{Data, State};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+// Largest upload accepted, before resizing.
+pub const MAX_UPLOAD: u64 = 2 * 1024 * 1024;
+// Largest width or height of an uploaded image, so a small file can't
+// decode to a huge one.
+const MAX_DIMENSION: u32 = 4096;
+// Edge length of the stored square avatars.
+const SIZE: u32 = 128;
+const MAX_EMOJI_LENGTH: usize = 8;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", content = "value", rename_all = "lowercase")]
+pub enum Avatar {
+    Emoji(String),
+    // File name in the media directory.
+    Image(String),
+    // URL of the Gravatar of the member's email. Only filled in for views,
+    // never stored.
+    Gravatar(String),
+}
+
+lazy_static! {
+    static ref GRAVATARS: RwLock<HashMap<String, String>> = RwLock::new(HashMap::new());
+}
+
+// Gravatar hashes the trimmed, lowercased address. Falls back to a
+// generated pattern for addresses without a Gravatar.
+fn gravatar(email: &str) -> String {
+    let email = email.trim().to_lowercase();
+    if let Some(url) = GRAVATARS.read().unwrap().get(&email) {
+        return url.clone();
+    }
+    let hash = Sha256::digest(email.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    let url = format!(
+        "https://www.gravatar.com/avatar/{}?s={}&d=identicon",
+        hash, SIZE
+    );
+    GRAVATARS.write().unwrap().insert(email, url.clone());
+    url
+}
+
+// The avatar shown for a member: the uploaded one, else the Gravatar of
+// their email.
+pub fn of(member: &Member) -> Option<Avatar> {
+    match (&member.avatar, &member.email) {
+        (Some(avatar), _) => Some(avatar.clone()),
+        (None, Some(email)) if !email.trim().is_empty() => Some(Avatar::Gravatar(gravatar(email))),
+        _ => None,
+    }
+}
+
+impl Avatar {
+    pub fn url(&self) -> Option<String> {
+        match self {
+            Avatar::Image(file) => Some(format!("/media/{}", file)),
+            Avatar::Gravatar(url) => Some(url.clone()),
+            Avatar::Emoji(_) => None,
+        }
+    }
+}
+
+// A few characters without any letters or digits, so the field can't be
+// used for a second name.
+fn valid_emoji(emoji: &str) -> bool {
+    let length = emoji.chars().count();
+    length > 0
+        && length <= MAX_EMOJI_LENGTH
+        && !emoji
+            .chars()
+            .any(|c| c.is_alphanumeric() || c.is_whitespace() || c.is_control())
+}
+
+// Decodes the upload, crops it to a square and stores it as PNG.
+fn store_image(dir: &str, bytes: &[u8]) -> Result<String, Status> {
+    let reader = Reader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|_| Status::UnprocessableEntity)?;
+    match reader.format() {
+        Some(ImageFormat::Png) | Some(ImageFormat::Jpeg) | Some(ImageFormat::Gif) => (),
+        _ => return Err(Status::UnsupportedMediaType),
+    }
+    let (width, height) = Reader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()
+        .and_then(|reader| reader.into_dimensions().ok())
+        .ok_or(Status::UnprocessableEntity)?;
+    if width > MAX_DIMENSION || height > MAX_DIMENSION {
+        return Err(Status::PayloadTooLarge);
+    }
+    let image = reader
+        .decode()
+        .map_err(|_| Status::UnprocessableEntity)?
+        .resize_to_fill(SIZE, SIZE, FilterType::Lanczos3);
+    fs::create_dir_all(dir).map_err(|_| Status::InternalServerError)?;
+    let file = format!("{}.png", generate_token());
+    image
+        .save_with_format(Path::new(dir).join(&file), ImageFormat::Png)
+        .map_err(|e| {
+            tracing::error!(error = %e, "could not store avatar");
+            Status::InternalServerError
+        })?;
+    Ok(file)
+}
+
+fn remove_image(dir: &str, avatar: &Option<Avatar>) {
+    if let Some(Avatar::Image(file)) = avatar {
+        if let Err(e) = fs::remove_file(Path::new(dir).join(file)) {
+            tracing::warn!(file = file.as_str(), error = %e, "could not remove avatar");
+        }
+    }
+}
+
+// The uploaded images of a draft about to be purged.
+pub fn remove_all(dir: &str, draft: &Draft) {
+    for member in &draft.members {
+        remove_image(dir, &member.avatar);
+    }
+}
+
+// Replaces the avatar of the member with the token, returning whether the
+// member exists.
+fn set(id: usize, key: &str, avatar: Option<Avatar>, config: &AppConfig, drafts: &Drafts) -> bool {
+    let entry = match drafts.get(id) {
+        Some(entry) => entry,
+        None => return false,
+    };
+    let mut draft = entry.write();
+    let member = match draft.member_by_token(key).cloned() {
+        Some(member) => member,
+        None => return false,
+    };
+    let mut member = draft.members.take(&member).expect("member of the draft");
+    remove_image(&config.media_dir, &member.avatar);
+    member.avatar = avatar;
+    draft.members.insert(member);
+    true
+}
+
+// The fields of the avatar form: either an `image` file or an `emoji`.
+fn read_form(content_type: &ContentType, data: Data) -> Result<(Vec<u8>, String), Status> {
+    let boundary = content_type
+        .params()
+        .find(|(key, _)| *key == "boundary")
+        .map(|(_, value)| value.to_string())
+        .ok_or(Status::BadRequest)?;
+    let mut image = Vec::new();
+    let mut emoji = String::new();
+    let mut too_large = false;
+    Multipart::with_body(data.open().take(MAX_UPLOAD + 64 * 1024), boundary)
+        .foreach_entry(|entry| match &*entry.headers.name {
+            "image" => {
+                let mut data = entry.data.take(MAX_UPLOAD + 1);
+                if data.read_to_end(&mut image).is_ok() && image.len() as u64 > MAX_UPLOAD {
+                    too_large = true;
+                }
+            }
+            "emoji" => {
+                let _ = entry.data.take(64).read_to_string(&mut emoji);
+            }
+            _ => (),
+        })
+        .map_err(|_| Status::BadRequest)?;
+    if too_large {
+        return Err(Status::PayloadTooLarge);
+    }
+    Ok((image, emoji.trim().to_string()))
+}
+
+#[post("/draft/<id>/ticket/<key>/avatar", data = "<data>")]
+pub fn upload(
+    id: usize,
+    key: String,
+    content_type: &ContentType,
+    data: Data,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    if !content_type.is_form_data() {
+        return Err(Status::UnsupportedMediaType);
+    }
+    let (image, emoji) = read_form(content_type, data)?;
+    let avatar = if !image.is_empty() {
+        Avatar::Image(store_image(&config.media_dir, &image)?)
+    } else if valid_emoji(&emoji) {
+        Avatar::Emoji(emoji)
+    } else {
+        return Err(Status::UnprocessableEntity);
+    };
+    if !set(id, &key, Some(avatar.clone()), &config, &drafts) {
+        remove_image(&config.media_dir, &Some(avatar));
+        return Err(Status::NotFound);
+    }
+    Ok(Redirect::to(format!("/draft/{}/ticket/{}", id, key)))
+}
+
+#[post("/draft/<id>/ticket/<key>/avatar/delete")]
+pub fn remove(
+    id: usize,
+    key: String,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    if !set(id, &key, None, &config, &drafts) {
+        return Err(Status::NotFound);
+    }
+    Ok(Redirect::to(format!("/draft/{}/ticket/{}", id, key)))
+}
+
+#[delete("/api/draft/<id>/ticket/<key>/avatar")]
+pub fn api_delete(
+    id: usize,
+    key: String,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Status {
+    if set(id, &key, None, &config, &drafts) {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    }
+}
+
+// Uploaded avatars. The names are random tokens, so they can't be guessed
+// from a draft.
+#[get("/media/<file..>")]
+pub fn show(file: PathBuf, config: State<AppConfig>) -> Option<NamedFile> {
+    NamedFile::open(Path::new(&config.media_dir).join(file)).ok()
+}
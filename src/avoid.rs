@@ -0,0 +1,172 @@
+use crate::{name_key, Draft, Drafts, Member, Organizer};
+use rocket::http::Status;
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use std::collections::HashMap;
+
+pub use api::avoid::{default_penalty, Avoid, Overview};
+
+const MAX_PENALTY: u32 = 10;
+const MAX_AVOIDED: usize = 200;
+
+// Adds the penalties to the weights the draw minimizes, next to the pairs
+// of past editions.
+pub fn add_weights(avoided: &[Avoid], weights: &mut HashMap<(String, String), u32>) {
+    for avoid in avoided {
+        *weights
+            .entry((name_key(&avoid.giver), name_key(&avoid.recipient)))
+            .or_insert(0) += avoid.penalty;
+    }
+}
+
+// The pairs drawn anyway. Nobody but the members learns about the tickets
+// in the zero-knowledge and organizer-blind modes, so nothing is reported
+// there.
+pub fn violated<'a>(draft: &Draft, members: impl IntoIterator<Item = &'a Member>) -> Vec<Avoid> {
+    if draft.passphrases || draft.blind || draft.avoid.is_empty() {
+        return Vec::new();
+    }
+    let drawn = members
+        .into_iter()
+        .filter_map(|member| Some((name_key(&member.name), name_key(&member.recipient()?))))
+        .collect::<Vec<(String, String)>>();
+    draft
+        .avoid
+        .iter()
+        .filter(|avoid| drawn.contains(&(name_key(&avoid.giver), name_key(&avoid.recipient))))
+        .cloned()
+        .collect()
+}
+
+// The pairs with the names as the members are called, unknown members,
+// pairs of a member with themselves and penalties out of range rejected.
+fn validate(draft: &Draft, avoided: Vec<Avoid>) -> Result<Vec<Avoid>, Status> {
+    if avoided.len() > MAX_AVOIDED {
+        return Err(Status::UnprocessableEntity);
+    }
+    let mut valid: Vec<Avoid> = Vec::new();
+    for avoid in avoided {
+        let giver = draft
+            .find_member(&avoid.giver)
+            .ok_or(Status::UnprocessableEntity)?;
+        let recipient = draft
+            .find_member(&avoid.recipient)
+            .ok_or(Status::UnprocessableEntity)?;
+        if giver.name == recipient.name || avoid.penalty == 0 || avoid.penalty > MAX_PENALTY {
+            return Err(Status::UnprocessableEntity);
+        }
+        // The same pair twice keeps the last penalty.
+        valid.retain(|known| known.giver != giver.name || known.recipient != recipient.name);
+        valid.push(Avoid {
+            giver: giver.name.clone(),
+            recipient: recipient.name.clone(),
+            penalty: avoid.penalty,
+        });
+    }
+    Ok(valid)
+}
+
+// The pairs of members still in the draft.
+fn known(draft: &Draft) -> Vec<Avoid> {
+    draft
+        .avoid
+        .iter()
+        .filter(|avoid| {
+            draft.find_member(&avoid.giver).is_some()
+                && draft.find_member(&avoid.recipient).is_some()
+        })
+        .cloned()
+        .collect()
+}
+
+// Changes the pairs to avoid. They apply from the next draw on.
+fn update(
+    id: usize,
+    organizer: &Organizer,
+    drafts: &Drafts,
+    change: impl FnOnce(&Draft) -> Vec<Avoid>,
+) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    if draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    draft.avoid = validate(&draft, change(&draft))?;
+    Ok(())
+}
+
+#[get("/api/draft/<id>/avoid")]
+pub fn api_list(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Json<Overview>, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    Ok(Json(Overview {
+        avoided: draft.avoid.clone(),
+        violated: draft.violated.clone(),
+    }))
+}
+
+#[put("/api/draft/<id>/avoid", format = "json", data = "<avoided>")]
+pub fn api_set(
+    id: usize,
+    avoided: Json<Vec<Avoid>>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    update(id, &organizer, &drafts, |_| avoided.into_inner())?;
+    Ok(Status::NoContent)
+}
+
+#[derive(FromForm)]
+pub struct AvoidForm {
+    giver: String,
+    recipient: String,
+    penalty: Option<u32>,
+}
+
+#[post("/draft/<id>/avoid", data = "<form>")]
+pub fn add(
+    id: usize,
+    form: Form<AvoidForm>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    let form = form.into_inner();
+    update(id, &organizer, &drafts, |draft| {
+        let mut avoided = known(draft);
+        avoided.push(Avoid {
+            giver: form.giver,
+            recipient: form.recipient,
+            penalty: form.penalty.unwrap_or_else(default_penalty),
+        });
+        avoided
+    })?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+#[post("/draft/<id>/avoid/delete", data = "<form>")]
+pub fn remove(
+    id: usize,
+    form: Form<AvoidForm>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    update(id, &organizer, &drafts, |draft| {
+        known(draft)
+            .into_iter()
+            .filter(|avoid| avoid.giver != form.giver || avoid.recipient != form.recipient)
+            .collect()
+    })?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
@@ -0,0 +1,23 @@
+use crate::Draft;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const VERSION: u32 = 1;
+
+#[derive(Deserialize, Serialize)]
+pub struct Backup {
+    pub version: u32,
+    pub created: DateTime<Utc>,
+    // Deleted drafts are kept as `null` to preserve the ids.
+    pub drafts: Vec<Option<Draft>>,
+}
+
+impl Backup {
+    pub fn new(drafts: Vec<Option<Draft>>) -> Backup {
+        Backup {
+            version: VERSION,
+            created: Utc::now(),
+            drafts,
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::config::AppConfig;
+use crate::i18n::Locale;
+use crate::{
+    audit, elephant, generate_token, idempotency, invite, limit, name_key, pool, qr, sanitize,
+    theme, Draft, DraftError, Drafts, Member, DEFAULT_TIMEZONE,
+};
+use chrono::Utc;
+use rocket::http::Status;
+use rocket::response::status;
+use rocket::State;
+use rocket_contrib::json::Json;
+use std::collections::HashSet;
+
+pub use api::bulk::{BulkRequest, Definition, ItemResult, Mode};
+
+// Drafts one request may create.
+const MAX_BATCH: usize = 100;
+
+// The same checks as for a draft created through the form, drawn right
+// away.
+fn build(definition: Definition, config: &AppConfig) -> Result<Draft, DraftError> {
+    let timezone = definition.timezone.unwrap_or(DEFAULT_TIMEZONE);
+    let date = Draft::parse_date(&definition.date, timezone)?;
+    let mut members = HashSet::new();
+    let mut names = HashSet::new();
+    for defined in definition.members {
+        // Checked as stored, a name of nothing but spaces is none.
+        let name = sanitize::line(&defined.name);
+        if name.is_empty() {
+            return Err(DraftError::NoTeamOrNameDefined);
+        }
+        if !names.insert(name_key(&name)) {
+            return Err(DraftError::MemberAlreadyDefined { name });
+        }
+        let mut member = Member::new(name, defined.team);
+        member.email = defined
+            .email
+            .map(|email| sanitize::line(&email))
+            .filter(|email| email != "");
+        member.pool = defined
+            .pool
+            .map(|pool| sanitize::line(&pool))
+            .filter(|pool| pool != "");
+        member.language = defined.language.map(Locale::from);
+        members.insert(member);
+    }
+    let mut pools = definition
+        .pools
+        .into_iter()
+        .map(|pool| pool::Pool {
+            name: sanitize::line(&pool.name),
+            ..pool
+        })
+        .collect::<Vec<pool::Pool>>();
+    pool::complete(&mut pools, &members);
+    let mut draft = Draft {
+        title: sanitize::line(&definition.title),
+        slug: None,
+        mode: definition.mode,
+        turns: Vec::new(),
+        rules: match definition.mode {
+            elephant::Mode::SecretSanta => None,
+            elephant::Mode::WhiteElephant => Some(elephant::Rules::default()),
+        },
+        pools,
+        budget: definition.budget,
+        team_budgets: definition.team_budgets,
+        polls: Vec::new(),
+        comments: Vec::new(),
+        emails: definition.emails,
+        passphrases: definition.passphrases,
+        blind: definition.blind,
+        theme: definition.theme,
+        avoid: Vec::new(),
+        violated: Vec::new(),
+        spending: false,
+        reassign: Vec::new(),
+        revealed: false,
+        series: None,
+        google: None,
+        draw_at: None,
+        date,
+        timezone,
+        members,
+        created: Utc::now(),
+        retain_until: None,
+        trashed: None,
+        reminded: false,
+        organizer_token: generate_token(),
+        co_organizers: Vec::new(),
+        organizer_invitations: Vec::new(),
+        share_token: None,
+        audit: Vec::new(),
+    };
+    if draft
+        .theme
+        .as_deref()
+        .map_or(false, |id| theme::find(id).is_none())
+    {
+        return Err(DraftError::InvalidData);
+    }
+    if definition.invite {
+        invite::invite_all(&mut draft);
+    }
+    draft.validate(config)?;
+    // Drawn once every member set a passphrase or answered their
+    // invitation.
+    if !draft.passphrases && !invite::pending(&draft) {
+        draft.calculate_tickets(config.max_draw_attempts)?;
+    }
+    draft.record(audit::Actor::Organizer, audit::Action::Created);
+    if draft.is_drawn() {
+        draft.record(audit::Actor::System, audit::Action::Drawn);
+    }
+    Ok(draft)
+}
+
+// Answers 201 if every draft was created, 207 if only some were and 422
+// if none were. Every draft counts against the rate limits, 429 if they
+// don't leave room for all of them.
+#[post("/api/draft/bulk", format = "json", data = "<request>")]
+pub fn create(
+    request: Json<BulkRequest>,
+    locale: Locale,
+    origin: qr::Origin,
+    key: idempotency::Key,
+    address: limit::ClientAddress,
+    config: State<AppConfig>,
+    creations: State<limit::Creations>,
+    responses: State<idempotency::Responses>,
+    drafts: State<Drafts>,
+) -> Result<status::Custom<Json<Vec<ItemResult>>>, Status> {
+    if request.drafts.len() > MAX_BATCH {
+        return Err(Status::PayloadTooLarge);
+    }
+    let body = idempotency::fingerprint(&*request);
+    let (status, results) = responses.run(&key, &body, || -> Result<_, Status> {
+        let BulkRequest {
+            mode,
+            drafts: definitions,
+        } = request.into_inner();
+        let built = definitions
+            .into_iter()
+            .map(|definition| build(definition, &config))
+            .collect::<Vec<Result<Draft, DraftError>>>();
+        let failed = built.iter().filter(|draft| draft.is_err()).count();
+        let insert = failed == 0 || mode == Mode::BestEffort;
+        if insert {
+            creations
+                .charge(&config, &drafts, address.0, built.len() - failed)
+                .map_err(|_| {
+                    tracing::warn!(ip = ?address.0, "bulk draft creation rate limited");
+                    Status::TooManyRequests
+                })?;
+        }
+
+        let mut created = 0;
+        let results = built
+            .into_iter()
+            .enumerate()
+            .map(|(index, draft)| match draft {
+                Ok(draft) if insert => {
+                    created += 1;
+                    let organizer_token = draft.organizer_token.clone();
+                    let id = drafts.insert(draft);
+                    if let Some(entry) = drafts.get(id) {
+                        invite::send(&entry.read(), id, &origin, &config, locale);
+                    }
+                    ItemResult {
+                        index,
+                        id: Some(id),
+                        slug: drafts
+                            .get_any(id)
+                            .and_then(|entry| entry.read().slug.clone()),
+                        organizer_token: Some(organizer_token),
+                        ..ItemResult::default()
+                    }
+                }
+                Ok(_) => ItemResult {
+                    index,
+                    ..ItemResult::default()
+                },
+                Err(e) => ItemResult {
+                    index,
+                    error: Some(e.to_string()),
+                    ..ItemResult::default()
+                },
+            })
+            .collect::<Vec<ItemResult>>();
+        tracing::info!(created, failed, "bulk draft creation");
+
+        let status = if failed == 0 {
+            Status::Created
+        } else if created > 0 {
+            Status::MultiStatus
+        } else {
+            Status::UnprocessableEntity
+        };
+        Ok((status, results))
+    })??;
+    Ok(status::Custom(status, Json(results)))
+}
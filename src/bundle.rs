@@ -0,0 +1,56 @@
+use crate::i18n::Locale;
+use crate::qr::Origin;
+use crate::{cards, export, Draft, Member};
+use std::error::Error;
+use std::io::{Cursor, Write};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#x27;")
+}
+
+fn ticket_html(draft: &Draft, member: &Member, url: &str, locale: Locale) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"{lang}\">\n<head>\n<meta charset=\"UTF-8\">\n<title>{title}</title>\n</head>\n\
+         <body style=\"font-family: sans-serif; text-align: center;\">\n\
+         <h1>{title}</h1>\n<p>{date}</p>\n<h2>{name}, {yours}</h2>\n\
+         <p style=\"font-size: 400%;\">{ticket}</p>\n<p><a href=\"{url}\">{url}</a></p>\n</body>\n</html>\n",
+        lang = locale.code(),
+        yours = escape_html(locale.text("ticket_yours")),
+        title = escape_html(&draft.title),
+        date = locale.format_date(&draft.local_date(), true),
+        name = escape_html(&member.name),
+        ticket = escape_html(&member.recipient().unwrap_or_default()),
+        url = escape_html(url),
+    )
+}
+
+// One HTML page and one printable card per member, named after the member.
+pub fn zip(
+    id: usize,
+    draft: &Draft,
+    origin: &Origin,
+    locale: Locale,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut members = draft.members.iter().collect::<Vec<_>>();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (index, member) in members.iter().enumerate() {
+        let name = format!("{:03}_{}", index + 1, export::sanitize(&member.name));
+        let url = origin.ticket_url(id, member);
+
+        writer.start_file(format!("{}.html", name), FileOptions::default())?;
+        writer.write_all(ticket_html(draft, member, &url, locale).as_bytes())?;
+
+        writer.start_file(format!("{}.pdf", name), FileOptions::default())?;
+        writer.write_all(&cards::pdf_for(id, draft, &[*member], origin, locale)?)?;
+    }
+    Ok(writer.finish()?.into_inner())
+}
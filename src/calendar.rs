@@ -0,0 +1,67 @@
+use crate::i18n::Locale;
+use crate::Draft;
+use chrono::Utc;
+
+const DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\r', "")
+        .replace('\n', "\\n")
+}
+
+// RFC 5545 lines must not exceed 75 octets, longer ones are continued
+// on the next line starting with a single space.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            len = 1;
+        }
+        folded.push(c);
+        len += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+pub fn description(draft: &Draft, locale: Locale) -> String {
+    let mut names = draft
+        .members
+        .iter()
+        .map(|member| member.name.as_str())
+        .collect::<Vec<&str>>();
+    names.sort();
+    locale
+        .text("calendar_description")
+        .replace("{names}", &names.join(", "))
+}
+
+pub fn event(id: usize, draft: &Draft, locale: Locale) -> String {
+    let description = description(draft, locale);
+
+    let lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//weihnachts-wichtel//DE".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:draft-{}@weihnachts-wichtel", id),
+        format!("DTSTAMP:{}", Utc::now().format(DATE_FORMAT)),
+        format!(
+            "DTSTART:{}",
+            draft.date.with_timezone(&Utc).format(DATE_FORMAT)
+        ),
+        format!("SUMMARY:{}", escape(&draft.title)),
+        format!("DESCRIPTION:{}", escape(&description)),
+        "END:VEVENT".to_string(),
+        "END:VCALENDAR".to_string(),
+    ];
+    lines.iter().map(|line| fold(line)).collect()
+}
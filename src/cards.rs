@@ -0,0 +1,118 @@
+use crate::i18n::Locale;
+use crate::qr::{self, Origin};
+use crate::{Draft, Member};
+use printpdf::{BuiltinFont, Line, Mm, PdfDocument, PdfLayerReference, Point};
+use std::io::BufWriter;
+
+// Landscape A5 pages, folded in the middle to an A6 card: the giver's name
+// is printed on the front half, the assignment on the inner half.
+const WIDTH: f64 = 210.0;
+const HEIGHT: f64 = 148.0;
+const QR_SIZE: f64 = 30.0;
+
+fn square(x: f64, y: f64, size: f64) -> Line {
+    Line {
+        points: vec![
+            (Point::new(Mm(x), Mm(y)), false),
+            (Point::new(Mm(x + size), Mm(y)), false),
+            (Point::new(Mm(x + size), Mm(y + size)), false),
+            (Point::new(Mm(x), Mm(y + size)), false),
+        ],
+        is_closed: true,
+        has_fill: true,
+        has_stroke: false,
+        is_clipping_path: false,
+    }
+}
+
+fn draw_qr(layer: &PdfLayerReference, url: &str, x: f64, y: f64) {
+    if let Some((width, modules)) = qr::modules(url) {
+        let size = QR_SIZE / width as f64;
+        for (index, dark) in modules.into_iter().enumerate() {
+            if dark {
+                let column = (index % width) as f64;
+                let row = (index / width) as f64;
+                layer.add_shape(square(
+                    x + column * size,
+                    y + QR_SIZE - (row + 1.0) * size,
+                    size,
+                ));
+            }
+        }
+    }
+}
+
+pub fn pdf(
+    id: usize,
+    draft: &Draft,
+    origin: &Origin,
+    locale: Locale,
+) -> Result<Vec<u8>, printpdf::Error> {
+    let mut members = draft.members.iter().collect::<Vec<_>>();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    pdf_for(id, draft, &members, origin, locale)
+}
+
+pub fn pdf_for(
+    id: usize,
+    draft: &Draft,
+    members: &[&Member],
+    origin: &Origin,
+    locale: Locale,
+) -> Result<Vec<u8>, printpdf::Error> {
+    let (doc, first_page, first_layer) = PdfDocument::new(
+        draft.title.as_str(),
+        Mm(WIDTH),
+        Mm(HEIGHT),
+        locale.text("card_kind"),
+    );
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+    let date = locale.format_date(&draft.local_date(), false);
+
+    for (index, member) in members.iter().enumerate() {
+        let (page, layer) = if index == 0 {
+            (first_page, first_layer)
+        } else {
+            doc.add_page(Mm(WIDTH), Mm(HEIGHT), locale.text("card_kind"))
+        };
+        let layer = doc.get_page(page).get_layer(layer);
+
+        layer.add_shape(Line {
+            points: vec![
+                (Point::new(Mm(WIDTH / 2.0), Mm(5.0)), false),
+                (Point::new(Mm(WIDTH / 2.0), Mm(HEIGHT - 5.0)), false),
+            ],
+            is_closed: false,
+            has_fill: false,
+            has_stroke: true,
+            is_clipping_path: false,
+        });
+
+        layer.use_text(draft.title.as_str(), 16, Mm(10.0), Mm(120.0), &bold);
+        layer.use_text(date.as_str(), 12, Mm(10.0), Mm(110.0), &font);
+        layer.use_text(locale.text("card_for"), 14, Mm(10.0), Mm(70.0), &font);
+        layer.use_text(member.name.as_str(), 22, Mm(10.0), Mm(58.0), &bold);
+
+        let x = WIDTH / 2.0 + 10.0;
+        layer.use_text(locale.text("card_giver"), 14, Mm(x), Mm(80.0), &font);
+        match member.recipient() {
+            Some(ticket) => layer.use_text(ticket.as_str(), 26, Mm(x), Mm(65.0), &bold),
+            None => layer.use_text(locale.text("card_not_drawn"), 14, Mm(x), Mm(65.0), &font),
+        }
+        draw_qr(&layer, &origin.ticket_url(id, member), x, 15.0);
+        layer.use_text(
+            locale.text("card_online"),
+            9,
+            Mm(x + QR_SIZE + 5.0),
+            Mm(28.0),
+            &font,
+        );
+    }
+
+    let mut buffer = BufWriter::new(Vec::new());
+    doc.save(&mut buffer)?;
+    buffer
+        .into_inner()
+        .map_err(|e| printpdf::Error::from(e.into_error()))
+}
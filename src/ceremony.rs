@@ -0,0 +1,150 @@
+use crate::config::AppConfig;
+use crate::events::{Event, Events};
+use crate::Drafts;
+use rand::seq::SliceRandom;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use ws::{CloseCode, Handshake};
+
+const POLL: Duration = Duration::from_secs(15);
+
+enum Lookup {
+    Missing,
+    Open,
+    Drawn(Vec<String>),
+}
+
+fn lookup(drafts: &Drafts, id: usize) -> Lookup {
+    let entry = match drafts.get(id) {
+        Some(entry) => entry,
+        None => return Lookup::Missing,
+    };
+    let draft = entry.read();
+    if draft.is_drawn() {
+        Lookup::Drawn(
+            draft
+                .members
+                .iter()
+                .map(|member| member.name.clone())
+                .collect(),
+        )
+    } else {
+        Lookup::Open
+    }
+}
+
+// One projected screen watching the draw of a draft, connected to
+// `/draft/<id>`. Once the draft is drawn the givers are sent one by one
+// in random order, never who they drew.
+struct Screen {
+    out: ws::Sender,
+    drafts: Drafts,
+    events: Events,
+    delay: Duration,
+    closed: Arc<AtomicBool>,
+}
+
+impl Screen {
+    fn send(out: &ws::Sender, message: serde_json::Value) -> bool {
+        out.send(message.to_string()).is_ok()
+    }
+
+    fn present(out: &ws::Sender, mut names: Vec<String>, delay: Duration, closed: &AtomicBool) {
+        names.shuffle(&mut rand::thread_rng());
+        if !Screen::send(
+            out,
+            serde_json::json!({ "type": "start", "total": names.len() }),
+        ) {
+            return;
+        }
+        for (index, name) in names.into_iter().enumerate() {
+            thread::sleep(delay);
+            if closed.load(Ordering::SeqCst) {
+                return;
+            }
+            let message = serde_json::json!({ "type": "name", "index": index, "name": name });
+            if !Screen::send(out, message) {
+                return;
+            }
+        }
+        Screen::send(out, serde_json::json!({ "type": "done" }));
+    }
+}
+
+impl ws::Handler for Screen {
+    fn on_open(&mut self, shake: Handshake) -> ws::Result<()> {
+        let id = match shake
+            .request
+            .resource()
+            .trim_start_matches("/draft/")
+            .parse::<usize>()
+        {
+            Ok(id) => id,
+            Err(_) => return self.out.close(CloseCode::Policy),
+        };
+        let out = self.out.clone();
+        let drafts = self.drafts.clone();
+        let receiver = self.events.receiver(id);
+        let delay = self.delay;
+        let closed = self.closed.clone();
+        thread::spawn(move || loop {
+            match lookup(&drafts, id) {
+                Lookup::Missing => {
+                    let _ = out.close(CloseCode::Policy);
+                    return;
+                }
+                Lookup::Drawn(names) => {
+                    Screen::present(&out, names, delay, &closed);
+                    return;
+                }
+                Lookup::Open => (),
+            }
+            loop {
+                if closed.load(Ordering::SeqCst) {
+                    return;
+                }
+                match receiver.recv_timeout(POLL) {
+                    Ok(Some(Event::DrawComplete)) => break,
+                    Ok(_) | Err(RecvTimeoutError::Timeout) => (),
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn on_close(&mut self, _: CloseCode, _: &str) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+}
+
+// Starts the WebSocket server on its own port, if one is configured.
+pub fn spawn(address: &str, config: &AppConfig, drafts: Drafts, events: Events) {
+    let port = match config.ceremony_port {
+        Some(port) => port,
+        None => return,
+    };
+    let address = format!("{}:{}", address, port);
+    let delay = Duration::from_millis(config.ceremony_delay);
+    let result = thread::Builder::new()
+        .name("ceremony".to_string())
+        .spawn(move || {
+            tracing::info!(address = address.as_str(), "starting draw ceremony server");
+            let result = ws::listen(address.as_str(), |out| Screen {
+                out,
+                drafts: drafts.clone(),
+                events: events.clone(),
+                delay,
+                closed: Arc::new(AtomicBool::new(false)),
+            });
+            if let Err(e) = result {
+                tracing::error!(error = %e, "draw ceremony server failed");
+            }
+        });
+    if let Err(e) = result {
+        tracing::error!(error = %e, "could not start the draw ceremony server");
+    }
+}
@@ -0,0 +1,203 @@
+use crate::i18n::DEFAULT_LOCALE;
+use crate::{import, matcher, name_key, pool, Draft, Member};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+
+pub const USAGE: &str = "usage: weihnachts-wichtel [serve]
+       weihnachts-wichtel draw --input <members.csv> [--exclusions <exclusions.toml>]
+                               [--output <assignments.json>] [--attempts <n>]";
+
+struct Options {
+    input: String,
+    exclusions: Option<String>,
+    output: Option<String>,
+    attempts: u32,
+}
+
+fn parse(args: &[String]) -> Result<Options, String> {
+    let mut input = None;
+    let mut exclusions = None;
+    let mut output = None;
+    let mut attempts = 100;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", arg))
+        };
+        match arg.as_str() {
+            "--input" | "-i" => input = Some(value()?),
+            "--exclusions" | "-e" => exclusions = Some(value()?),
+            "--output" | "-o" => output = Some(value()?),
+            "--attempts" => {
+                attempts = value()?
+                    .parse()
+                    .map_err(|_| "--attempts needs a number".to_string())?
+            }
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+    Ok(Options {
+        input: input.ok_or_else(|| "--input is missing".to_string())?,
+        exclusions,
+        output,
+        attempts,
+    })
+}
+
+// One pair that must not be drawn, e.g.
+//
+//     [[exclusion]]
+//     giver = "Anna"
+//     recipient = "Ben"
+#[derive(Deserialize)]
+struct Exclusion {
+    giver: String,
+    recipient: String,
+}
+
+#[derive(Deserialize)]
+struct Exclusions {
+    #[serde(default)]
+    exclusion: Vec<Exclusion>,
+}
+
+#[derive(Serialize)]
+struct Assignment {
+    giver: String,
+    recipient: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pool: Option<String>,
+}
+
+fn read_members(path: &str) -> Result<Vec<Member>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    import::parse_members(&HashSet::new(), &text, DEFAULT_LOCALE).map_err(|errors| {
+        errors
+            .iter()
+            .map(|error| format!("{}:{}: {}", path, error.row, error.message))
+            .collect::<Vec<String>>()
+            .join("\n")
+    })
+}
+
+// The excluded pairs by name key, checked against the members so a typo
+// doesn't silently allow a pair.
+fn read_exclusions(path: &str, members: &[Member]) -> Result<HashSet<(String, String)>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let exclusions = toml::from_str::<Exclusions>(&text).map_err(|e| format!("{}: {}", path, e))?;
+    let names = members
+        .iter()
+        .map(|member| name_key(&member.name))
+        .collect::<HashSet<String>>();
+    exclusions
+        .exclusion
+        .into_iter()
+        .map(|exclusion| {
+            for name in &[&exclusion.giver, &exclusion.recipient] {
+                if !names.contains(&name_key(name)) {
+                    return Err(format!("{}: unknown member {}", path, name));
+                }
+            }
+            Ok((name_key(&exclusion.giver), name_key(&exclusion.recipient)))
+        })
+        .collect()
+}
+
+// Draws the members of every pool like the web application does. Draws
+// hitting an excluded pair are thrown away and repeated.
+fn assign(
+    members: Vec<Member>,
+    exclusions: &HashSet<(String, String)>,
+    attempts: u32,
+) -> Result<Vec<Assignment>, String> {
+    let mut assignments = Vec::new();
+    for (_, members) in pool::group(&members) {
+        let members = members.into_iter().cloned().collect::<Vec<Member>>();
+        let teams = members
+            .iter()
+            .map(|member| member.team)
+            .collect::<Vec<u32>>();
+        if let Some(team) = matcher::infeasible_team(&teams) {
+            return Err(format!(
+                "team {} has more members than there are others to draw",
+                team
+            ));
+        }
+        let mut drawn = None;
+        for _ in 0..attempts.max(1) {
+            let candidate =
+                Draft::draw_pool(members.clone(), attempts).map_err(|e| e.to_string())?;
+            let allowed = candidate.iter().all(|member| {
+                member.recipient().map_or(false, |recipient| {
+                    !exclusions.contains(&(name_key(&member.name), name_key(&recipient)))
+                })
+            });
+            if allowed {
+                drawn = Some(candidate);
+                break;
+            }
+        }
+        let drawn = drawn.ok_or_else(|| {
+            format!(
+                "no draw without the excluded pairs found in {} attempts",
+                attempts
+            )
+        })?;
+        assignments.extend(drawn.into_iter().filter_map(|member| {
+            Some(Assignment {
+                recipient: member.recipient()?,
+                giver: member.name,
+                pool: member.pool,
+            })
+        }));
+    }
+    assignments.sort_by(|a, b| a.giver.cmp(&b.giver));
+    Ok(assignments)
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let members = read_members(&options.input)?;
+    if members.len() < 2 {
+        return Err(format!(
+            "{}: at least two members are needed",
+            options.input
+        ));
+    }
+    let exclusions = match &options.exclusions {
+        Some(path) => read_exclusions(path, &members)?,
+        None => HashSet::new(),
+    };
+    let assignments = assign(members, &exclusions, options.attempts)?;
+    let json = serde_json::to_string_pretty(&assignments).map_err(|e| e.to_string())?;
+    match &options.output {
+        Some(path) => {
+            fs::write(path, json + "\n").map_err(|e| format!("{}: {}", path, e))?;
+            eprintln!("{} assignments written to {}", assignments.len(), path);
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+// `draw` subcommand: draws the members of a CSV file (the same format as
+// the import on the draft page) without starting the server. Returns the
+// exit code.
+pub fn draw(args: &[String]) -> i32 {
+    let options = match parse(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    match run(options) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
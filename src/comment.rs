@@ -0,0 +1,147 @@
+use crate::{audit, sanitize, Drafts, Organizer};
+use chrono::{DateTime, Utc};
+use rocket::http::Status;
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use serde::{Deserialize, Serialize};
+
+pub use api::comment::NewComment;
+
+const MAX_NAME: usize = 50;
+const MAX_MESSAGE: usize = 1000;
+
+// A message on the draft page, e.g. about who brings what.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Comment {
+    // Stays the same when earlier comments are deleted.
+    pub id: usize,
+    pub name: String,
+    pub message: String,
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct CommentView {
+    id: usize,
+    name: String,
+    // The escaped message with its line breaks, to be rendered as is.
+    html: String,
+    time: DateTime<Utc>,
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            '/' => escaped.push_str("&#x2F;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Comment {
+    pub fn view(&self) -> CommentView {
+        CommentView {
+            id: self.id,
+            name: self.name.clone(),
+            html: escape(&self.message)
+                .lines()
+                .collect::<Vec<&str>>()
+                .join("<br>"),
+            time: self.time,
+        }
+    }
+}
+
+fn add(id: usize, comment: NewComment, drafts: &Drafts) -> Result<usize, Status> {
+    let name = sanitize::line(&comment.name);
+    let message = sanitize::text(&comment.message);
+    if name == ""
+        || message == ""
+        || name.chars().count() > MAX_NAME
+        || message.chars().count() > MAX_MESSAGE
+    {
+        return Err(Status::UnprocessableEntity);
+    }
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    let comment = draft.comments.last().map_or(0, |comment| comment.id + 1);
+    draft.comments.push(Comment {
+        id: comment,
+        name,
+        message,
+        time: Utc::now(),
+    });
+    Ok(comment)
+}
+
+fn delete(id: usize, comment: usize, organizer: &Organizer, drafts: &Drafts) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    let index = draft
+        .comments
+        .iter()
+        .position(|entry| entry.id == comment)
+        .ok_or(Status::NotFound)?;
+    let comment = draft.comments.remove(index);
+    draft.record(
+        audit::Actor::Organizer,
+        audit::Action::CommentDeleted { name: comment.name },
+    );
+    Ok(())
+}
+
+#[post("/api/draft/<id>/comment", format = "json", data = "<comment>")]
+pub fn api_create(
+    id: usize,
+    comment: Json<NewComment>,
+    drafts: State<Drafts>,
+) -> Result<Json<usize>, Status> {
+    add(id, comment.into_inner(), &drafts).map(Json)
+}
+
+#[post("/draft/<id>/comment", data = "<comment>")]
+pub fn create(
+    id: usize,
+    comment: Form<NewComment>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    add(id, comment.into_inner(), &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}#comments", id)))
+}
+
+#[delete("/api/draft/<id>/comment/<comment>")]
+pub fn api_delete(
+    id: usize,
+    comment: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    delete(id, comment, &organizer, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/comment/<comment>/delete")]
+pub fn remove(
+    id: usize,
+    comment: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    delete(id, comment, &organizer, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}#comments", id)))
+}
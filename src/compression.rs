@@ -0,0 +1,139 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{ContentType, Header};
+use rocket::response::Body;
+use rocket::{Request, Response};
+use std::io::{Cursor, Read, Write};
+
+// Bodies smaller than this aren't worth compressing.
+const MIN_SIZE: usize = 860;
+const BROTLI_QUALITY: u32 = 5;
+const BROTLI_WINDOW: u32 = 22;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+
+    // The best encoding the client accepts, brotli over gzip.
+    fn negotiate(header: &str) -> Option<Encoding> {
+        let accepted = header
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let name = parts.next()?.trim().to_lowercase();
+                let quality = parts
+                    .find_map(|part| part.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                Some((name, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .map(|(name, _)| name)
+            .collect::<Vec<String>>();
+        [Encoding::Brotli, Encoding::Gzip]
+            .iter()
+            .copied()
+            .find(|encoding| accepted.iter().any(|name| name == encoding.name()))
+    }
+
+    fn encode(self, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoding::Brotli => {
+                let mut compressed = Vec::new();
+                {
+                    let mut writer = brotli::CompressorWriter::new(
+                        &mut compressed,
+                        4096,
+                        BROTLI_QUALITY,
+                        BROTLI_WINDOW,
+                    );
+                    writer.write_all(bytes)?;
+                }
+                Ok(compressed)
+            }
+            Encoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+// Text formats: pages, JSON, feeds and stylesheets. Images, PDFs and zips
+// are compressed already.
+fn compressible(content_type: &ContentType) -> bool {
+    content_type.top() == "text"
+        || *content_type == ContentType::JSON
+        || content_type.sub().as_str().ends_with("+xml")
+        || *content_type == ContentType::JavaScript
+        || *content_type == ContentType::SVG
+}
+
+// Compresses sized text responses for clients sending `Accept-Encoding`.
+// Streamed responses like the live events or the draft listing are left
+// alone, as compressing them would mean holding them back.
+pub struct CompressionFairing;
+
+impl Fairing for CompressionFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Compression",
+            kind: Kind::Response,
+        }
+    }
+
+    fn on_response(&self, request: &Request, response: &mut Response) {
+        let encoding = match request
+            .headers()
+            .get_one("Accept-Encoding")
+            .and_then(Encoding::negotiate)
+        {
+            Some(encoding) => encoding,
+            None => return,
+        };
+        if response.headers().contains("Content-Encoding")
+            || !response
+                .content_type()
+                .map_or(false, |ct| compressible(&ct))
+        {
+            return;
+        }
+        let mut body = match response.take_body() {
+            Some(Body::Sized(body, size)) if size as usize >= MIN_SIZE => body,
+            Some(body) => {
+                response.set_raw_body(body);
+                return;
+            }
+            None => return,
+        };
+        let mut bytes = Vec::new();
+        if let Err(e) = body.read_to_end(&mut bytes) {
+            tracing::warn!(error = %e, "could not read a response body to compress");
+            response.set_sized_body(Cursor::new(bytes));
+            return;
+        }
+        match encoding.encode(&bytes) {
+            Ok(compressed) => {
+                response.set_header(Header::new("Content-Encoding", encoding.name()));
+                response.set_sized_body(Cursor::new(compressed));
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "could not compress a response");
+                response.set_sized_body(Cursor::new(bytes));
+            }
+        }
+        response.adjoin_header(Header::new("Vary", "Accept-Encoding"));
+    }
+}
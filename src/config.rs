@@ -0,0 +1,284 @@
+use crate::i18n::{self, Locale};
+use rocket::config::{Config, Value};
+use serde::Deserialize;
+use std::env;
+use std::str::FromStr;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+// VAPID key pair for Web Push. The private key is a PEM file, the public
+// key the URL-safe base64 form handed to the browser.
+#[derive(Deserialize, Debug, Clone)]
+pub struct PushConfig {
+    pub private_key: String,
+    pub public_key: String,
+    pub subject: String,
+}
+
+// OAuth client of the Google Calendar integration. Its redirect URI is
+// `<base_url>/oauth/google`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct GoogleConfig {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+// The `[<environment>.app]` (or `[global.app]`) table of the Rocket config.
+// Every value can be overridden by an `APP_<NAME>` environment variable,
+// e.g. `APP_BASE_URL` or `APP_SMTP_HOST`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AppConfig {
+    pub img_dir: String,
+    pub css_dir: String,
+    // Where uploaded avatars are stored.
+    pub media_dir: String,
+    pub max_members: usize,
+    pub max_title_length: usize,
+    // Drafts kept at most, the trash not counted.
+    pub max_drafts: Option<usize>,
+    // Drafts a client address may create per hour.
+    pub max_drafts_per_hour: Option<u32>,
+    // Whether the client address is taken from `X-Real-IP`, as set by a
+    // reverse proxy in front, instead of the connection.
+    pub trusted_proxy: bool,
+    // Distinct teams a draft with members needs.
+    pub min_teams: usize,
+    // Draws that end in a dead end before giving up on a draft.
+    pub max_draw_attempts: u32,
+    pub default_locale: String,
+    pub base_url: Option<String>,
+    pub admin_token: Option<String>,
+    pub data_file: Option<String>,
+    // Where notifications waiting to be sent are kept across restarts.
+    pub outbox_file: Option<String>,
+    // Attempts to send a notification before it is given up.
+    pub max_send_attempts: u32,
+    // Base64 key the tickets are encrypted with, stored in plain if unset.
+    pub ticket_key: Option<String>,
+    // Drafts are removed this many days after their date, unless extended.
+    pub retention_days: Option<i64>,
+    // Minutes between two retention sweeps.
+    pub sweep_interval: u64,
+    // Days a deleted draft can be restored before it is purged.
+    pub trash_days: i64,
+    // Port of the WebSocket server for the draw ceremony, off if unset.
+    pub ceremony_port: Option<u16>,
+    // Milliseconds between two names shown in the draw ceremony.
+    pub ceremony_delay: u64,
+    // Live event streams of draft pages open at a time, each one holds a
+    // worker thread.
+    pub max_event_streams: usize,
+    pub log_level: String,
+    pub log_format: String,
+    // HSTS and an enforced content security policy, off for development.
+    pub strict_security: bool,
+    pub smtp: Option<SmtpConfig>,
+    pub push: Option<PushConfig>,
+    pub google: Option<GoogleConfig>,
+}
+
+impl Default for AppConfig {
+    fn default() -> AppConfig {
+        AppConfig {
+            img_dir: "img".to_string(),
+            css_dir: "css".to_string(),
+            media_dir: "media".to_string(),
+            max_members: 500,
+            max_title_length: 100,
+            max_drafts: None,
+            max_drafts_per_hour: None,
+            trusted_proxy: false,
+            min_teams: 2,
+            max_draw_attempts: 100,
+            default_locale: i18n::DEFAULT_LOCALE.code().to_string(),
+            base_url: None,
+            admin_token: None,
+            data_file: None,
+            outbox_file: None,
+            max_send_attempts: 8,
+            ticket_key: None,
+            retention_days: None,
+            sweep_interval: 60,
+            trash_days: 30,
+            ceremony_port: None,
+            ceremony_delay: 2000,
+            max_event_streams: 8,
+            log_level: "info".to_string(),
+            log_format: "text".to_string(),
+            strict_security: true,
+            smtp: None,
+            push: None,
+            google: None,
+        }
+    }
+}
+
+fn var<T: FromStr>(name: &str) -> Option<T> {
+    env::var(name).ok().and_then(|value| value.parse().ok())
+}
+
+impl AppConfig {
+    // Falls back to the defaults on an invalid table, returning the error
+    // to be logged once logging is set up.
+    pub fn load(config: &Config) -> (AppConfig, Option<String>) {
+        let (mut app, error) = match config.get_table("app") {
+            Ok(table) => match Value::Table(table.clone()).try_into::<AppConfig>() {
+                Ok(app) => (app, None),
+                Err(e) => (AppConfig::default(), Some(e.to_string())),
+            },
+            Err(_) => (AppConfig::default(), None),
+        };
+        app.override_from_env();
+        (app, error)
+    }
+
+    fn override_from_env(&mut self) {
+        if let Some(dir) = var("APP_IMG_DIR") {
+            self.img_dir = dir;
+        }
+        if let Some(dir) = var("APP_CSS_DIR") {
+            self.css_dir = dir;
+        }
+        if let Some(dir) = var("APP_MEDIA_DIR") {
+            self.media_dir = dir;
+        }
+        if let Some(max) = var("APP_MAX_MEMBERS") {
+            self.max_members = max;
+        }
+        if let Some(length) = var("APP_MAX_TITLE_LENGTH") {
+            self.max_title_length = length;
+        }
+        if let Some(max) = var("APP_MAX_DRAFTS") {
+            self.max_drafts = Some(max);
+        }
+        if let Some(max) = var("APP_MAX_DRAFTS_PER_HOUR") {
+            self.max_drafts_per_hour = Some(max);
+        }
+        if let Some(trusted) = var("APP_TRUSTED_PROXY") {
+            self.trusted_proxy = trusted;
+        }
+        if let Some(teams) = var("APP_MIN_TEAMS") {
+            self.min_teams = teams;
+        }
+        if let Some(attempts) = var("APP_MAX_DRAW_ATTEMPTS") {
+            self.max_draw_attempts = attempts;
+        }
+        if let Some(locale) = var("APP_DEFAULT_LOCALE") {
+            self.default_locale = locale;
+        }
+        if let Some(url) = var("APP_BASE_URL") {
+            self.base_url = Some(url);
+        }
+        if let Some(token) = var("APP_ADMIN_TOKEN") {
+            self.admin_token = Some(token);
+        }
+        if let Some(file) = var("APP_DATA_FILE") {
+            self.data_file = Some(file);
+        }
+        if let Some(file) = var("APP_OUTBOX_FILE") {
+            self.outbox_file = Some(file);
+        }
+        if let Some(attempts) = var("APP_MAX_SEND_ATTEMPTS") {
+            self.max_send_attempts = attempts;
+        }
+        if let Some(key) = var("APP_TICKET_KEY") {
+            self.ticket_key = Some(key);
+        }
+        if let Some(days) = var("APP_RETENTION_DAYS") {
+            self.retention_days = Some(days);
+        }
+        if let Some(interval) = var("APP_SWEEP_INTERVAL") {
+            self.sweep_interval = interval;
+        }
+        if let Some(days) = var("APP_TRASH_DAYS") {
+            self.trash_days = days;
+        }
+        if let Some(port) = var("APP_CEREMONY_PORT") {
+            self.ceremony_port = Some(port);
+        }
+        if let Some(delay) = var("APP_CEREMONY_DELAY") {
+            self.ceremony_delay = delay;
+        }
+        if let Some(max) = var("APP_MAX_EVENT_STREAMS") {
+            self.max_event_streams = max;
+        }
+        if let Some(level) = var("APP_LOG_LEVEL") {
+            self.log_level = level;
+        }
+        if let Some(format) = var("APP_LOG_FORMAT") {
+            self.log_format = format;
+        }
+        if let Some(strict) = var("APP_STRICT_SECURITY") {
+            self.strict_security = strict;
+        }
+        if let Some(host) = var::<String>("APP_SMTP_HOST") {
+            let smtp = self.smtp.get_or_insert(SmtpConfig {
+                host: String::new(),
+                port: default_smtp_port(),
+                username: None,
+                password: None,
+                from: String::new(),
+            });
+            smtp.host = host;
+        }
+        if let Some(smtp) = self.smtp.as_mut() {
+            if let Some(port) = var("APP_SMTP_PORT") {
+                smtp.port = port;
+            }
+            if let Some(username) = var("APP_SMTP_USERNAME") {
+                smtp.username = Some(username);
+            }
+            if let Some(password) = var("APP_SMTP_PASSWORD") {
+                smtp.password = Some(password);
+            }
+            if let Some(from) = var("APP_SMTP_FROM") {
+                smtp.from = from;
+            }
+        }
+        if let Some(private_key) = var::<String>("APP_PUSH_PRIVATE_KEY") {
+            let push = self.push.get_or_insert(PushConfig {
+                private_key: String::new(),
+                public_key: String::new(),
+                subject: String::new(),
+            });
+            push.private_key = private_key;
+        }
+        if let Some(push) = self.push.as_mut() {
+            if let Some(public_key) = var("APP_PUSH_PUBLIC_KEY") {
+                push.public_key = public_key;
+            }
+            if let Some(subject) = var("APP_PUSH_SUBJECT") {
+                push.subject = subject;
+            }
+        }
+        if let Some(client_id) = var::<String>("APP_GOOGLE_CLIENT_ID") {
+            let google = self.google.get_or_insert(GoogleConfig {
+                client_id: String::new(),
+                client_secret: String::new(),
+            });
+            google.client_id = client_id;
+        }
+        if let Some(google) = self.google.as_mut() {
+            if let Some(secret) = var("APP_GOOGLE_CLIENT_SECRET") {
+                google.client_secret = secret;
+            }
+        }
+    }
+
+    pub fn default_locale(&self) -> Locale {
+        Locale::from_code(&self.default_locale).unwrap_or(i18n::DEFAULT_LOCALE)
+    }
+}
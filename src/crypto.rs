@@ -0,0 +1,106 @@
+use aes_gcm::aead::{Aead, NewAead};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use lazy_static::lazy_static;
+use rand::Rng;
+use std::sync::RwLock;
+
+// Marks a ticket as encrypted, anything else is a plain name from before a
+// key was configured.
+const PREFIX: &str = "sealed:";
+const NONCE_LENGTH: usize = 12;
+
+lazy_static! {
+    static ref CIPHER: RwLock<Option<Aes256Gcm>> = RwLock::new(None);
+}
+
+// Sets the server key, the base64 form of 32 random bytes, e.g. from
+// `openssl rand -base64 32`.
+pub fn init(key: &str) -> Result<(), String> {
+    let key = base64::decode(key.trim()).map_err(|e| e.to_string())?;
+    if key.len() != 32 {
+        return Err(format!("expected 32 bytes, got {}", key.len()));
+    }
+    *CIPHER.write().unwrap() = Some(Aes256Gcm::new(Key::from_slice(&key)));
+    Ok(())
+}
+
+// Encrypts the name of a recipient with a fresh nonce, so equal tickets
+// don't look equal. Stays readable without a key.
+pub fn seal(name: &str) -> String {
+    match CIPHER.read().unwrap().as_ref() {
+        Some(cipher) => {
+            let nonce = rand::thread_rng().gen::<[u8; NONCE_LENGTH]>();
+            let mut sealed = nonce.to_vec();
+            sealed.extend(
+                cipher
+                    .encrypt(Nonce::from_slice(&nonce), name.as_bytes())
+                    .expect("encrypting a ticket failed"),
+            );
+            format!("{}{}", PREFIX, base64::encode(sealed))
+        }
+        None => name.to_string(),
+    }
+}
+
+// The name of the recipient, or none if the ticket was sealed with another
+// or no longer configured key.
+pub fn open(ticket: &str) -> Option<String> {
+    let sealed = match ticket.strip_prefix(PREFIX) {
+        Some(sealed) => base64::decode(sealed).ok()?,
+        None => return Some(ticket.to_string()),
+    };
+    if sealed.len() < NONCE_LENGTH {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+    let cipher = CIPHER.read().unwrap();
+    let name = cipher
+        .as_ref()?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .ok()?;
+    String::from_utf8(name).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The key is global, so every test sets the same one.
+    fn init_key() {
+        init(&base64::encode([7u8; 32])).expect("a valid key");
+    }
+
+    #[test]
+    fn opens_what_it_sealed() {
+        init_key();
+        let sealed = seal("Anna");
+        assert!(sealed.starts_with(PREFIX));
+        assert!(!sealed.contains("Anna"));
+        assert_ne!(sealed, seal("Anna"), "the nonce is fresh");
+        assert_eq!(open(&sealed).as_deref(), Some("Anna"));
+    }
+
+    #[test]
+    fn rejects_tampered_tickets() {
+        init_key();
+        let sealed = seal("Anna");
+        let mut bytes = base64::decode(&sealed[PREFIX.len()..]).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert_eq!(open(&format!("{}{}", PREFIX, base64::encode(&bytes))), None);
+        bytes.truncate(NONCE_LENGTH - 1);
+        assert_eq!(open(&format!("{}{}", PREFIX, base64::encode(&bytes))), None);
+        assert_eq!(open(&format!("{}not base64", PREFIX)), None);
+    }
+
+    #[test]
+    fn opens_plain_tickets_as_they_are() {
+        assert_eq!(open("Anna").as_deref(), Some("Anna"));
+    }
+
+    #[test]
+    fn rejects_keys_of_another_length() {
+        assert!(init(&base64::encode([7u8; 16])).is_err());
+        assert!(init("not base64").is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::config::AppConfig;
+use crate::{
+    audit, events, google, mail, name_key, push, sanitize, validation, Draft, Drafts, Member,
+    Organizer,
+};
+use chrono::Utc;
+use rocket::http::Status;
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use std::thread;
+use std::time::Duration;
+
+pub use api::deadline::DeadlineRequest;
+
+// How often the scheduler looks for drafts to draw.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// Members can add themselves until the deadline the organizer set, then
+// the draft is drawn.
+pub fn open(draft: &Draft) -> bool {
+    !draft.is_drawn()
+        && !draft.is_archived()
+        && draft
+            .draw_at
+            .map_or(false, |deadline| deadline > Utc::now())
+}
+
+// Draws the drafts whose deadline passed, with the same notifications as
+// the organizer's draw. Returns how many were drawn.
+pub fn draw_due(drafts: &Drafts, config: &AppConfig, events: &events::Events) -> usize {
+    let mut drawn = 0;
+    for (id, entry) in drafts.active() {
+        let mut draft = entry.write();
+        match draft.draw_at {
+            Some(deadline) if deadline <= Utc::now() && !draft.is_drawn() => (),
+            _ => continue,
+        }
+        match draft.calculate_tickets(config.max_draw_attempts) {
+            Ok(()) => {
+                draft.record(audit::Actor::System, audit::Action::Drawn);
+                events.publish(id, events::Event::DrawComplete);
+                let messages = push::drawn(&draft, id, config);
+                let emails = mail::drawn(config, id, &draft);
+                google::create_event(config, id, &draft, drafts);
+                drop(draft);
+                push::notify(config, messages);
+                mail::notify(emails);
+                drawn += 1;
+                tracing::info!(id, "draft drawn at its deadline");
+            }
+            // Not tried again, the organizer has to draw it themselves.
+            Err(e) => {
+                draft.draw_at = None;
+                tracing::warn!(id, error = %e, "could not draw draft at its deadline");
+            }
+        }
+    }
+    drawn
+}
+
+pub fn spawn(drafts: Drafts, config: &AppConfig, events: events::Events) {
+    let config = config.clone();
+    let result = thread::Builder::new()
+        .name("deadline".to_string())
+        .spawn(move || loop {
+            draw_due(&drafts, &config, &events);
+            thread::sleep(CHECK_INTERVAL);
+        });
+    if let Err(e) = result {
+        tracing::error!(error = %e, "could not start the deadline scheduler");
+    }
+}
+
+// Sets the deadline, local to the draft's timezone, or removes it.
+fn set(
+    id: usize,
+    deadline: Option<&str>,
+    organizer: &Organizer,
+    drafts: &Drafts,
+) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    if draft.is_drawn() || draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    let deadline = match deadline.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(value) => {
+            let deadline = Draft::parse_date(value, draft.timezone)
+                .map_err(|_| Status::UnprocessableEntity)?
+                .with_timezone(&Utc);
+            if deadline <= Utc::now() || deadline > draft.date.with_timezone(&Utc) {
+                return Err(Status::UnprocessableEntity);
+            }
+            Some(deadline)
+        }
+        None => None,
+    };
+    draft.draw_at = deadline;
+    Ok(())
+}
+
+#[post("/api/draft/<id>/deadline", format = "json", data = "<request>")]
+pub fn api_set(
+    id: usize,
+    request: Json<DeadlineRequest>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    set(id, request.deadline.as_deref(), &organizer, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/deadline", data = "<request>")]
+pub fn set_deadline(
+    id: usize,
+    request: Form<DeadlineRequest>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    set(id, request.deadline.as_deref(), &organizer, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+#[derive(FromForm)]
+pub struct JoinRequest {
+    name: String,
+    team: u32,
+    email: Option<String>,
+}
+
+// Self-registration while the deadline hasn't passed.
+#[post("/draft/<id>/join", data = "<request>")]
+pub fn join(
+    id: usize,
+    request: Form<JoinRequest>,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    let request = request.into_inner();
+    let name = sanitize::line(&request.name);
+    if name.is_empty() || name.chars().count() > validation::MAX_NAME_LENGTH {
+        return Err(Status::UnprocessableEntity);
+    }
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !open(&draft) {
+        return Err(Status::Conflict);
+    }
+    if draft.members.len() >= config.max_members {
+        return Err(Status::PayloadTooLarge);
+    }
+    if draft
+        .members
+        .iter()
+        .any(|member| name_key(&member.name) == name_key(&name))
+    {
+        return Err(Status::Conflict);
+    }
+    let mut member = Member::new(name.clone(), request.team);
+    member.email = request
+        .email
+        .map(|email| sanitize::line(&email))
+        .filter(|email| !email.is_empty());
+    draft.members.insert(member);
+    draft.record(
+        audit::Actor::Participant(name.clone()),
+        audit::Action::MembersAdded {
+            names: vec![name.clone()],
+        },
+    );
+    events.publish(id, events::Event::MemberJoined { names: vec![name] });
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
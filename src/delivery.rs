@@ -0,0 +1,210 @@
+use crate::config::AppConfig;
+use crate::{mail, name_key, push, Drafts, Organizer};
+use chrono::Utc;
+use lazy_static::lazy_static;
+use rocket::http::Status;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+pub use api::delivery::{Channel, Delivery, Kind, Stage};
+
+// Deliveries kept per member, the oldest dropped first.
+const MAX_DELIVERIES: usize = 20;
+
+// Why a notification didn't go out.
+pub struct Error {
+    pub bounced: bool,
+    pub message: String,
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Error {
+        Error {
+            bounced: false,
+            message,
+        }
+    }
+}
+
+// Whom a notification is for, to track its delivery.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Target {
+    pub draft: usize,
+    pub member: String,
+    pub kind: Kind,
+}
+
+#[derive(Default)]
+struct Log {
+    next: u64,
+    // Each delivery with its number, to find it again when it is finished.
+    deliveries: HashMap<(usize, String), Vec<(u64, Delivery)>>,
+}
+
+lazy_static! {
+    // Only kept in memory like the metrics, it is for finding out why
+    // someone didn't get their ticket, not a record.
+    static ref LOG: Mutex<Log> = Mutex::new(Log::default());
+}
+
+// Records a notification about to be sent and returns its number.
+pub fn queue(target: &Target, channel: Channel) -> u64 {
+    let mut log = LOG.lock().unwrap();
+    log.next += 1;
+    let number = log.next;
+    let deliveries = log
+        .deliveries
+        .entry((target.draft, name_key(&target.member)))
+        .or_insert_with(Vec::new);
+    deliveries.push((
+        number,
+        Delivery {
+            channel,
+            kind: target.kind,
+            stage: Stage::Queued,
+            time: Utc::now(),
+            error: None,
+        },
+    ));
+    if deliveries.len() > MAX_DELIVERIES {
+        deliveries.remove(0);
+    }
+    number
+}
+
+pub fn finish(target: &Target, number: u64, result: &Result<(), Error>) {
+    let mut log = LOG.lock().unwrap();
+    let delivery = log
+        .deliveries
+        .get_mut(&(target.draft, name_key(&target.member)))
+        .and_then(|deliveries| {
+            deliveries
+                .iter_mut()
+                .find(|(queued, _)| *queued == number)
+                .map(|(_, delivery)| delivery)
+        });
+    if let Some(delivery) = delivery {
+        delivery.time = Utc::now();
+        match result {
+            Ok(()) => delivery.stage = Stage::Sent,
+            Err(e) => {
+                delivery.stage = if e.bounced {
+                    Stage::Bounced
+                } else {
+                    Stage::Failed
+                };
+                delivery.error = Some(e.message.clone());
+            }
+        }
+    }
+}
+
+// The deliveries to the member, the latest first.
+pub fn of(draft: usize, member: &str) -> Vec<Delivery> {
+    let log = LOG.lock().unwrap();
+    log.deliveries
+        .get(&(draft, name_key(member)))
+        .map(|deliveries| {
+            deliveries
+                .iter()
+                .rev()
+                .map(|(_, delivery)| delivery.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[derive(Serialize)]
+pub struct View {
+    name: String,
+    deliveries: Vec<Delivery>,
+}
+
+// The deliveries of every member, for the organizer.
+pub fn views(id: usize, names: &[String]) -> Vec<View> {
+    names
+        .iter()
+        .map(|name| View {
+            name: name.clone(),
+            deliveries: of(id, name),
+        })
+        .filter(|view| !view.deliveries.is_empty())
+        .collect()
+}
+
+#[get("/api/draft/<id>/deliveries")]
+pub fn api_list(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Json<BTreeMap<String, Vec<Delivery>>>, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    Ok(Json(
+        draft
+            .members
+            .iter()
+            .map(|member| (member.name.clone(), of(id, &member.name)))
+            .collect(),
+    ))
+}
+
+// Sends the member their ticket again, by email and push.
+fn retry(
+    id: usize,
+    name: &str,
+    organizer: &Organizer,
+    config: &AppConfig,
+    drafts: &Drafts,
+) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    let member = draft.find_member(name).ok_or(Status::NotFound)?;
+    if !draft.is_drawn() || draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    let name = member.name.clone();
+    mail::ticket(config, id, &draft, member);
+    push::notify(
+        config,
+        push::drawn(&draft, id, config)
+            .into_iter()
+            .filter(|(target, _, _)| target.member == name)
+            .collect(),
+    );
+    Ok(())
+}
+
+#[post("/api/draft/<id>/deliveries/<name>/retry")]
+pub fn api_retry(
+    id: usize,
+    name: String,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    retry(id, &name, &organizer, &config, &drafts)?;
+    Ok(Status::Accepted)
+}
+
+#[post("/draft/<id>/deliveries/<name>/retry")]
+pub fn retry_delivery(
+    id: usize,
+    name: String,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    retry(id, &name, &organizer, &config, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
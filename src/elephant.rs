@@ -0,0 +1,10 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+pub use api::elephant::{Mode, Rules};
+
+pub fn turn_order<R: Rng>(mut names: Vec<String>, rng: &mut R) -> Vec<String> {
+    names.sort();
+    names.shuffle(rng);
+    names
+}
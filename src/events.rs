@@ -0,0 +1,145 @@
+use serde::Serialize;
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+// A stream without events for this long ends, the browser reconnects.
+const IDLE: Duration = Duration::from_secs(10 * 60);
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Event {
+    MemberJoined { names: Vec<String> },
+    DrawComplete,
+    TicketViewed { name: String },
+}
+
+impl Event {
+    fn name(&self) -> &'static str {
+        match self {
+            Event::MemberJoined { .. } => "member-joined",
+            Event::DrawComplete => "draw-complete",
+            Event::TicketViewed { .. } => "ticket-viewed",
+        }
+    }
+
+    fn encode(&self) -> String {
+        let data = match self {
+            Event::MemberJoined { names } => serde_json::json!({ "names": names }),
+            Event::DrawComplete => serde_json::json!({}),
+            Event::TicketViewed { name } => serde_json::json!({ "name": name }),
+        };
+        format!("event: {}\ndata: {}\n\n", self.name(), data)
+    }
+}
+
+// Broadcasts draft events to the listeners of that draft. Clones share
+// the listeners. `None` only checks whether a listener is still there.
+#[derive(Clone)]
+pub struct Events {
+    subscribers: Arc<Mutex<Vec<(usize, Sender<Option<Event>>)>>>,
+    streams: Arc<AtomicUsize>,
+    max_streams: usize,
+}
+
+impl Events {
+    pub fn new(max_streams: usize) -> Events {
+        Events {
+            subscribers: Arc::default(),
+            streams: Arc::default(),
+            max_streams,
+        }
+    }
+
+    pub fn receiver(&self, id: usize) -> Receiver<Option<Event>> {
+        let (sender, receiver) = mpsc::channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push((id, sender));
+        }
+        receiver
+    }
+
+    // `None` once `max_streams` streams are open.
+    pub fn subscribe(&self, id: usize) -> Option<EventStream> {
+        if self.streams.fetch_add(1, Ordering::SeqCst) >= self.max_streams {
+            self.streams.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        Some(EventStream {
+            receiver: self.receiver(id),
+            buffer: Vec::new(),
+            position: 0,
+            last_event: Instant::now(),
+            streams: self.streams.clone(),
+        })
+    }
+
+    pub fn publish(&self, id: usize, event: Event) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|(subscribed, sender)| {
+                *subscribed != id || sender.send(Some(event.clone())).is_ok()
+            });
+        }
+    }
+
+    // Drops the listeners that are gone, also of drafts without events.
+    fn prune(&self) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|(_, sender)| sender.send(None).is_ok());
+        }
+    }
+}
+
+pub fn spawn(events: Events) {
+    thread::spawn(move || loop {
+        thread::sleep(PRUNE_INTERVAL);
+        events.prune();
+    });
+}
+
+// Body of a `text/event-stream` response. A comment is sent when nothing
+// happened for a while, which keeps proxies from closing the connection
+// and ends the stream once the client is gone. After `IDLE` the stream
+// ends by itself, to give the worker thread back.
+pub struct EventStream {
+    receiver: Receiver<Option<Event>>,
+    buffer: Vec<u8>,
+    position: usize,
+    last_event: Instant,
+    streams: Arc<AtomicUsize>,
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.streams.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Read for EventStream {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.buffer.len() {
+            if self.last_event.elapsed() >= IDLE {
+                return Ok(0);
+            }
+            let text = match self.receiver.recv_timeout(KEEP_ALIVE) {
+                Ok(Some(event)) => {
+                    self.last_event = Instant::now();
+                    event.encode()
+                }
+                Ok(None) | Err(RecvTimeoutError::Timeout) => ": keep-alive\n\n".to_string(),
+                Err(RecvTimeoutError::Disconnected) => return Ok(0),
+            };
+            self.buffer = text.into_bytes();
+            self.position = 0;
+        }
+        let length = out.len().min(self.buffer.len() - self.position);
+        out[..length].copy_from_slice(&self.buffer[self.position..self.position + length]);
+        self.position += length;
+        Ok(length)
+    }
+}
@@ -0,0 +1,61 @@
+use crate::Draft;
+use rocket::http::ContentType;
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use std::io::Cursor;
+
+pub struct Download {
+    pub filename: String,
+    pub content_type: ContentType,
+    pub body: Vec<u8>,
+}
+
+impl<'r> Responder<'r> for Download {
+    fn respond_to(self, _: &Request) -> response::Result<'r> {
+        Response::build()
+            .header(self.content_type)
+            .raw_header(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", self.filename),
+            )
+            .sized_body(Cursor::new(self.body))
+            .ok()
+    }
+}
+
+pub fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+pub fn filename(draft: &Draft, extension: &str) -> String {
+    format!("{}.{}", sanitize(&draft.title), extension)
+}
+
+pub fn csv(draft: &Draft, assignments: bool) -> Result<Vec<u8>, csv::Error> {
+    let mut members = draft.members.iter().collect::<Vec<_>>();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    if assignments {
+        writer.write_record(&["name", "team", "email", "pool", "ticket"])?;
+    } else {
+        writer.write_record(&["name", "team", "email", "pool"])?;
+    }
+    for member in members {
+        let team = member.team.to_string();
+        let email = member.email.clone().unwrap_or_default();
+        let pool = member.pool.clone().unwrap_or_default();
+        if assignments {
+            let ticket = member.recipient().unwrap_or_default();
+            writer.write_record(&[member.name.as_str(), &team, &email, &pool, &ticket])?;
+        } else {
+            writer.write_record(&[member.name.as_str(), &team, &email, &pool])?;
+        }
+    }
+    match writer.into_inner() {
+        Ok(body) => Ok(body),
+        Err(e) => Err(e.into_error().into()),
+    }
+}
@@ -0,0 +1,97 @@
+use crate::config::AppConfig;
+use crate::verify::{self, Violation};
+use crate::{
+    audit, avoid, crypto, elephant, events, google, invite, mail, push, Drafts, Member, Organizer,
+};
+use rocket::http::Status;
+use rocket::response::status;
+use rocket::State;
+use rocket_contrib::json::Json;
+use std::collections::{BTreeMap, HashSet};
+
+// Takes the tickets of a draw done elsewhere, e.g. names drawn from a hat,
+// as `{"giver": "recipient", ...}` for every member. They have to keep the
+// same rules as a draw here, so nobody gives to themselves, their own team
+// or across pools, and everyone gives and receives exactly once. Answers
+// the violations with 422 otherwise.
+#[post("/api/draft/<id>/assignments", format = "json", data = "<assignments>")]
+pub fn api_import(
+    id: usize,
+    assignments: Json<BTreeMap<String, String>>,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+) -> Result<Status, status::Custom<Json<verify::Report>>> {
+    let rejected = |status: Status| status::Custom(status, Json(verify::Report::new(Vec::new())));
+    let entry = drafts.get(id).ok_or_else(|| rejected(Status::NotFound))?;
+    let _operation = entry
+        .operation()
+        .ok_or_else(|| rejected(Status::Conflict))?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(rejected(Status::Forbidden));
+    }
+    // The tickets of the zero-knowledge mode are locked with passphrases
+    // the members set for a draw here.
+    if draft.is_drawn()
+        || draft.is_archived()
+        || draft.passphrases
+        || draft.mode == elephant::Mode::WhiteElephant
+        || invite::pending(&draft)
+    {
+        return Err(rejected(Status::Conflict));
+    }
+
+    let mut violations = Vec::new();
+    let mut tickets = BTreeMap::new();
+    for (giver, recipient) in assignments.into_inner() {
+        match draft.find_member(&giver) {
+            // The same member under two spellings gives twice.
+            Some(member) if tickets.contains_key(&member.name) => {
+                violations.push(Violation::UnknownGiver { giver })
+            }
+            Some(member) => {
+                tickets.insert(member.name.clone(), recipient);
+            }
+            None => violations.push(Violation::UnknownGiver { giver }),
+        }
+    }
+    let mut assigned = draft.clone();
+    assigned.members = draft
+        .members
+        .iter()
+        .map(|member| {
+            let mut member = member.clone();
+            member.ticket = tickets.get(&member.name).map(|recipient| {
+                // Stored under the name as the recipient is called.
+                let name = draft
+                    .find_member(recipient)
+                    .map_or(recipient.as_str(), |recipient| recipient.name.as_str());
+                crypto::seal(name)
+            });
+            member
+        })
+        .collect::<HashSet<Member>>();
+    violations.extend(verify::check(&assigned));
+    if !violations.is_empty() {
+        return Err(status::Custom(
+            Status::UnprocessableEntity,
+            Json(verify::Report::new(violations)),
+        ));
+    }
+
+    let violated = avoid::violated(&assigned, &assigned.members);
+    draft.members = assigned.members;
+    draft.violated = violated;
+    draft.record(audit::Actor::Organizer, audit::Action::Assigned);
+    events.publish(id, events::Event::DrawComplete);
+    let messages = push::drawn(&draft, id, &config);
+    let emails = mail::drawn(&config, id, &draft);
+    google::create_event(&config, id, &draft, &drafts);
+    drop(draft);
+    push::notify(&config, messages);
+    mail::notify(emails);
+    tracing::info!(draft = id, "tickets imported");
+    Ok(Status::NoContent)
+}
@@ -0,0 +1,77 @@
+use crate::i18n::Locale;
+use crate::{qr, Draft, Drafts};
+use chrono::Utc;
+use rocket::http::ContentType;
+use rocket::response::content::Content;
+use rocket::State;
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn entry(id: usize, draft: &Draft, origin: &qr::Origin, locale: Locale) -> String {
+    let key = draft.slug.clone().unwrap_or_else(|| id.to_string());
+    let url = origin.draft_url(&key);
+    let date = locale.format_date(&draft.local_date(), true);
+    format!(
+        "<entry>\n<id>{url}</id>\n<title>{title}</title>\n<link href=\"{url}\"/>\n\
+         <updated>{updated}</updated>\n<summary>{summary}</summary>\n</entry>\n",
+        url = escape_xml(&url),
+        title = escape_xml(&draft.title),
+        updated = draft.created.to_rfc3339(),
+        summary = escape_xml(&format!(
+            "{} {} ({})",
+            locale.text("feed_date"),
+            date,
+            draft.timezone
+        )),
+    )
+}
+
+// An Atom feed of the upcoming drafts, soonest first. With the organizer
+// token of a draft, only the drafts created with that token are listed,
+// as feed readers can't send the organizer cookies.
+#[get("/feed.xml?<token>")]
+pub fn show(
+    token: Option<String>,
+    locale: Locale,
+    origin: qr::Origin,
+    drafts: State<Drafts>,
+) -> Content<String> {
+    let mut upcoming = drafts
+        .active()
+        .into_iter()
+        .map(|(id, entry)| (id, entry.read().clone()))
+        .filter(|(_, draft)| draft.is_upcoming())
+        .filter(|(_, draft)| {
+            token.as_ref().map_or(true, |token| {
+                !draft.organizer_token.is_empty() && draft.organizer_token == *token
+            })
+        })
+        .collect::<Vec<(usize, Draft)>>();
+    upcoming.sort_by_key(|(_, draft)| draft.date);
+    let updated = upcoming
+        .iter()
+        .map(|(_, draft)| draft.created)
+        .max()
+        .unwrap_or_else(Utc::now);
+    let entries = upcoming
+        .iter()
+        .map(|(id, draft)| entry(*id, draft, &origin, locale))
+        .collect::<String>();
+    let feed = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n\
+         <id>{url}</id>\n<title>{title}</title>\n<link rel=\"self\" href=\"{url}\"/>\n\
+         <author><name>Weihnachts Wichtel</name></author>\n<updated>{updated}</updated>\n{entries}</feed>\n",
+        url = escape_xml(&origin.feed_url()),
+        title = escape_xml(locale.text("feed_title")),
+        updated = updated.to_rfc3339(),
+        entries = entries,
+    );
+    Content(ContentType::new("application", "atom+xml"), feed)
+}
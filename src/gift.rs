@@ -0,0 +1,58 @@
+use crate::{audit, Draft, Drafts};
+use chrono::Utc;
+use rocket::http::Status;
+use rocket::response::Redirect;
+use rocket::State;
+
+// Marks the gift of the member with the token as received. Only possible
+// once the exchange took place.
+fn confirm(id: usize, key: &str, drafts: &Drafts) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    let member = draft
+        .member_by_token(key)
+        .cloned()
+        .ok_or(Status::NotFound)?;
+    if !draft.is_drawn() || !draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    if member.received.is_some() {
+        return Ok(());
+    }
+    let mut member = draft.members.take(&member).ok_or(Status::NotFound)?;
+    member.received = Some(Utc::now());
+    let name = member.name.clone();
+    draft.members.insert(member);
+    draft.record(
+        audit::Actor::Participant(name.clone()),
+        audit::Action::GiftReceived { name },
+    );
+    Ok(())
+}
+
+#[post("/api/draft/<id>/ticket/<key>/received")]
+pub fn api_confirm(id: usize, key: String, drafts: State<Drafts>) -> Result<Status, Status> {
+    confirm(id, &key, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/ticket/<key>/received")]
+pub fn confirm_received(id: usize, key: String, drafts: State<Drafts>) -> Result<Redirect, Status> {
+    confirm(id, &key, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}/ticket/{}", id, key)))
+}
+
+// Members still waiting for their gift after the exchange, by name.
+pub fn outstanding(draft: &Draft) -> Vec<String> {
+    if !draft.is_drawn() || !draft.is_archived() {
+        return Vec::new();
+    }
+    let mut names = draft
+        .members
+        .iter()
+        .filter(|member| member.received.is_none())
+        .map(|member| member.name.clone())
+        .collect::<Vec<String>>();
+    names.sort();
+    names
+}
@@ -0,0 +1,247 @@
+use crate::config::{AppConfig, GoogleConfig};
+use crate::i18n::Locale;
+use crate::{calendar, crypto, generate_token, Draft, Drafts, Organizer};
+use chrono::Duration;
+use rocket::http::uri::Uri;
+use rocket::http::{Cookie, Cookies, Status};
+use rocket::response::Redirect;
+use rocket::State;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::thread;
+
+const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const EVENTS_URL: &str = "https://www.googleapis.com/calendar/v3/calendars/primary/events";
+const SCOPE: &str = "https://www.googleapis.com/auth/calendar.events";
+const STATE_COOKIE: &str = "google-state";
+// The exchange has no end, the event lasts this long.
+const EVENT_HOURS: i64 = 2;
+
+// The organizer's grant to create the event of a draft in their calendar.
+// The refresh token is sealed like the tickets.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Grant {
+    refresh_token: String,
+    // Id of the created event, so a redraw doesn't create a second one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    event: Option<String>,
+}
+
+fn redirect_uri(config: &AppConfig) -> Option<String> {
+    let base_url = config.base_url.as_ref()?;
+    Some(format!("{}/oauth/google", base_url.trim_end_matches('/')))
+}
+
+// Whether the integration is configured.
+pub fn available(config: &AppConfig) -> bool {
+    config.google.is_some() && config.base_url.is_some()
+}
+
+#[derive(Deserialize)]
+struct Tokens {
+    access_token: String,
+    refresh_token: Option<String>,
+}
+
+fn tokens(google: &GoogleConfig, params: &[(&str, &str)]) -> Result<Tokens, String> {
+    let mut form = vec![
+        ("client_id", google.client_id.as_str()),
+        ("client_secret", google.client_secret.as_str()),
+    ];
+    form.extend_from_slice(params);
+    ureq::post(TOKEN_URL)
+        .send_form(&form)
+        .map_err(|e| e.to_string())?
+        .into_json()
+        .map_err(|e| e.to_string())
+}
+
+// Title, date and description like the iCalendar file, with every member
+// who gave an email address invited.
+fn event(draft: &Draft, locale: Locale) -> serde_json::Value {
+    let timezone = draft.timezone.name();
+    let attendees = draft
+        .members
+        .iter()
+        .filter_map(|member| member.email.as_ref())
+        .filter(|email| !email.trim().is_empty())
+        .map(|email| json!({ "email": email.trim() }))
+        .collect::<Vec<serde_json::Value>>();
+    json!({
+        "summary": draft.title,
+        "description": calendar::description(draft, locale),
+        "start": { "dateTime": draft.date.to_rfc3339(), "timeZone": timezone },
+        "end": {
+            "dateTime": (draft.date + Duration::hours(EVENT_HOURS)).to_rfc3339(),
+            "timeZone": timezone,
+        },
+        "attendees": attendees,
+    })
+}
+
+#[derive(Deserialize)]
+struct Created {
+    id: String,
+}
+
+fn insert_event(
+    google: &GoogleConfig,
+    grant: &Grant,
+    event: serde_json::Value,
+) -> Result<String, String> {
+    let refresh_token =
+        crypto::open(&grant.refresh_token).ok_or_else(|| "unreadable refresh token".to_string())?;
+    let access_token = tokens(
+        google,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &refresh_token),
+        ],
+    )?
+    .access_token;
+    let created: Created = ureq::post(EVENTS_URL)
+        .query("sendUpdates", "all")
+        .set("Authorization", &format!("Bearer {}", access_token))
+        .send_json(event)
+        .map_err(|e| e.to_string())?
+        .into_json()
+        .map_err(|e| e.to_string())?;
+    Ok(created.id)
+}
+
+// Creates the event of a drawn draft whose organizer connected their
+// calendar, in the background so the draw doesn't wait for Google.
+pub fn create_event(config: &AppConfig, id: usize, draft: &Draft, drafts: &Drafts) {
+    let (google, grant) = match (&config.google, &draft.google) {
+        (Some(google), Some(grant)) if grant.event.is_none() => (google.clone(), grant.clone()),
+        _ => return,
+    };
+    let event = event(draft, config.default_locale());
+    let drafts = drafts.clone();
+    thread::spawn(move || match insert_event(&google, &grant, event) {
+        Ok(event) => {
+            if let Some(entry) = drafts.get(id) {
+                if let Some(grant) = entry.write().google.as_mut() {
+                    grant.event = Some(event);
+                }
+            }
+            tracing::info!(id, "google calendar event created");
+        }
+        Err(e) => tracing::warn!(
+            id,
+            error = e.as_str(),
+            "could not create google calendar event"
+        ),
+    });
+}
+
+// Sends the organizer to Google to grant access to their calendar. The
+// state ties the answer to this browser and draft.
+#[get("/draft/<id>/google")]
+pub fn connect(
+    id: usize,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+    mut cookies: Cookies,
+) -> Result<Redirect, Status> {
+    let google = config.google.as_ref().ok_or(Status::NotFound)?;
+    let redirect_uri = redirect_uri(&config).ok_or(Status::NotFound)?;
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    if !organizer.owns(&entry.read()) {
+        return Err(Status::Forbidden);
+    }
+    let state = format!("{}.{}", id, generate_token());
+    cookies.add(
+        Cookie::build(STATE_COOKIE, state.clone())
+            .path("/")
+            .http_only(true)
+            .finish(),
+    );
+    Ok(Redirect::to(format!(
+        "{}?client_id={}&redirect_uri={}&response_type=code&scope={}&access_type=offline&prompt=consent&state={}",
+        AUTH_URL,
+        Uri::percent_encode(&google.client_id),
+        Uri::percent_encode(&redirect_uri),
+        Uri::percent_encode(SCOPE),
+        Uri::percent_encode(&state),
+    )))
+}
+
+#[get("/oauth/google?<code>&<state>")]
+pub fn callback(
+    code: Option<String>,
+    state: String,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+    mut cookies: Cookies,
+) -> Result<Redirect, Status> {
+    let expected = cookies
+        .get(STATE_COOKIE)
+        .map(|cookie| cookie.value().to_string());
+    cookies.remove(Cookie::build(STATE_COOKIE, "").path("/").finish());
+    if expected.as_deref() != Some(state.as_str()) {
+        return Err(Status::BadRequest);
+    }
+    let id = state
+        .split('.')
+        .next()
+        .and_then(|id| id.parse::<usize>().ok())
+        .ok_or(Status::BadRequest)?;
+    // Without a code the organizer declined.
+    let code = match code {
+        Some(code) => code,
+        None => return Ok(Redirect::to(format!("/draft/{}", id))),
+    };
+    let google = config.google.as_ref().ok_or(Status::NotFound)?;
+    let redirect_uri = redirect_uri(&config).ok_or(Status::NotFound)?;
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    if !organizer.owns(&entry.read()) {
+        return Err(Status::Forbidden);
+    }
+    let refresh_token = tokens(
+        google,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", &redirect_uri),
+        ],
+    )
+    .map_err(|e| {
+        tracing::warn!(
+            id,
+            error = e.as_str(),
+            "could not redeem google authorization"
+        );
+        Status::BadGateway
+    })?
+    .refresh_token
+    .ok_or(Status::BadGateway)?;
+    let mut draft = entry.write();
+    draft.google = Some(Grant {
+        refresh_token: crypto::seal(&refresh_token),
+        event: None,
+    });
+    if draft.is_drawn() {
+        create_event(&config, id, &draft, &drafts);
+    }
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+// Forgets the grant. An event created already stays in the calendar.
+#[post("/draft/<id>/google/delete")]
+pub fn disconnect(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    draft.google = None;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
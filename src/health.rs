@@ -0,0 +1,76 @@
+use crate::config::AppConfig;
+use rocket::http::Status;
+use rocket::response::status;
+use rocket::State;
+use rocket_contrib::json::Json;
+use rocket_contrib::templates::Metadata;
+use serde::Serialize;
+use std::path::Path;
+
+const TEMPLATES: &[&str] = &[
+    "app",
+    "index",
+    "archive",
+    "reveal",
+    "invitation",
+    "series",
+    "draft",
+    "draft_white_elephant",
+    "draft_insertion",
+    "draft_share",
+    "ticket",
+    "ticket_locked",
+    "ticket_request",
+    "polls",
+    "admin",
+    "500",
+    "limited",
+];
+
+#[derive(Serialize)]
+pub struct Health {
+    status: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct Readiness {
+    status: &'static str,
+    storage: bool,
+    templates: bool,
+}
+
+#[get("/healthz")]
+pub fn healthz() -> Json<Health> {
+    Json(Health { status: "ok" })
+}
+
+pub fn storage_ready(config: &AppConfig) -> bool {
+    match &config.data_file {
+        Some(path) => match Path::new(path).parent() {
+            Some(dir) if dir != Path::new("") => dir.is_dir(),
+            _ => true,
+        },
+        None => true,
+    }
+}
+
+#[get("/readyz")]
+pub fn readyz(metadata: Metadata, config: State<AppConfig>) -> status::Custom<Json<Readiness>> {
+    let storage = storage_ready(&config);
+    let templates = TEMPLATES
+        .iter()
+        .all(|name| metadata.contains_template(name));
+    let (status, text) = if storage && templates {
+        (Status::Ok, "ok")
+    } else {
+        (Status::ServiceUnavailable, "unavailable")
+    };
+    status::Custom(
+        status,
+        Json(Readiness {
+            status: text,
+            storage,
+            templates,
+        }),
+    )
+}
@@ -0,0 +1,956 @@
+use crate::config::AppConfig;
+use chrono::{DateTime, TimeZone};
+use chrono_tz::Tz;
+use rocket::request::{self, FromRequest, Request};
+use rocket::State;
+use rocket_contrib::templates::tera;
+use rocket_contrib::templates::Template;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    De,
+    En,
+}
+
+pub const DEFAULT_LOCALE: Locale = Locale::De;
+
+impl Locale {
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::De => "de",
+            Locale::En => "en",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Locale> {
+        let primary = code.split(|c| c == '-' || c == '_').next().unwrap_or("");
+        match primary.trim().to_lowercase().as_str() {
+            "de" => Some(Locale::De),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    // Picks the supported language with the highest quality value,
+    // e.g. from "en-US,en;q=0.9,de;q=0.8".
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut languages = header
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let locale = Locale::from_code(parts.next()?)?;
+                let quality = parts
+                    .find_map(|part| part.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                Some((locale, quality))
+            })
+            .collect::<Vec<(Locale, f32)>>();
+        languages.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        languages.first().map(|(locale, _)| *locale)
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::De => DE,
+            Locale::En => EN,
+        }
+    }
+
+    pub fn text(self, key: &str) -> &'static str {
+        match self.catalog().iter().find(|(k, _)| *k == key) {
+            Some((_, text)) => text,
+            None => match DEFAULT_LOCALE.catalog().iter().find(|(k, _)| *k == key) {
+                Some((_, text)) => text,
+                None => "",
+            },
+        }
+    }
+
+    // A date the way the locale writes it, with or without the time.
+    pub fn format_date<T: TimeZone>(self, date: &DateTime<T>, time: bool) -> String
+    where
+        T::Offset: std::fmt::Display,
+    {
+        let format = match (self, time) {
+            (Locale::De, false) => "%d.%m.%Y",
+            (Locale::De, true) => "%d.%m.%Y, %H:%M Uhr",
+            (Locale::En, false) => "%b %-d, %Y",
+            (Locale::En, true) => "%b %-d, %Y, %-I:%M %p",
+        };
+        date.format(format).to_string()
+    }
+
+    pub fn texts(self) -> HashMap<&'static str, &'static str> {
+        let mut texts = DEFAULT_LOCALE
+            .catalog()
+            .iter()
+            .cloned()
+            .collect::<HashMap<_, _>>();
+        texts.extend(self.catalog().iter().cloned());
+        texts
+    }
+}
+
+// The language as the API names it, see `api::language`.
+impl From<api::language::Language> for Locale {
+    fn from(language: api::language::Language) -> Locale {
+        match language {
+            api::language::Language::De => Locale::De,
+            api::language::Language::En => Locale::En,
+        }
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Locale {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        let cookie = request
+            .cookies()
+            .get("lang")
+            .and_then(|cookie| Locale::from_code(cookie.value()));
+        let header = request
+            .headers()
+            .get_one("Accept-Language")
+            .and_then(Locale::from_accept_language);
+        let default = match request.guard::<State<AppConfig>>() {
+            request::Outcome::Success(config) => config.default_locale(),
+            _ => DEFAULT_LOCALE,
+        };
+        request::Outcome::Success(cookie.or(header).unwrap_or(default))
+    }
+}
+
+// Renders a template with the catalog of the locale available as `t`
+// and the language code as `lang`.
+pub fn render<C: Serialize>(name: &'static str, locale: Locale, context: C) -> Template {
+    let mut context = match serde_json::to_value(context) {
+        Ok(Value::Object(context)) => context,
+        _ => serde_json::Map::new(),
+    };
+    context.insert("lang".to_string(), Value::from(locale.code()));
+    context.insert(
+        "t".to_string(),
+        serde_json::to_value(locale.texts()).unwrap_or(Value::Null),
+    );
+    Template::render(name, Value::Object(context))
+}
+
+// The `localdate` filter: writes an RFC 3339 date the way the locale
+// `lang` does, in the timezone `tz` if given, e.g.
+// `{{ draft.date | localdate(lang=lang, tz=draft.timezone) }}`. With
+// `time=false` only the day is written.
+pub fn date_filter(value: Value, args: HashMap<String, Value>) -> tera::Result<Value> {
+    let date = value
+        .as_str()
+        .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+        .ok_or("localdate expects an RFC 3339 date")?;
+    let locale = args
+        .get("lang")
+        .and_then(Value::as_str)
+        .and_then(Locale::from_code)
+        .unwrap_or(DEFAULT_LOCALE);
+    let time = args.get("time").and_then(Value::as_bool).unwrap_or(true);
+    let timezone = args
+        .get("tz")
+        .and_then(Value::as_str)
+        .and_then(|tz| tz.parse::<Tz>().ok());
+    let text = match timezone {
+        Some(timezone) => locale.format_date(&date.with_timezone(&timezone), time),
+        None => locale.format_date(&date, time),
+    };
+    Ok(Value::String(text))
+}
+
+const DE: &[(&str, &str)] = &[
+    ("nav_home", "Home"),
+    ("nav_new_draft", "Neue Losbox"),
+    ("cancel", "Abbrechen"),
+    ("back", "Gehe zurück"),
+    ("error_500_title", "500 Interner Serverfehler"),
+    ("error_500_heading", "Fehlercode 500"),
+    ("error_500_subtitle", "Interner Serverfehler"),
+    ("error_500_text", "Leider konnte Ihre Anfrage zur Zeit nicht bearbeitet werden."),
+    ("error_500_retry", "Bitte versuchen Sie es später erneut."),
+    ("error_request_id", "Bitte geben Sie bei Rückfragen diese Kennung an"),
+    ("limit_title", "Zu viele neue Losboxen"),
+    ("limit_address", "Von Ihrer Adresse wurden in der letzten Stunde zu viele Losboxen angelegt. Bitte versuchen Sie es in {minutes} Minuten erneut."),
+    ("limit_full", "Auf dieser Instanz können gerade keine weiteren Losboxen angelegt werden. Bitte versuchen Sie es später erneut."),
+    ("index_title", "Weihnachts Wichtel"),
+    ("index_welcome", "Willkommen beim Wichteln, schaue doch weiter unten, ob"),
+    ("index_welcome_started", "das Losverfahren bereits gestartet wurde."),
+    ("index_welcome_create", "Ansonsten erstelle doch selber eines unter 'Neue Losbox'."),
+    ("index_drafts", "Losboxen"),
+    ("draft_draw", "Auslosen"),
+    ("draft_calendar", "Zum Kalender hinzufügen"),
+    ("calendar_description", "Wichteln mit: {names}"),
+    ("draft_import", "Teilnehmer importieren"),
+    ("draft_export_csv", "CSV Export"),
+    ("draft_export_json", "JSON Export"),
+    ("draft_cards", "Karten drucken"),
+    ("draft_bundle", "Alle Lose als ZIP"),
+    ("draft_labels", "Adressetiketten"),
+    ("draft_distribute", "Lose verteilen"),
+    ("draft_not_found", "Losbox nicht gefunden"),
+    ("draft_not_found_manual", "Haben Sie die URL manuell eingegeben?"),
+    ("draft_not_found_hint", "Versuchen Sie über die Hauptseite auf Ihre gewünschte Losbox zu navigieren."),
+    ("insertion_title", "Neue Losbox"),
+    ("insertion_heading", "Neue Losbox zusammenschnüren"),
+    ("insertion_label_title", "Titel"),
+    ("insertion_label_date", "Datum"),
+    ("insertion_label_members", "Teilnehmer"),
+    ("insertion_placeholder", "Text Eingabe..."),
+    ("insertion_help_name", "Dein Name"),
+    ("insertion_help_team", "Deine Teamnummer"),
+    ("insertion_submit", "Absenden"),
+    ("insertion_label_pools", "Gruppen"),
+    ("insertion_help_pool", "Gruppe (optional)"),
+    ("insertion_help_pool_name", "Name der Gruppe"),
+    ("insertion_help_pool_budget", "Budget in €"),
+    ("pool_budget", "Budget"),
+    ("insertion_label_budget", "Budget"),
+    ("insertion_help_budget", "Budget in € für alle ohne eigenes Budget (optional)"),
+    ("insertion_label_team_budgets", "Budgets einzelner Teams"),
+    ("insertion_help_budget_team", "Teamnummer"),
+    ("ticket_budget", "Budget für das Geschenk"),
+    ("error_invalid_budget", "Ein Budget muss größer als null sein"),
+    ("error_unknown_budget_team", "Team {team} hat ein Budget, aber keine Teilnehmer"),
+    ("error_duplicate_pool", "Die Gruppe {name} wurde doppelt angegeben"),
+    ("error_invalid_email_template", "Die E-Mail-Vorlage {template} enthält Fehler oder unbekannte Variablen"),
+    ("error_passphrase_missing", "{name} hat noch kein Passwort für das Los gesetzt"),
+    ("insertion_label_passphrases", "Lose mit Passwörtern verschlüsseln"),
+    ("insertion_help_passphrases", "Jeder setzt vor der Auslosung über seinen Link ein Passwort, ohne das niemand, auch nicht der Organisator, das Los lesen kann"),
+    ("insertion_label_blind", "Lose vor dem Organisator verbergen"),
+    ("insertion_help_email", "E-Mail (optional)"),
+    ("insertion_label_invite", "Teilnehmer per E-Mail einladen"),
+    ("insertion_help_invite", "Teilnehmer mit E-Mail sagen vor der Auslosung zu oder ab und füllen ihren Wunschzettel aus, wer absagt, wird nicht ausgelost"),
+    ("invite_title", "Einladung"),
+    ("invite_question", "bist du dabei?"),
+    ("invite_accepted", "du bist dabei"),
+    ("invite_wishlist", "Wunschzettel"),
+    ("invite_wishlist_help", "Was wünschst du dir? Dein Wichtel sieht es mit seinem Los"),
+    ("invite_accept", "Zusagen"),
+    ("invite_decline", "Absagen"),
+    ("invite_save", "Wunschzettel speichern"),
+    ("invite_closed", "Die Auslosung hat schon stattgefunden"),
+    ("invite_ticket", "Zum Los"),
+    ("invite_status_accepted", "Zugesagt"),
+    ("invite_status_pending", "Noch offen"),
+    ("invite_status_declined", "Abgesagt"),
+    ("ticket_wishlist", "Wunschzettel"),
+    ("mail_invite_subject", "Einladung zu {title}"),
+    ("mail_invite_body", "Hallo {name},\n\ndu bist zum Wichteln {title} am {date} eingeladen. Hier kannst du zu- oder absagen und deinen Wunschzettel ausfüllen: {url}"),
+    ("audit_invitation_accepted", "{name} hat zugesagt"),
+    ("audit_invitation_declined", "{name} hat abgesagt"),
+    ("preset_label", "Vorlagen"),
+    ("preset_couples", "Paare"),
+    ("preset_couples_help", "Jedes Paar ist ein Team, Partner ziehen sich nicht gegenseitig"),
+    ("preset_office", "Büro mit Abteilungen"),
+    ("preset_office_help", "Jede Abteilung ist ein Team, jeder beschenkt jemanden aus einer anderen Abteilung"),
+    ("preset_family", "Familie mit Kindern"),
+    ("preset_family_help", "Jeder Haushalt ist ein Team, die Kinder losen unter sich mit eigenem Budget"),
+    ("preset_family_kids", "Kinder"),
+    ("insertion_help_blind", "Lose öffnen sich nur über die persönlichen Links der Teilnehmer, der Organisator sieht nur den Fortschritt"),
+    ("blind_notice", "Lose öffnen sich nur über den persönlichen Link, den jeder Teilnehmer erhalten hat"),
+    ("passphrase_set", "setz ein Passwort für dein Los"),
+    ("passphrase_set_again", "dein Passwort ist gesetzt, du kannst es bis zur Auslosung ändern"),
+    ("passphrase_help", "Nur mit diesem Passwort lässt sich dein Los nach der Auslosung öffnen. Vergessen lässt es sich nicht zurücksetzen."),
+    ("passphrase_label", "Passwort"),
+    ("passphrase_repeat", "Passwort wiederholen"),
+    ("passphrase_mismatch", "Die Passwörter stimmen nicht überein"),
+    ("passphrase_save", "Passwort speichern"),
+    ("passphrase_saved", "Passwort gespeichert"),
+    ("passphrase_enter", "gib dein Passwort ein, um dein Los zu öffnen"),
+    ("passphrase_unlock", "Öffnen"),
+    ("passphrase_wrong", "Falsches Passwort"),
+    ("passphrase_waiting", "Noch ohne Passwort"),
+    ("feed_title", "Anstehende Wichteleien"),
+    ("feed_date", "Bescherung am"),
+    ("timeline_drawn", "Ausgelost"),
+    ("timeline_viewed", "Lose angesehen"),
+    ("timeline_received", "Geschenke angekommen"),
+    ("gift_outstanding", "Noch ohne Geschenk"),
+    ("gift_confirm", "Ich habe mein Geschenk bekommen"),
+    ("gift_confirmed", "Du hast bestätigt, dass dein Geschenk angekommen ist."),
+    ("thanks_heading", "Danke sagen"),
+    ("thanks_help", "Die Nachricht geht an deinen Wichtel, ohne dass du erfährst, wer es ist."),
+    ("thanks_send", "Dankeschön senden"),
+    ("thanks_update", "Dankeschön ändern"),
+    ("thanks_santa", "Dein Wichtel hat sich zu erkennen gegeben:"),
+    ("thanks_received", "Dankeschön von deinem Beschenkten"),
+    ("thanks_sign", "Mich zu erkennen geben"),
+    ("thanks_sign_help", "Dann sieht dein Beschenkter, dass das Geschenk von dir war."),
+    ("thanks_signed", "Du hast dich zu erkennen gegeben."),
+    ("mail_thanks_subject", "Ein Dankeschön zu {title}"),
+    ("mail_thanks_body", "Hallo {name},\n\ndein Beschenkter bedankt sich:\n\n{message}\n\nAuf {url} kannst du dich zu erkennen geben."),
+    ("timeline_comment", "{name} hat kommentiert"),
+    ("archive_title", "Archiv"),
+    ("archive_empty", "Noch keine vergangenen Wichteleien"),
+    ("archive_revealed", "aufgedeckt"),
+    ("archive_notice", "Diese Wichtelei liegt in der Vergangenheit und kann nicht mehr geändert werden."),
+    ("archive_reveal", "Für alle aufdecken, wer wen beschenkt hat"),
+    ("archive_pairs", "Wer wen beschenkt hat"),
+    ("archive_assignments", "Alle Zuordnungen ansehen"),
+    ("deadline_draw", "Automatische Auslosung am"),
+    ("deadline_set", "Anmeldeschluss setzen"),
+    ("deadline_help", "Bis dahin können sich Teilnehmer selbst eintragen, danach wird automatisch ausgelost."),
+    ("join_heading", "Selbst eintragen"),
+    ("join_email", "E-Mail (optional)"),
+    ("join_submit", "Eintragen"),
+    ("google_heading", "Google Kalender"),
+    ("google_help", "Nach der Auslosung wird in deinem Google Kalender ein Termin für die Wichtelei angelegt, zu dem alle Teilnehmer mit E-Mail-Adresse eingeladen werden."),
+    ("google_connect", "Google Kalender verbinden"),
+    ("google_connected", "Verbunden: der Termin wird nach der Auslosung angelegt."),
+    ("google_disconnect", "Verbindung trennen"),
+    ("series_heading", "Jedes Jahr wieder"),
+    ("series_help", "Als Reihe wird nach dem Termin automatisch die nächste Wichtelei mit denselben Mitgliedern und Teams angelegt. Dabei zieht niemand jemanden, den er in den letzten drei Ausgaben schon beschenkt hat, solange das möglich ist."),
+    ("series_name", "Name der Reihe"),
+    ("series_interval", "Abstand in Monaten"),
+    ("series_start", "Als Reihe fortsetzen"),
+    ("series_part", "Teil der Reihe"),
+    ("series_edition", "Ausgabe"),
+    ("series_editions", "Alle Ausgaben"),
+    ("audit_revealed", "Lose für alle aufgedeckt"),
+    ("audit_gift_received", "{name} hat das Geschenk bekommen"),
+    ("login_title", "Los per E-Mail"),
+    ("login_link", "Mein Los per E-Mail"),
+    ("login_email", "Deine E-Mail-Adresse"),
+    ("login_help", "Wir schicken dir einen Link, der 15 Minuten lang einmal gültig ist."),
+    ("login_request", "Link anfordern"),
+    ("login_sent", "Falls die Adresse zu einem Teilnehmer gehört, ist der Link unterwegs."),
+    ("login_unavailable", "Hier können leider keine E-Mails verschickt werden."),
+    ("mail_login_subject", "Dein Link zu {title}"),
+    ("mail_login_body", "Hallo {name},\n\nmit diesem Link siehst du dein Los: {url}\n\nEr ist {minutes} Minuten lang einmal gültig."),
+    ("insertion_label_mode", "Spiel"),
+    ("insertion_mode_secret_santa", "Wichteln"),
+    ("insertion_mode_white_elephant", "Schrottwichteln mit Klauen"),
+    ("insertion_label_max_steals", "Diebstähle pro Geschenk"),
+    ("insertion_help_max_steals", "Nur beim Schrottwichteln"),
+    ("elephant_order", "Reihenfolge"),
+    ("elephant_not_drawn", "Die Reihenfolge wurde noch nicht ausgelost."),
+    ("elephant_draw", "Reihenfolge auslosen"),
+    ("elephant_rules", "Regeln"),
+    ("elephant_rule_turns", "Wer an der Reihe ist, packt ein neues Geschenk aus oder klaut ein bereits ausgepacktes."),
+    ("elephant_rule_max_steals", "Höchstens so oft kann ein Geschenk geklaut werden"),
+    ("elephant_rule_no_steal_back", "Ein Geschenk darf nicht sofort zurückgeklaut werden."),
+    ("elephant_rule_final_swap", "Nach der letzten Runde darf die erste Person noch einmal mit jemandem tauschen."),
+    ("ticket_title", "Los"),
+    ("ticket_heading", "Ihr Los wurde zufällig gezogen"),
+    ("ticket_yours", "dein Los:"),
+    ("ticket_not_found_title", "Los nicht gefunden"),
+    ("ticket_not_found_heading", "Es wurde kein passendes Los gefunden"),
+    ("ticket_plain_locked", "Dieses Los lässt sich nur mit deiner Passphrase im Browser öffnen."),
+    ("ticket_not_found_text", "Möglicherweise ist nur noch Ihr Name in der Losbox vorhanden, oder Sie sind gar nicht Mitglied der Losung."),
+    ("import_title", "Import"),
+    ("import_heading", "Teilnehmer importieren"),
+    ("import_columns", "Die CSV Datei benötigt die Spalten <code>name</code> und <code>team</code>, optional <code>email</code>, <code>pool</code> und <code>sprache</code> (<code>de</code> oder <code>en</code>)."),
+    ("import_redraw", "Nach dem Import wird die Losbox ausgelost, außer sie wartet noch auf Passphrasen, Einladungen oder ihren Stichtag."),
+    ("import_file", "Datei"),
+    ("import_content", "Inhalt"),
+    ("import_submit", "Importieren"),
+    ("import_row", "Zeile"),
+    ("import_error_header", "Kopfzeile konnte nicht gelesen werden"),
+    ("import_error_columns", "Die Spalten 'name' und 'team' werden benötigt"),
+    ("import_error_row", "Zeile konnte nicht gelesen werden"),
+    ("import_error_name", "Name fehlt"),
+    ("import_error_team_missing", "Teamnummer fehlt"),
+    ("import_error_team_number", "Teamnummer ist keine Zahl"),
+    ("import_error_duplicate", "{name} ist bereits Mitglied"),
+    ("distribute_title", "Verteilen"),
+    ("distribute_heading", "Lose verteilen"),
+    ("distribute_help", "Jeder Teilnehmer erreicht sein Los über den persönlichen Link oder QR Code."),
+    ("card_kind", "Karte"),
+    ("card_for", "Für"),
+    ("card_giver", "Du bist der Wichtel für"),
+    ("card_not_drawn", "(noch nicht ausgelost)"),
+    ("card_online", "Details online"),
+    ("error_invalid_data", "Ungültige Formulardaten"),
+    ("error_name_missing", "Nicht alle Teilnehmer haben einen Namen"),
+    ("error_name_too_long", "Namen dürfen höchstens {max} Zeichen lang sein"),
+    ("error_invalid_team", "Das Team von {name} muss eine Zahl sein"),
+    ("error_member_already_defined", "Der Teilnehmer {name} wurde bereits angegeben"),
+    ("error_not_enough_possibilities", "Es gibt nicht genügend Möglichkeiten, die Teams aufzuteilen"),
+    ("error_no_team_or_name", "Es wurde kein Team oder Name angegeben"),
+    ("error_infeasible", "Nach {attempts} Versuchen wurde keine Auslosung gefunden, {name} blieb ohne Los"),
+    ("error_invalid_date", "Ungültiges Datum"),
+    ("error_invalid_timezone", "Unbekannte Zeitzone"),
+    ("error_date_in_past", "Das Datum der Losbox liegt in der Vergangenheit"),
+    ("error_too_many_members", "Die Losbox hat mehr als {max} Teilnehmer"),
+    ("error_title_missing", "Bitte gib einen Titel an"),
+    ("error_title_too_long", "Der Titel darf höchstens {max} Zeichen lang sein"),
+    ("error_too_few_teams", "Die Teilnehmer müssen aus mindestens {min} Teams kommen"),
+    ("error_team_too_large", "Team {team} hat mehr als die Hälfte der Teilnehmer"),
+    ("lang_de", "Deutsch"),
+    ("lang_en", "English"),
+    ("language_heading", "Deine Sprache"),
+    ("language_browser", "Wie im Browser"),
+    ("language_save", "Speichern"),
+    ("language_help", "In dieser Sprache bekommst du dein Los, E-Mails und Erinnerungen"),
+    ("address_heading", "Deine Lieferadresse"),
+    ("address_help", "Falls die Geschenke verschickt werden. Nur wer dich gezogen hat und der Organisator sehen sie"),
+    ("address_save", "Adresse speichern"),
+    ("address_recipient", "Lieferadresse"),
+    ("label_kind", "Etiketten"),
+    ("label_giver", "Wichtel: {name}"),
+    ("theme_heading", "Aussehen"),
+    ("theme_help", "Gilt für die Seite der Auslosung und die Lose"),
+    ("theme_save", "Übernehmen"),
+    ("theme_christmas", "Weihnachtlich"),
+    ("theme_winter", "Winterlich"),
+    ("theme_neutral", "Neutral"),
+    ("theme_corporate", "Firma"),
+    ("share_heading", "Link zum Teilen"),
+    ("share_help", "Zeigt nur Titel, Datum und Teilnehmer, nie wer wen beschenkt. Ein neuer Link macht den alten ungültig"),
+    ("share_create", "Link erstellen"),
+    ("share_renew", "Neuer Link"),
+    ("share_remove", "Link entfernen"),
+    ("share_members", "Dabei sind"),
+    ("avoid_heading", "Möglichst nicht ziehen"),
+    ("avoid_help", "Anders als Teams werden diese Paare gezogen, wenn es nicht anders geht: Gezogen wird dann so, dass die Summe der Gewichtungen (1 bis 10 je Paar) so gering wie möglich ist. Gilt ab der nächsten Auslosung"),
+    ("avoid_giver", "Wichtel"),
+    ("avoid_recipient", "Beschenkter"),
+    ("avoid_penalty", "Gewichtung"),
+    ("avoid_add", "Hinzufügen"),
+    ("avoid_remove", "Entfernen"),
+    ("avoid_violated", "Diese Paare ließen sich nicht vermeiden:"),
+    ("spending_heading", "Ausgaben"),
+    ("spending_enable", "Wichtel nach ihren Ausgaben fragen"),
+    ("spending_disable", "Nicht mehr fragen und Angaben löschen"),
+    ("spending_help", "Die Wichtel können auf ihrem Los angeben, was ihr Geschenk gekostet hat. Nach dem Austausch siehst du nur die Summe, den Schnitt und wie viele über oder weit unter dem Budget lagen, nie wer was ausgegeben hat"),
+    ("spending_total", "Summe"),
+    ("spending_average", "Schnitt"),
+    ("spending_range", "Spanne"),
+    ("spending_median", "Median"),
+    ("spending_recorded", "Angaben"),
+    ("spending_over_budget", "Über dem Budget"),
+    ("spending_under_budget", "Unter der Hälfte des Budgets"),
+    ("spending_too_few", "Noch zu wenige Angaben für eine anonyme Auswertung"),
+    ("spent_heading", "Was hat dein Geschenk gekostet?"),
+    ("spent_save", "Speichern"),
+    ("spent_help", "Freiwillig. Der Organisator sieht nur die Summe aller Angaben, um das Budget fürs nächste Mal zu planen. Leer lassen, um die Angabe zu löschen"),
+    ("reassign_heading", "Problem mit deinem Los?"),
+    ("reassign_help", "Zum Beispiel, wenn du deinen Ex gezogen hast. Der Organisator sieht nur den Grund, nicht wer fragt oder wen du gezogen hast. Bei Zustimmung bekommst du ein neues Los"),
+    ("reassign_ask", "Neues Los anfragen"),
+    ("reassign_pending", "Deine Anfrage liegt dem Organisator vor."),
+    ("reassign_denied", "Der Organisator hat deine letzte Anfrage abgelehnt."),
+    ("reassign_requests", "Anfragen für ein neues Los"),
+    ("reassign_approve", "Neu ziehen"),
+    ("reassign_deny", "Ablehnen"),
+    ("reassign_anonymous", "Wer fragt, bleibt verborgen. Neu ziehen kann auch ein paar andere Lose ändern, deren Wichtel dann benachrichtigt werden"),
+    ("delivery_heading", "Zustellung"),
+    ("delivery_member", "Mitglied"),
+    ("delivery_latest", "Zuletzt"),
+    ("delivery_email", "E-Mail"),
+    ("delivery_push", "Push"),
+    ("delivery_queued", "wartet"),
+    ("delivery_sent", "gesendet"),
+    ("delivery_bounced", "abgewiesen"),
+    ("delivery_failed", "fehlgeschlagen"),
+    ("delivery_retry", "Erneut senden"),
+    ("delivery_help", "Erneut senden schickt das Los noch einmal per E-Mail und Push. Abgewiesene Adressen oder Abos erst vom Mitglied korrigieren lassen"),
+    ("audit_heading", "Aktivität"),
+    ("audit_organizer", "Organisator"),
+    ("audit_admin", "Administrator"),
+    ("audit_system", "System"),
+    ("audit_created", "Losbox erstellt"),
+    ("audit_cloned", "Losbox aus Losbox {from} kopiert"),
+    ("audit_members_added", "Teilnehmer hinzugefügt: {names}"),
+    ("audit_drawn", "Lose gezogen"),
+    ("audit_redrawn", "Lose neu gezogen"),
+    ("audit_assigned", "Lose aus einer eigenen Auslosung übernommen"),
+    ("audit_ticket_viewed", "Los von {name} angesehen"),
+    ("audit_retention_extended", "Aufbewahrung bis {until} verlängert"),
+    ("retention_warning", "Diese Losbox wird bald gelöscht am"),
+    ("retention_extend", "Aufbewahrung verlängern"),
+    ("audit_trashed", "Losbox in den Papierkorb verschoben"),
+    ("audit_restored", "Losbox wiederhergestellt"),
+    ("audit_swapped", "Lose von {first} und {second} getauscht"),
+    ("audit_ticket_redrawn", "Los von {name} neu gezogen, {changed} Lose geändert"),
+    ("audit_reassigned", "Los auf Anfrage neu gezogen, {changed} Lose geändert"),
+    ("poll_heading", "Umfragen"),
+    ("poll_votes", "Stimmen"),
+    ("poll_question", "Frage, z.B. Selbstgemacht oder gekauft?"),
+    ("poll_options", "Eine Antwort pro Zeile"),
+    ("poll_create", "Umfrage starten"),
+    ("poll_vote", "Abstimmen"),
+    ("poll_submit", "Stimme abgeben"),
+    ("audit_poll_created", "Umfrage gestartet: {question}"),
+    ("comment_heading", "Kommentare"),
+    ("comment_name", "Dein Name"),
+    ("comment_message", "Nachricht, z.B. wer bringt was mit?"),
+    ("comment_submit", "Kommentieren"),
+    ("comment_delete", "Löschen"),
+    ("audit_comment_deleted", "Kommentar von {name} gelöscht"),
+    ("mail_ticket_subject", "Dein Wichtel-Los ist da"),
+    ("mail_ticket_body", "Hallo {{ giver }},\n\nunter {{ ticket_url }} erfährst du, wen du beschenkst.{% if budget %}\nDas Budget liegt bei {{ budget }} €.{% endif %}"),
+    ("mail_reminder_subject", "Bald ist Bescherung"),
+    ("mail_reminder_body", "Hallo {{ giver }},\n\ndenk an dein Geschenk! Dein Los findest du unter {{ ticket_url }}.{% if budget %}\nDas Budget liegt bei {{ budget }} €.{% endif %}"),
+    ("admin_trash", "Papierkorb"),
+    ("admin_trashed", "Gelöscht"),
+    ("admin_purge", "Endgültig gelöscht am"),
+    ("admin_restore", "Wiederherstellen"),
+    ("stats_drafts", "Losboxen"),
+    ("stats_members", "Teilnehmer"),
+    ("stats_drawn", "Gezogen"),
+    ("stats_this_month", "Diesen Monat"),
+    ("ceremony_title", "Ziehung"),
+    ("ceremony_waiting", "Warte auf die Ziehung..."),
+    ("ceremony_drawing", "Die Lose werden gezogen"),
+    ("ceremony_done", "Alle Lose sind gezogen!"),
+    ("draft_ceremony", "Ziehung vorführen"),
+    ("push_subscribe", "Benachrichtigungen aktivieren"),
+    ("push_subscribed", "Du wirst benachrichtigt."),
+    ("avatar_heading", "Dein Avatar"),
+    ("avatar_image", "Bild"),
+    ("avatar_image_help", "PNG, JPEG oder GIF, höchstens 2 MB."),
+    ("avatar_emoji", "Oder ein Emoji"),
+    ("avatar_save", "Speichern"),
+    ("avatar_remove", "Entfernen"),
+    ("push_drawn", "Die Lose sind gezogen, schau dir an wen du beschenkst!"),
+    ("push_reminder", "In {days} Tagen ist Bescherung, denk an dein Geschenk!"),
+    ("admin_title", "Verwaltung"),
+    ("admin_token", "Admin-Token"),
+    ("admin_login", "Anmelden"),
+    ("admin_login_error", "Das Token ist ungültig"),
+    ("admin_logout", "Abmelden"),
+    ("admin_drafts", "Losboxen"),
+    ("admin_draft", "Losbox"),
+    ("admin_members", "Teilnehmer"),
+    ("admin_drawn", "Gezogen"),
+    ("admin_open", "offen"),
+    ("admin_past", "vergangen"),
+    ("admin_storage", "Speicher"),
+    ("admin_data_file", "Datendatei"),
+    ("admin_created", "Erstellt"),
+    ("admin_date", "Datum"),
+    ("admin_size", "Größe"),
+    ("admin_delete", "Löschen"),
+    ("admin_delete_confirm", "Losbox in den Papierkorb verschieben?"),
+    ("admin_outbox", "Nicht zugestellte Benachrichtigungen"),
+    ("admin_outbox_pending", "Wartende Benachrichtigungen"),
+    ("admin_outbox_recipient", "Empfänger"),
+    ("admin_outbox_attempts", "Versuche"),
+    ("admin_outbox_error", "Fehler"),
+    ("admin_outbox_retry", "Erneut senden"),
+    ("admin_outbox_discard", "Verwerfen"),
+    ("admin_outbox_help", "Diese E-Mails und Push-Benachrichtigungen wurden nach allen Versuchen oder nach einer endgültigen Ablehnung aufgegeben"),
+    ("organizers_heading", "Organisatoren"),
+    ("organizers_pending", "eingeladen"),
+    ("organizers_transfer_pending", "Übergabe angeboten"),
+    ("organizers_remove", "Entfernen"),
+    ("organizers_withdraw", "Zurückziehen"),
+    ("organizers_email", "E-Mail-Adresse"),
+    ("organizers_invite", "Einladen"),
+    ("organizers_transfer", "Die Losbox ganz übergeben, statt mitorganisieren zu lassen"),
+    ("organizers_help", "Mitorganisatoren können alles außer Organisatoren verwalten. Bei einer Übergabe verlierst du den Zugriff, sobald sie angenommen wird. Der Link in der E-Mail gilt 7 Tage"),
+    ("organizers_co_help", "Du organisierst diese Losbox mit. Organisatoren verwaltet nur ihr Besitzer"),
+    ("organizers_accept_question", "Diese Losbox mitorganisieren?"),
+    ("organizers_accept_owner", "Diese Losbox übernehmen?"),
+    ("organizers_invited_as", "Eingeladen als"),
+    ("organizers_accept", "Annehmen"),
+    ("mail_organizer_subject", "Organisiere {title} mit"),
+    ("mail_owner_subject", "Übernimm die Losbox {title}"),
+    ("mail_organizer_body", "Hallo,\n\ndu wurdest eingeladen, die Losbox {title} zu organisieren. Hier kannst du annehmen, der Link gilt {days} Tage: {url}"),
+    ("audit_organizer_added", "{email} organisiert mit"),
+    ("audit_organizer_removed", "{email} als Organisator entfernt"),
+    ("audit_ownership_transferred", "Losbox an {email} übergeben"),
+];
+
+const EN: &[(&str, &str)] = &[
+    ("nav_home", "Home"),
+    ("nav_new_draft", "New raffle box"),
+    ("cancel", "Cancel"),
+    ("back", "Go back"),
+    ("error_500_title", "500 Internal Server Error"),
+    ("error_500_heading", "Error Code 500"),
+    ("error_500_subtitle", "Internal Server Error"),
+    ("error_500_text", "Unfortunately your request could not be processed."),
+    ("error_500_retry", "Please try again later."),
+    ("error_request_id", "Please mention this id when reporting the error"),
+    ("limit_title", "Too many new raffle boxes"),
+    ("limit_address", "Too many raffle boxes were created from your address in the last hour. Please try again in {minutes} minutes."),
+    ("limit_full", "No more raffle boxes can be created on this instance right now. Please try again later."),
+    ("index_title", "Secret Santa"),
+    ("index_welcome", "Welcome to Secret Santa, have a look below whether"),
+    ("index_welcome_started", "the draw has already started."),
+    ("index_welcome_create", "Otherwise create one yourself under 'New raffle box'."),
+    ("index_drafts", "Raffle boxes"),
+    ("draft_draw", "Draw"),
+    ("draft_calendar", "Add to calendar"),
+    ("calendar_description", "Secret Santa with: {names}"),
+    ("draft_import", "Import participants"),
+    ("draft_export_csv", "CSV export"),
+    ("draft_export_json", "JSON export"),
+    ("draft_cards", "Print cards"),
+    ("draft_bundle", "All tickets as ZIP"),
+    ("draft_labels", "Address labels"),
+    ("draft_distribute", "Distribute tickets"),
+    ("draft_not_found", "Raffle box not found"),
+    ("draft_not_found_manual", "Did you enter the URL manually?"),
+    ("draft_not_found_hint", "Try to navigate to the raffle box from the home page."),
+    ("insertion_title", "New raffle box"),
+    ("insertion_heading", "Put together a new raffle box"),
+    ("insertion_label_title", "Title"),
+    ("insertion_label_date", "Date"),
+    ("insertion_label_members", "Participants"),
+    ("insertion_placeholder", "Text input..."),
+    ("insertion_help_name", "Your name"),
+    ("insertion_help_team", "Your team number"),
+    ("insertion_submit", "Submit"),
+    ("insertion_label_pools", "Pools"),
+    ("insertion_help_pool", "Pool (optional)"),
+    ("insertion_help_pool_name", "Name of the pool"),
+    ("insertion_help_pool_budget", "Budget in €"),
+    ("pool_budget", "Budget"),
+    ("insertion_label_budget", "Budget"),
+    ("insertion_help_budget", "Budget in € for everyone without their own budget (optional)"),
+    ("insertion_label_team_budgets", "Budgets of single teams"),
+    ("insertion_help_budget_team", "Team number"),
+    ("ticket_budget", "Budget for the gift"),
+    ("error_invalid_budget", "A budget has to be more than zero"),
+    ("error_unknown_budget_team", "Team {team} has a budget but no participants"),
+    ("error_duplicate_pool", "The pool {name} was given twice"),
+    ("error_invalid_email_template", "The email template {template} has errors or unknown variables"),
+    ("error_passphrase_missing", "{name} has not set a passphrase for the ticket yet"),
+    ("insertion_label_passphrases", "Encrypt tickets with passphrases"),
+    ("insertion_help_passphrases", "Everyone sets a passphrase on their link before the draw, without which nobody, not even the organizer, can read the ticket"),
+    ("insertion_label_blind", "Hide tickets from the organizer"),
+    ("insertion_help_email", "Email (optional)"),
+    ("insertion_label_invite", "Invite members by email"),
+    ("insertion_help_invite", "Members with an email accept or decline before the draw and fill in their wishlist, whoever declines isn't drawn"),
+    ("invite_title", "Invitation"),
+    ("invite_question", "are you in?"),
+    ("invite_accepted", "you're in"),
+    ("invite_wishlist", "Wishlist"),
+    ("invite_wishlist_help", "What would you like? Your santa sees it with their ticket"),
+    ("invite_accept", "Accept"),
+    ("invite_decline", "Decline"),
+    ("invite_save", "Save wishlist"),
+    ("invite_closed", "The draw already took place"),
+    ("invite_ticket", "To your ticket"),
+    ("invite_status_accepted", "Accepted"),
+    ("invite_status_pending", "Pending"),
+    ("invite_status_declined", "Declined"),
+    ("ticket_wishlist", "Wishlist"),
+    ("mail_invite_subject", "Invitation to {title}"),
+    ("mail_invite_body", "Hi {name},\n\nyou are invited to {title} on {date}. Here you can accept or decline and fill in your wishlist: {url}"),
+    ("audit_invitation_accepted", "{name} accepted"),
+    ("audit_invitation_declined", "{name} declined"),
+    ("preset_label", "Templates"),
+    ("preset_couples", "Couples"),
+    ("preset_couples_help", "Every couple is a team, so partners don't draw each other"),
+    ("preset_office", "Office with departments"),
+    ("preset_office_help", "Every department is a team, so everyone gives to someone from another department"),
+    ("preset_family", "Family with kids"),
+    ("preset_family_help", "Every household is a team, the kids draw among themselves with a budget of their own"),
+    ("preset_family_kids", "Kids"),
+    ("insertion_help_blind", "Tickets only open on the members' personal links, the organizer only sees the progress"),
+    ("blind_notice", "Tickets only open on the personal link every member received"),
+    ("passphrase_set", "set a passphrase for your ticket"),
+    ("passphrase_set_again", "your passphrase is set, you can change it until the draw"),
+    ("passphrase_help", "Only this passphrase opens your ticket after the draw. It can't be reset once forgotten."),
+    ("passphrase_label", "Passphrase"),
+    ("passphrase_repeat", "Repeat passphrase"),
+    ("passphrase_mismatch", "The passphrases don't match"),
+    ("passphrase_save", "Save passphrase"),
+    ("passphrase_saved", "Passphrase saved"),
+    ("passphrase_enter", "enter your passphrase to open your ticket"),
+    ("passphrase_unlock", "Open"),
+    ("passphrase_wrong", "Wrong passphrase"),
+    ("passphrase_waiting", "Still without a passphrase"),
+    ("feed_title", "Upcoming gift exchanges"),
+    ("feed_date", "Exchange on"),
+    ("timeline_drawn", "Drawn"),
+    ("timeline_viewed", "Tickets viewed"),
+    ("timeline_received", "Gifts received"),
+    ("gift_outstanding", "Still waiting for a gift"),
+    ("gift_confirm", "I received my gift"),
+    ("gift_confirmed", "You confirmed that your gift arrived."),
+    ("thanks_heading", "Say thank you"),
+    ("thanks_help", "The note goes to your santa without telling you who they are."),
+    ("thanks_send", "Send thank-you note"),
+    ("thanks_update", "Change thank-you note"),
+    ("thanks_santa", "Your santa revealed themselves:"),
+    ("thanks_received", "A thank-you note from your recipient"),
+    ("thanks_sign", "Reveal myself"),
+    ("thanks_sign_help", "Your recipient then sees that the gift was from you."),
+    ("thanks_signed", "You revealed yourself."),
+    ("mail_thanks_subject", "A thank-you note for {title}"),
+    ("mail_thanks_body", "Hi {name},\n\nyour recipient says thank you:\n\n{message}\n\nAt {url} you can reveal yourself."),
+    ("timeline_comment", "{name} commented"),
+    ("archive_title", "Archive"),
+    ("archive_empty", "No past exchanges yet"),
+    ("archive_revealed", "revealed"),
+    ("archive_notice", "This exchange is over and can't be changed anymore."),
+    ("archive_reveal", "Reveal to everyone who gave to whom"),
+    ("archive_pairs", "Who gave to whom"),
+    ("archive_assignments", "See all assignments"),
+    ("deadline_draw", "Drawn automatically on"),
+    ("deadline_set", "Set registration deadline"),
+    ("deadline_help", "Until then members can add themselves, afterwards the draft is drawn automatically."),
+    ("join_heading", "Join"),
+    ("join_email", "Email (optional)"),
+    ("join_submit", "Join"),
+    ("google_heading", "Google Calendar"),
+    ("google_help", "Once drawn, an event for the exchange is created in your Google Calendar and every member with an email address is invited."),
+    ("google_connect", "Connect Google Calendar"),
+    ("google_connected", "Connected: the event is created once the draft is drawn."),
+    ("google_disconnect", "Disconnect"),
+    ("series_heading", "Every year again"),
+    ("series_help", "As a series, the next exchange with the same members and teams is created automatically after the date. Nobody draws someone they already gave to in the last three editions, as long as that is possible."),
+    ("series_name", "Name of the series"),
+    ("series_interval", "Interval in months"),
+    ("series_start", "Continue as a series"),
+    ("series_part", "Part of the series"),
+    ("series_edition", "Edition"),
+    ("series_editions", "All editions"),
+    ("audit_revealed", "tickets revealed to everyone"),
+    ("audit_gift_received", "{name} received their gift"),
+    ("login_title", "Ticket by email"),
+    ("login_link", "My ticket by email"),
+    ("login_email", "Your email address"),
+    ("login_help", "We send you a link that works once within 15 minutes."),
+    ("login_request", "Request link"),
+    ("login_sent", "If the address belongs to a member, the link is on its way."),
+    ("login_unavailable", "Sorry, this instance can't send emails."),
+    ("mail_login_subject", "Your link to {title}"),
+    ("mail_login_body", "Hi {name},\n\nthis link shows you your ticket: {url}\n\nIt works once within {minutes} minutes."),
+    ("insertion_label_mode", "Game"),
+    ("insertion_mode_secret_santa", "Secret Santa"),
+    ("insertion_mode_white_elephant", "White elephant"),
+    ("insertion_label_max_steals", "Steals per gift"),
+    ("insertion_help_max_steals", "White elephant only"),
+    ("elephant_order", "Turn order"),
+    ("elephant_not_drawn", "The turn order has not been drawn yet."),
+    ("elephant_draw", "Draw turn order"),
+    ("elephant_rules", "Rules"),
+    ("elephant_rule_turns", "On your turn, unwrap a new gift or steal one that was already unwrapped."),
+    ("elephant_rule_max_steals", "Times a gift can be stolen at most"),
+    ("elephant_rule_no_steal_back", "A gift can't be stolen right back."),
+    ("elephant_rule_final_swap", "After the last turn, the first player may swap with anyone once more."),
+    ("ticket_title", "Ticket"),
+    ("ticket_heading", "Your ticket was drawn at random"),
+    ("ticket_yours", "your ticket:"),
+    ("ticket_not_found_title", "Ticket not found"),
+    ("ticket_not_found_heading", "No matching ticket was found"),
+    ("ticket_plain_locked", "This ticket only opens with your passphrase in a browser."),
+    ("ticket_not_found_text", "Possibly only your own name is left in the raffle box, or you are not a participant of this draw."),
+    ("import_title", "Import"),
+    ("import_heading", "Import participants"),
+    ("import_columns", "The CSV file needs the columns <code>name</code> and <code>team</code>, optionally <code>email</code>, <code>pool</code> and <code>language</code> (<code>de</code> or <code>en</code>)."),
+    ("import_redraw", "The raffle box is drawn after the import, unless it still waits for passphrases, invitations or its deadline."),
+    ("import_file", "File"),
+    ("import_content", "Content"),
+    ("import_submit", "Import"),
+    ("import_row", "Row"),
+    ("import_error_header", "Header row could not be read"),
+    ("import_error_columns", "The columns 'name' and 'team' are required"),
+    ("import_error_row", "Row could not be read"),
+    ("import_error_name", "Name is missing"),
+    ("import_error_team_missing", "Team number is missing"),
+    ("import_error_team_number", "Team number is not a number"),
+    ("import_error_duplicate", "{name} is already a participant"),
+    ("distribute_title", "Distribute"),
+    ("distribute_heading", "Distribute tickets"),
+    ("distribute_help", "Every participant reaches their ticket via the personal link or QR code."),
+    ("card_kind", "Card"),
+    ("card_for", "For"),
+    ("card_giver", "You are the Secret Santa for"),
+    ("card_not_drawn", "(not drawn yet)"),
+    ("card_online", "Details online"),
+    ("error_invalid_data", "Invalid form data"),
+    ("error_name_missing", "Please give every member a name"),
+    ("error_name_too_long", "Names can be at most {max} characters long"),
+    ("error_invalid_team", "The team of {name} has to be a number"),
+    ("error_member_already_defined", "Member {name} was already defined"),
+    ("error_not_enough_possibilities", "Not enough possibilities to separate the teams"),
+    ("error_no_team_or_name", "No team or name defined"),
+    ("error_infeasible", "No draw found after {attempts} attempts, {name} was left without a ticket"),
+    ("error_invalid_date", "Invalid date"),
+    ("error_invalid_timezone", "Unknown timezone"),
+    ("error_date_in_past", "The date of the draft lies in the past"),
+    ("error_too_many_members", "The raffle box has more than {max} participants"),
+    ("error_title_missing", "Please enter a title"),
+    ("error_title_too_long", "The title may be at most {max} characters long"),
+    ("error_too_few_teams", "The participants have to come from at least {min} teams"),
+    ("error_team_too_large", "Team {team} has more than half of the participants"),
+    ("lang_de", "Deutsch"),
+    ("lang_en", "English"),
+    ("language_heading", "Your language"),
+    ("language_browser", "Same as the browser"),
+    ("language_save", "Save"),
+    ("language_help", "Your ticket, emails and reminders come in this language"),
+    ("address_heading", "Your shipping address"),
+    ("address_help", "In case the gifts are mailed. Only whoever drew you and the organizer see it"),
+    ("address_save", "Save address"),
+    ("address_recipient", "Shipping address"),
+    ("label_kind", "Labels"),
+    ("label_giver", "Santa: {name}"),
+    ("theme_heading", "Look"),
+    ("theme_help", "Applies to the page of the draft and the tickets"),
+    ("theme_save", "Apply"),
+    ("theme_christmas", "Christmas"),
+    ("theme_winter", "Winter"),
+    ("theme_neutral", "Neutral"),
+    ("theme_corporate", "Corporate"),
+    ("share_heading", "Share link"),
+    ("share_help", "Only shows title, date and members, never who gives to whom. A new link replaces the old one"),
+    ("share_create", "Create link"),
+    ("share_renew", "New link"),
+    ("share_remove", "Remove link"),
+    ("share_members", "Taking part"),
+    ("avoid_heading", "Avoid if possible"),
+    ("avoid_help", "Unlike teams, these pairs are drawn when there is no other way: the draw then keeps the total weight (1 to 10 per pair) as low as possible. Applies from the next draw on"),
+    ("avoid_giver", "Giver"),
+    ("avoid_recipient", "Recipient"),
+    ("avoid_penalty", "Weight"),
+    ("avoid_add", "Add"),
+    ("avoid_remove", "Remove"),
+    ("avoid_violated", "These pairs could not be avoided:"),
+    ("spending_heading", "Spending"),
+    ("spending_enable", "Ask the givers what they spent"),
+    ("spending_disable", "Stop asking and delete the amounts"),
+    ("spending_help", "The givers can enter on their ticket what their gift cost. After the exchange you only see the total, the average and how many were above or far below the budget, never who spent what"),
+    ("spending_total", "Total"),
+    ("spending_average", "Average"),
+    ("spending_range", "Range"),
+    ("spending_median", "Median"),
+    ("spending_recorded", "Amounts"),
+    ("spending_over_budget", "Above the budget"),
+    ("spending_under_budget", "Below half the budget"),
+    ("spending_too_few", "Too few amounts yet for an anonymous summary"),
+    ("spent_heading", "What did your gift cost?"),
+    ("spent_save", "Save"),
+    ("spent_help", "Optional. The organizer only sees the sum of all amounts, to plan next year's budget. Leave it empty to delete yours"),
+    ("reassign_heading", "A problem with your ticket?"),
+    ("reassign_help", "For example when you drew your ex. The organizer only sees the reason, not who asks or whom you drew. If they agree, you get a new ticket"),
+    ("reassign_ask", "Ask for a new ticket"),
+    ("reassign_pending", "Your request is with the organizer."),
+    ("reassign_denied", "The organizer turned down your last request."),
+    ("reassign_requests", "Requests for a new ticket"),
+    ("reassign_approve", "Redraw"),
+    ("reassign_deny", "Deny"),
+    ("reassign_anonymous", "Who asks stays hidden. Redrawing may change a few other tickets too, whose givers are notified"),
+    ("delivery_heading", "Delivery"),
+    ("delivery_member", "Member"),
+    ("delivery_latest", "Latest"),
+    ("delivery_email", "Email"),
+    ("delivery_push", "Push"),
+    ("delivery_queued", "queued"),
+    ("delivery_sent", "sent"),
+    ("delivery_bounced", "bounced"),
+    ("delivery_failed", "failed"),
+    ("delivery_retry", "Send again"),
+    ("delivery_help", "Send again sends the ticket once more by email and push. Have the member fix a bounced address or subscription first"),
+    ("audit_heading", "Activity"),
+    ("audit_organizer", "Organizer"),
+    ("audit_admin", "Administrator"),
+    ("audit_system", "System"),
+    ("audit_created", "raffle box created"),
+    ("audit_cloned", "raffle box copied from raffle box {from}"),
+    ("audit_members_added", "participants added: {names}"),
+    ("audit_drawn", "tickets drawn"),
+    ("audit_redrawn", "tickets drawn again"),
+    ("audit_assigned", "tickets taken from an own draw"),
+    ("audit_ticket_viewed", "ticket of {name} viewed"),
+    ("audit_retention_extended", "retention extended until {until}"),
+    ("retention_warning", "This raffle box will soon be deleted on"),
+    ("retention_extend", "Extend retention"),
+    ("audit_trashed", "raffle box moved to the trash"),
+    ("audit_restored", "raffle box restored"),
+    ("audit_swapped", "tickets of {first} and {second} swapped"),
+    ("audit_ticket_redrawn", "ticket of {name} redrawn, {changed} tickets changed"),
+    ("audit_reassigned", "ticket redrawn on request, {changed} tickets changed"),
+    ("poll_heading", "Polls"),
+    ("poll_votes", "votes"),
+    ("poll_question", "Question, e.g. homemade or bought?"),
+    ("poll_options", "One answer per line"),
+    ("poll_create", "Start poll"),
+    ("poll_vote", "Vote"),
+    ("poll_submit", "Cast vote"),
+    ("audit_poll_created", "poll started: {question}"),
+    ("comment_heading", "Comments"),
+    ("comment_name", "Your name"),
+    ("comment_message", "Message, e.g. who brings what?"),
+    ("comment_submit", "Comment"),
+    ("comment_delete", "Delete"),
+    ("audit_comment_deleted", "comment by {name} deleted"),
+    ("mail_ticket_subject", "Your Secret Santa ticket is ready"),
+    ("mail_ticket_body", "Hi {{ giver }},\n\nfind out who you are giving a present to at {{ ticket_url }}.{% if budget %}\nThe budget is {{ budget }} €.{% endif %}"),
+    ("mail_reminder_subject", "The exchange is coming up"),
+    ("mail_reminder_body", "Hi {{ giver }},\n\ndon't forget your present! Your ticket is at {{ ticket_url }}.{% if budget %}\nThe budget is {{ budget }} €.{% endif %}"),
+    ("admin_trash", "Trash"),
+    ("admin_trashed", "Deleted"),
+    ("admin_purge", "Purged on"),
+    ("admin_restore", "Restore"),
+    ("stats_drafts", "Raffle boxes"),
+    ("stats_members", "Participants"),
+    ("stats_drawn", "Drawn"),
+    ("stats_this_month", "This month"),
+    ("ceremony_title", "Draw"),
+    ("ceremony_waiting", "Waiting for the draw..."),
+    ("ceremony_drawing", "The tickets are being drawn"),
+    ("ceremony_done", "All tickets are drawn!"),
+    ("draft_ceremony", "Present the draw"),
+    ("push_subscribe", "Enable notifications"),
+    ("push_subscribed", "You will be notified."),
+    ("avatar_heading", "Your avatar"),
+    ("avatar_image", "Image"),
+    ("avatar_image_help", "PNG, JPEG or GIF, at most 2 MB."),
+    ("avatar_emoji", "Or an emoji"),
+    ("avatar_save", "Save"),
+    ("avatar_remove", "Remove"),
+    ("push_drawn", "The tickets are drawn, see who you are giving a present to!"),
+    ("push_reminder", "The exchange is in {days} days, remember your present!"),
+    ("admin_title", "Administration"),
+    ("admin_token", "Admin token"),
+    ("admin_login", "Log in"),
+    ("admin_login_error", "The token is invalid"),
+    ("admin_logout", "Log out"),
+    ("admin_drafts", "Raffle boxes"),
+    ("admin_draft", "Raffle box"),
+    ("admin_members", "Participants"),
+    ("admin_drawn", "Drawn"),
+    ("admin_open", "open"),
+    ("admin_past", "past"),
+    ("admin_storage", "Storage"),
+    ("admin_data_file", "Data file"),
+    ("admin_created", "Created"),
+    ("admin_date", "Date"),
+    ("admin_size", "Size"),
+    ("admin_delete", "Delete"),
+    ("admin_delete_confirm", "Move the raffle box to the trash?"),
+    ("admin_outbox", "Undelivered notifications"),
+    ("admin_outbox_pending", "Pending notifications"),
+    ("admin_outbox_recipient", "Recipient"),
+    ("admin_outbox_attempts", "Attempts"),
+    ("admin_outbox_error", "Error"),
+    ("admin_outbox_retry", "Send again"),
+    ("admin_outbox_discard", "Discard"),
+    ("admin_outbox_help", "These emails and push notifications were given up after all attempts or after being rejected for good"),
+    ("organizers_heading", "Organizers"),
+    ("organizers_pending", "invited"),
+    ("organizers_transfer_pending", "transfer offered"),
+    ("organizers_remove", "Remove"),
+    ("organizers_withdraw", "Withdraw"),
+    ("organizers_email", "Email address"),
+    ("organizers_invite", "Invite"),
+    ("organizers_transfer", "Hand the raffle box over entirely instead of adding a co-organizer"),
+    ("organizers_help", "Co-organizers can do everything but manage the organizers. When handing it over you lose access once it is accepted. The link in the email is valid for 7 days"),
+    ("organizers_co_help", "You co-organize this raffle box. Only its owner manages the organizers"),
+    ("organizers_accept_question", "Co-organize this raffle box?"),
+    ("organizers_accept_owner", "Take over this raffle box?"),
+    ("organizers_invited_as", "Invited as"),
+    ("organizers_accept", "Accept"),
+    ("mail_organizer_subject", "Co-organize {title}"),
+    ("mail_owner_subject", "Take over the raffle box {title}"),
+    ("mail_organizer_body", "Hi,\n\nyou were invited to organize the raffle box {title}. You can accept here, the link is valid for {days} days: {url}"),
+    ("audit_organizer_added", "{email} co-organizes"),
+    ("audit_organizer_removed", "{email} removed as organizer"),
+    ("audit_ownership_transferred", "raffle box handed over to {email}"),
+];
@@ -0,0 +1,148 @@
+use crate::config::AppConfig;
+use crate::{limit, Organizer};
+use chrono::{DateTime, Duration, Utc};
+use rocket::http::Status;
+use rocket::request::{self, FromRequest, Request};
+use rocket::{Outcome, State};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// Hours a response is replayed for its key.
+pub const KEY_HOURS: i64 = 24;
+const MAX_KEY: usize = 255;
+
+// The `Idempotency-Key` header of a request, scoped to its method and path
+// so the same key can't replay the response of another endpoint, and to
+// the client: its address and the organizer tokens it presents. Someone
+// else sending the same key doesn't get e.g. the organizer token of the
+// draft created with it. Requests without one are simply run.
+pub struct Key(Option<String>);
+
+// Who sent the request, hashed as it holds tokens.
+fn caller(request: &Request) -> String {
+    let address = request
+        .guard::<State<AppConfig>>()
+        .succeeded()
+        .and_then(|config| limit::client_address(request, &config))
+        .map(|address| address.to_string())
+        .unwrap_or_default();
+    let mut tokens = match request.guard::<Organizer>() {
+        Outcome::Success(organizer) => organizer.0,
+        _ => Vec::new(),
+    };
+    tokens.sort();
+    fingerprint(&(address, tokens))
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Key {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        match request.headers().get_one("Idempotency-Key").map(str::trim) {
+            None | Some("") => Outcome::Success(Key(None)),
+            Some(key) if key.len() > MAX_KEY => Outcome::Failure((Status::BadRequest, ())),
+            Some(key) => Outcome::Success(Key(Some(format!(
+                "{} {} {} {}",
+                request.method(),
+                request.uri().path(),
+                caller(request),
+                key
+            )))),
+        }
+    }
+}
+
+// A hash of the request body, to tell a retry from another request reusing
+// its key.
+pub fn fingerprint<T: Serialize>(body: &T) -> String {
+    let json = serde_json::to_vec(body).unwrap_or_default();
+    base64::encode(Sha256::digest(&json))
+}
+
+enum Slot {
+    Running,
+    Done(Arc<dyn Any + Send + Sync>),
+}
+
+// Responses of the requests sent with a key, so a retried request gets
+// the answer of the first instead of e.g. creating a second draft. Only
+// kept in memory, like the login links.
+#[derive(Default)]
+pub struct Responses(Mutex<HashMap<String, (DateTime<Utc>, String, Slot)>>);
+
+// Clears the key of a request that failed or panicked, so it can be
+// retried.
+struct Running<'a> {
+    responses: &'a Responses,
+    key: &'a str,
+    done: bool,
+}
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.responses.0.lock().unwrap().remove(self.key);
+        }
+    }
+}
+
+impl Responses {
+    // Runs the request unless one with the same key did already, answering
+    // 409 while the first one still runs and 422 if it had another body,
+    // see `fingerprint`. Only successful responses are kept.
+    pub fn run<T, E>(
+        &self,
+        key: &Key,
+        body: &str,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<Result<T, E>, Status>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let key = match &key.0 {
+            Some(key) => key,
+            None => return Ok(f()),
+        };
+        {
+            let mut responses = self.0.lock().unwrap();
+            let now = Utc::now();
+            responses.retain(|_, (created, _, _)| *created + Duration::hours(KEY_HOURS) > now);
+            match responses.get(key) {
+                Some((_, first, _)) if first != body => return Err(Status::UnprocessableEntity),
+                Some((_, _, Slot::Running)) => return Err(Status::Conflict),
+                Some((_, _, Slot::Done(response))) => {
+                    // The same key sent to the same endpoint always has the
+                    // same type of response.
+                    return response
+                        .downcast_ref::<T>()
+                        .cloned()
+                        .map(Ok)
+                        .ok_or(Status::UnprocessableEntity);
+                }
+                None => responses.insert(key.clone(), (now, body.to_string(), Slot::Running)),
+            };
+        }
+        let mut running = Running {
+            responses: self,
+            key,
+            done: false,
+        };
+        let result = f();
+        if let Ok(response) = &result {
+            let mut responses = self.0.lock().unwrap();
+            responses.insert(
+                key.clone(),
+                (
+                    Utc::now(),
+                    body.to_string(),
+                    Slot::Done(Arc::new(response.clone())),
+                ),
+            );
+            running.done = true;
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,113 @@
+use crate::i18n::Locale;
+use crate::{name_key, sanitize, Member};
+use serde::Serialize;
+use std::collections::HashSet;
+
+#[derive(Serialize, Debug)]
+pub struct RowError {
+    pub row: usize,
+    pub message: String,
+}
+
+impl RowError {
+    fn new<S: Into<String>>(row: usize, message: S) -> RowError {
+        RowError {
+            row,
+            message: message.into(),
+        }
+    }
+}
+
+fn column(headers: &csv::StringRecord, names: &[&str]) -> Option<usize> {
+    headers
+        .iter()
+        .position(|header| names.contains(&header.to_lowercase().as_str()))
+}
+
+// Rows are numbered like in a spreadsheet, the header being row 1. Names
+// already taken by `existing` members are reported as duplicates.
+pub fn parse_members(
+    existing: &HashSet<Member>,
+    text: &str,
+    locale: Locale,
+) -> Result<Vec<Member>, Vec<RowError>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(_) => return Err(vec![RowError::new(1, locale.text("import_error_header"))]),
+    };
+    let (name_column, team_column) = match (
+        column(&headers, &["name"]),
+        column(&headers, &["team", "teamnummer"]),
+    ) {
+        (Some(name), Some(team)) => (name, team),
+        _ => return Err(vec![RowError::new(1, locale.text("import_error_columns"))]),
+    };
+    let email_column = column(&headers, &["email", "e-mail", "mail"]);
+    let pool_column = column(&headers, &["pool", "gruppe"]);
+    let language_column = column(&headers, &["language", "sprache"]);
+
+    let mut names = existing
+        .iter()
+        .map(|member| name_key(&member.name))
+        .collect::<HashSet<String>>();
+    let mut members = Vec::new();
+    let mut errors = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let row = index + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(_) => {
+                errors.push(RowError::new(row, locale.text("import_error_row")));
+                continue;
+            }
+        };
+        let name = record.get(name_column).unwrap_or("");
+        let team = record.get(team_column).unwrap_or("");
+        if name == "" {
+            errors.push(RowError::new(row, locale.text("import_error_name")));
+            continue;
+        }
+        if team == "" {
+            errors.push(RowError::new(row, locale.text("import_error_team_missing")));
+            continue;
+        }
+        let team = match team.parse::<u32>() {
+            Ok(team) => team,
+            Err(_) => {
+                errors.push(RowError::new(row, locale.text("import_error_team_number")));
+                continue;
+            }
+        };
+        if !names.insert(name_key(name)) {
+            errors.push(RowError::new(
+                row,
+                locale
+                    .text("import_error_duplicate")
+                    .replace("{name}", name),
+            ));
+            continue;
+        }
+        let mut member = Member::new(name.to_string(), team);
+        member.email = email_column
+            .and_then(|column| record.get(column))
+            .map(sanitize::line)
+            .filter(|email| email != "");
+        member.pool = pool_column
+            .and_then(|column| record.get(column))
+            .filter(|pool| *pool != "")
+            .map(|pool| pool.to_string());
+        member.language = language_column
+            .and_then(|column| record.get(column))
+            .and_then(Locale::from_code);
+        members.push(member);
+    }
+    if errors.is_empty() {
+        Ok(members)
+    } else {
+        Err(errors)
+    }
+}
@@ -0,0 +1,287 @@
+use crate::config::AppConfig;
+use crate::i18n::{self, Locale};
+use crate::outbox::{self, Page};
+use crate::{audit, events, google, language, mail, push, qr, sanitize, Draft, Drafts};
+use rocket::http::Status;
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use rocket_contrib::templates::Template;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub use api::invite::Answer;
+
+const MAX_WISHLIST: usize = 1000;
+
+// Whether a member invited by email takes part. Members entered without an
+// email aren't asked, and those who decline are removed from the draft.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Invitation {
+    Pending,
+    Accepted,
+}
+
+// Invites every member with an email. The draft is drawn once all of them
+// answered.
+pub fn invite_all(draft: &mut Draft) {
+    draft.members = draft
+        .members
+        .drain()
+        .map(|mut member| {
+            if member
+                .email
+                .as_ref()
+                .map_or(false, |email| !email.trim().is_empty())
+            {
+                member.invitation = Some(Invitation::Pending);
+            }
+            member
+        })
+        .collect();
+}
+
+pub fn pending(draft: &Draft) -> bool {
+    draft
+        .members
+        .iter()
+        .any(|member| member.invitation == Some(Invitation::Pending))
+}
+
+// Sends the invitations still unanswered, in the background.
+pub fn send(draft: &Draft, id: usize, origin: &qr::Origin, config: &AppConfig, locale: Locale) {
+    for member in &draft.members {
+        match (&member.email, member.invitation) {
+            (Some(email), Some(Invitation::Pending)) => {
+                let locale = member.locale(locale);
+                let date = locale.format_date(&draft.local_date(), false);
+                let message = mail::Message {
+                    subject: locale
+                        .text("mail_invite_subject")
+                        .replace("{title}", &draft.title),
+                    body: locale
+                        .text("mail_invite_body")
+                        .replace("{name}", &member.name)
+                        .replace("{title}", &draft.title)
+                        .replace("{date}", &date)
+                        .replace("{url}", outbox::LINK),
+                };
+                let link = origin.link(id, member, Page::Invite);
+                mail::send_with(
+                    config,
+                    email.trim().to_string(),
+                    message,
+                    None,
+                    None,
+                    Some(link),
+                );
+            }
+            _ => (),
+        }
+    }
+}
+
+// The names of the members who declined, from the audit log as they are
+// no longer members.
+fn declined(draft: &Draft) -> Vec<String> {
+    draft
+        .audit
+        .iter()
+        .filter_map(|entry| match &entry.action {
+            audit::Action::InvitationDeclined { name } => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+pub struct Overview {
+    accepted: Vec<String>,
+    pending: Vec<String>,
+    declined: Vec<String>,
+}
+
+// The answers for the organizer, none if nobody was invited.
+pub fn overview(draft: &Draft) -> Option<Overview> {
+    let mut overview = Overview {
+        accepted: Vec::new(),
+        pending: Vec::new(),
+        declined: declined(draft),
+    };
+    for member in &draft.members {
+        match member.invitation {
+            Some(Invitation::Accepted) => overview.accepted.push(member.name.clone()),
+            Some(Invitation::Pending) => overview.pending.push(member.name.clone()),
+            None => (),
+        }
+    }
+    if overview.accepted.is_empty() && overview.pending.is_empty() && overview.declined.is_empty() {
+        return None;
+    }
+    overview.accepted.sort();
+    overview.pending.sort();
+    Some(overview)
+}
+
+// Records the answer of the member with the token and draws the draft once
+// nobody is left to answer, unless a deadline will.
+fn respond(
+    id: usize,
+    key: &str,
+    accept: bool,
+    wishlist: Option<String>,
+    config: &AppConfig,
+    events: &events::Events,
+    drafts: &Drafts,
+) -> Result<(), Status> {
+    let wishlist = wishlist
+        .map(|wishlist| sanitize::text(&wishlist))
+        .filter(|wishlist| !wishlist.is_empty());
+    if wishlist
+        .as_ref()
+        .map_or(false, |wishlist| wishlist.chars().count() > MAX_WISHLIST)
+    {
+        return Err(Status::UnprocessableEntity);
+    }
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    let member = draft
+        .member_by_token(key)
+        .filter(|member| member.invitation.is_some())
+        .cloned()
+        .ok_or(Status::NotFound)?;
+    if draft.is_drawn() || draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    let mut member = draft.members.take(&member).ok_or(Status::NotFound)?;
+    let name = member.name.clone();
+    if accept {
+        if member.invitation == Some(Invitation::Pending) {
+            draft.record(
+                audit::Actor::Participant(name.clone()),
+                audit::Action::InvitationAccepted { name },
+            );
+        }
+        member.invitation = Some(Invitation::Accepted);
+        member.wishlist = wishlist;
+        draft.members.insert(member);
+    } else {
+        draft.record(
+            audit::Actor::Participant(name.clone()),
+            audit::Action::InvitationDeclined { name },
+        );
+    }
+    if pending(&draft) || draft.passphrases || draft.draw_at.is_some() {
+        return Ok(());
+    }
+    match draft
+        .validate(config)
+        .and_then(|_| draft.calculate_tickets(config.max_draw_attempts))
+    {
+        Ok(()) => {
+            draft.record(audit::Actor::System, audit::Action::Drawn);
+            events.publish(id, events::Event::DrawComplete);
+            let messages = push::drawn(&draft, id, config);
+            let emails = mail::drawn(config, id, &draft);
+            google::create_event(config, id, &draft, drafts);
+            drop(draft);
+            push::notify(config, messages);
+            mail::notify(emails);
+        }
+        // Left to the organizer, e.g. after too many declined.
+        Err(e) => tracing::warn!(id, error = %e, "could not draw draft after the invitations"),
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct InvitationContext {
+    id: usize,
+    key: String,
+    title: String,
+    name: String,
+    accepted: bool,
+    wishlist: String,
+    // Answers are taken until the draw.
+    open: bool,
+    theme: Option<String>,
+}
+
+#[get("/draft/<id>/invite/<key>")]
+pub fn show(id: usize, key: String, locale: Locale, drafts: State<Drafts>) -> Template {
+    let locale = language::for_key(id, &key, locale, &drafts);
+    let context = drafts.get(id).and_then(|entry| {
+        let draft = entry.read();
+        let member = draft
+            .member_by_token(&key)
+            .filter(|member| member.invitation.is_some())?;
+        Some(InvitationContext {
+            id,
+            key: key.clone(),
+            title: draft.title.clone(),
+            name: member.name.clone(),
+            accepted: member.invitation == Some(Invitation::Accepted),
+            wishlist: member.wishlist.clone().unwrap_or_default(),
+            open: !draft.is_drawn() && !draft.is_archived(),
+            theme: draft.theme.clone(),
+        })
+    });
+    match context {
+        Some(context) => i18n::render("invitation", locale, context),
+        None => {
+            let mut context = HashMap::new();
+            context.insert("id", id.to_string());
+            i18n::render("ticket_not_found", locale, context)
+        }
+    }
+}
+
+#[post("/api/draft/<id>/invite/<key>", format = "json", data = "<answer>")]
+pub fn api_respond(
+    id: usize,
+    key: String,
+    answer: Json<Answer>,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    let answer = answer.into_inner();
+    respond(
+        id,
+        &key,
+        answer.accept,
+        answer.wishlist,
+        &config,
+        &events,
+        &drafts,
+    )?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/invite/<key>", data = "<answer>")]
+pub fn answer(
+    id: usize,
+    key: String,
+    answer: Form<Answer>,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    let answer = answer.into_inner();
+    respond(
+        id,
+        &key,
+        answer.accept,
+        answer.wishlist,
+        &config,
+        &events,
+        &drafts,
+    )?;
+    if answer.accept {
+        Ok(Redirect::to(format!("/draft/{}/invite/{}", id, key)))
+    } else {
+        Ok(Redirect::to(format!("/draft/{}", id)))
+    }
+}
@@ -0,0 +1,68 @@
+use crate::i18n::Locale;
+use crate::Drafts;
+use rocket::http::Status;
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+
+pub use api::language::LanguageRequest;
+
+// The language of the member with the token, for the pages behind their
+// own link. Falls back to the one of the request.
+pub fn for_key(id: usize, key: &str, fallback: Locale, drafts: &Drafts) -> Locale {
+    drafts
+        .get(id)
+        .and_then(|entry| {
+            entry
+                .read()
+                .member_by_token(key)
+                .map(|m| m.locale(fallback))
+        })
+        .unwrap_or(fallback)
+}
+
+// Sets the language of the member with the token, none to follow the
+// browser again.
+fn set(id: usize, key: &str, code: Option<&str>, drafts: &Drafts) -> Result<(), Status> {
+    let language = match code.map(str::trim).filter(|code| !code.is_empty()) {
+        Some(code) => Some(Locale::from_code(code).ok_or(Status::UnprocessableEntity)?),
+        None => None,
+    };
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    let member = draft
+        .member_by_token(key)
+        .cloned()
+        .ok_or(Status::NotFound)?;
+    let mut member = draft.members.take(&member).ok_or(Status::NotFound)?;
+    member.language = language;
+    draft.members.insert(member);
+    Ok(())
+}
+
+#[put(
+    "/api/draft/<id>/ticket/<key>/language",
+    format = "json",
+    data = "<request>"
+)]
+pub fn api_set(
+    id: usize,
+    key: String,
+    request: Json<LanguageRequest>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    set(id, &key, request.language.as_deref(), &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/ticket/<key>/language", data = "<request>")]
+pub fn set_language(
+    id: usize,
+    key: String,
+    request: Form<LanguageRequest>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    set(id, &key, request.language.as_deref(), &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}/ticket/{}", id, key)))
+}
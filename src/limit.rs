@@ -0,0 +1,192 @@
+use crate::config::AppConfig;
+use crate::i18n::{self, Locale};
+use crate::Drafts;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::uri::Origin;
+use rocket::http::{Header, Method, Status};
+use rocket::request::{self, FromRequest};
+use rocket::response::{self, Responder};
+use rocket::{Data, Request, State};
+use rocket_contrib::templates::Template;
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const LIMITED: &str = "/limited";
+const WINDOW: Duration = Duration::from_secs(3600);
+
+// Why a request creating a draft was turned away.
+#[derive(Clone, Copy)]
+pub enum Rejection {
+    // Too many drafts from the address, seconds until it may try again.
+    Address(u64),
+    // The instance holds as many drafts as it may.
+    Full,
+}
+
+// The address of the client. `X-Real-IP` can be sent by anyone, so it is
+// only believed with `trusted_proxy` set, when a reverse proxy sets it.
+pub fn client_address(request: &Request, config: &AppConfig) -> Option<IpAddr> {
+    if config.trusted_proxy {
+        request.client_ip()
+    } else {
+        request.remote().map(|address| address.ip())
+    }
+}
+
+// The address of the client, for routes charging creations themselves.
+pub struct ClientAddress(pub Option<IpAddr>);
+
+impl<'a, 'r> FromRequest<'a, 'r> for ClientAddress {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        let address = request
+            .guard::<State<AppConfig>>()
+            .succeeded()
+            .and_then(|config| client_address(request, &config));
+        request::Outcome::Success(ClientAddress(address))
+    }
+}
+
+// Requests that create a single draft. A bulk request is charged by the
+// route, once it knows how many drafts it holds.
+fn creates_draft(request: &Request) -> bool {
+    let path = request.uri().path();
+    request.method() == Method::Post
+        && (path == "/draft"
+            || path == "/api/draft"
+            || (path.starts_with("/api/draft/") && path.ends_with("/clone")))
+}
+
+// The drafts created per client address within the last hour. Shared by
+// the fairing and the bulk creation.
+#[derive(Default, Clone)]
+pub struct Creations(Arc<Mutex<HashMap<IpAddr, VecDeque<Instant>>>>);
+
+impl Creations {
+    // Charges `count` drafts to the address, all of them or none, from
+    // `max_drafts_per_hour` and `max_drafts` of the app config.
+    pub fn charge(
+        &self,
+        config: &AppConfig,
+        drafts: &Drafts,
+        ip: Option<IpAddr>,
+        count: usize,
+    ) -> Result<(), Rejection> {
+        if let Some(max) = config.max_drafts {
+            if drafts.active().len() + count > max {
+                return Err(Rejection::Full);
+            }
+        }
+        let (max, ip) = match (config.max_drafts_per_hour, ip) {
+            (Some(max), Some(ip)) => (max as usize, ip),
+            _ => return Ok(()),
+        };
+        let now = Instant::now();
+        let mut created = self.0.lock().unwrap();
+        created.retain(|_, times| {
+            while times.front().map_or(false, |time| now - *time > WINDOW) {
+                times.pop_front();
+            }
+            !times.is_empty()
+        });
+        let times = created.entry(ip).or_insert_with(VecDeque::new);
+        if times.len() + count > max {
+            // Until enough of the earlier ones are older than the window.
+            let wait = times
+                .get((times.len() + count).saturating_sub(max + 1))
+                .map_or(WINDOW, |time| WINDOW - (now - *time));
+            return Err(Rejection::Address(wait.as_secs() + 1));
+        }
+        times.extend(std::iter::repeat(now).take(count));
+        Ok(())
+    }
+}
+
+// Limits the drafts created per client address and hour and the drafts
+// kept overall. Like the shutdown, a fairing can't answer a request
+// itself, so rejected ones are rerouted to a route answering 429.
+pub struct RateLimitFairing {
+    creations: Creations,
+}
+
+impl RateLimitFairing {
+    pub fn new(creations: Creations) -> RateLimitFairing {
+        RateLimitFairing { creations }
+    }
+
+    fn check(&self, request: &Request) -> Option<Rejection> {
+        let config = request.guard::<State<AppConfig>>().succeeded()?;
+        let drafts = request.guard::<State<Drafts>>().succeeded()?;
+        let ip = client_address(request, &config);
+        self.creations.charge(&config, &drafts, ip, 1).err()
+    }
+}
+
+impl Fairing for RateLimitFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Rate limit",
+            kind: Kind::Request,
+        }
+    }
+
+    fn on_request(&self, request: &mut Request, _: &Data) {
+        if !creates_draft(request) {
+            return;
+        }
+        if let Some(rejection) = self.check(request) {
+            tracing::warn!(ip = ?request.remote(), "draft creation rate limited");
+            request.local_cache(|| Some(rejection));
+            request.set_method(Method::Get);
+            request.set_uri(Origin::parse(LIMITED).unwrap());
+        }
+    }
+}
+
+pub struct Limited {
+    retry_after: Option<u64>,
+    page: Template,
+}
+
+impl<'r> Responder<'r> for Limited {
+    fn respond_to(self, request: &Request) -> response::Result<'r> {
+        let mut response = self.page.respond_to(request)?;
+        response.set_status(Status::TooManyRequests);
+        if let Some(seconds) = self.retry_after {
+            response.set_header(Header::new("Retry-After", seconds.to_string()));
+        }
+        Ok(response)
+    }
+}
+
+pub struct LimitReason(Option<Rejection>);
+
+impl<'a, 'r> FromRequest<'a, 'r> for LimitReason {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        request::Outcome::Success(LimitReason(*request.local_cache(|| None::<Rejection>)))
+    }
+}
+
+#[get("/limited")]
+pub fn limited(reason: LimitReason, locale: Locale) -> Limited {
+    let (retry_after, text) = match reason.0 {
+        Some(Rejection::Address(seconds)) => (
+            Some(seconds),
+            locale
+                .text("limit_address")
+                .replace("{minutes}", &((seconds + 59) / 60).to_string()),
+        ),
+        _ => (None, locale.text("limit_full").to_string()),
+    };
+    let mut context = HashMap::new();
+    context.insert("text", text);
+    Limited {
+        retry_after,
+        page: i18n::render("limited", locale, context),
+    }
+}
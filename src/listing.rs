@@ -0,0 +1,88 @@
+use crate::store::Entry;
+use serde_json::Value;
+use std::io::{self, Read};
+
+// Serializes drafts one at a time while the response is written, so a
+// listing never holds more than a single draft in memory.
+pub struct DraftStream {
+    entries: std::vec::IntoIter<Entry>,
+    fields: Option<Vec<String>>,
+    buffer: Vec<u8>,
+    position: usize,
+    started: bool,
+    finished: bool,
+}
+
+impl DraftStream {
+    // `fields` is a comma separated list of the top-level keys to keep,
+    // e.g. `title,date` to leave out the members.
+    pub fn new(entries: Vec<Entry>, fields: Option<&str>) -> DraftStream {
+        DraftStream {
+            entries: entries.into_iter(),
+            fields: fields.map(|fields| {
+                fields
+                    .split(',')
+                    .map(|field| field.trim().to_string())
+                    .filter(|field| field != "")
+                    .collect()
+            }),
+            buffer: Vec::new(),
+            position: 0,
+            started: false,
+            finished: false,
+        }
+    }
+
+    fn project(&self, value: Value) -> Value {
+        match (value, &self.fields) {
+            (Value::Object(object), Some(fields)) => Value::Object(
+                object
+                    .into_iter()
+                    .filter(|(key, _)| fields.contains(key))
+                    .collect(),
+            ),
+            (value, _) => value,
+        }
+    }
+
+    // The next piece of the array, `None` once it is closed.
+    fn next_chunk(&mut self) -> io::Result<Option<Vec<u8>>> {
+        if self.finished {
+            return Ok(None);
+        }
+        let entry = match self.entries.next() {
+            Some(entry) => entry,
+            None => {
+                self.finished = true;
+                let end = if self.started { "]" } else { "[]" };
+                return Ok(Some(end.as_bytes().to_vec()));
+            }
+        };
+        let draft = entry.read().public();
+        let value = self.project(serde_json::to_value(&draft)?);
+        let mut chunk = if self.started {
+            b",".to_vec()
+        } else {
+            b"[".to_vec()
+        };
+        self.started = true;
+        serde_json::to_writer(&mut chunk, &value)?;
+        Ok(Some(chunk))
+    }
+}
+
+impl Read for DraftStream {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.buffer.len() {
+            match self.next_chunk()? {
+                Some(chunk) => self.buffer = chunk,
+                None => return Ok(0),
+            }
+            self.position = 0;
+        }
+        let length = out.len().min(self.buffer.len() - self.position);
+        out[..length].copy_from_slice(&self.buffer[self.position..self.position + length]);
+        self.position += length;
+        Ok(length)
+    }
+}
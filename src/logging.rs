@@ -0,0 +1,142 @@
+use crate::config::AppConfig;
+use crate::i18n::{self, Locale};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::request::{self, FromRequest};
+use rocket::{Data, Request, Response};
+use rocket_contrib::templates::Template;
+use std::collections::HashMap;
+use tracing::{field, Span};
+use tracing_subscriber::EnvFilter;
+
+// `RUST_LOG` takes precedence over the configured log level.
+pub fn init(config: &AppConfig) {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(config.log_level.as_str()));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    let result = if config.log_format == "json" {
+        builder.json().try_init()
+    } else {
+        builder.try_init()
+    };
+    if let Err(e) = result {
+        eprintln!("Error: Could not initialize logging: {}", e);
+    }
+}
+
+fn draft_id(request: &Request) -> Option<String> {
+    let mut segments = request.uri().segments();
+    segments.find(|segment| *segment == "draft")?;
+    segments.next().map(String::from)
+}
+
+struct RequestSpan(Span);
+
+const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+// Identifies a request in the logs, sent back as `X-Request-Id` and shown
+// on error pages. An id set by a proxy in front is kept, so its logs match
+// as well.
+#[derive(Clone)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    fn of(request: &Request) -> RequestId {
+        request
+            .headers()
+            .get_one(REQUEST_ID_HEADER)
+            .filter(|id| {
+                !id.is_empty()
+                    && id.len() <= 64
+                    && id
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            })
+            .map(|id| RequestId(id.to_string()))
+            .unwrap_or_else(|| {
+                RequestId(
+                    rand::thread_rng()
+                        .sample_iter(&Alphanumeric)
+                        .take(16)
+                        .map(char::from)
+                        .collect(),
+                )
+            })
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for RequestId {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        request::Outcome::Success(request.local_cache(|| RequestId::of(request)).clone())
+    }
+}
+
+// Shown for errors escaping a handler, with the id to quote when
+// reporting them.
+#[catch(500)]
+pub fn internal_error(request: &Request) -> Template {
+    let locale = request
+        .guard::<Locale>()
+        .succeeded()
+        .unwrap_or(i18n::DEFAULT_LOCALE);
+    let id = request.local_cache(|| RequestId::of(request));
+    let mut context = HashMap::new();
+    context.insert("request_id", id.0.clone());
+    i18n::render("500", locale, context)
+}
+
+// Rocket 0.4 handles a request on a single thread, so the span entered
+// here stays current for the handler until the response is finished.
+pub struct TracingFairing;
+
+impl Fairing for TracingFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Tracing",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    fn on_request(&self, request: &mut Request, _: &Data) {
+        let id = request.local_cache(|| RequestId::of(request)).0.clone();
+        let span = tracing::info_span!(
+            "request",
+            request_id = id.as_str(),
+            method = %request.method(),
+            uri = %request.uri(),
+            route = field::Empty,
+            draft = field::Empty,
+            status = field::Empty,
+        );
+        if let Some(id) = span.id() {
+            tracing::dispatcher::get_default(|dispatch| dispatch.enter(&id));
+        }
+        request.local_cache(|| RequestSpan(span));
+    }
+
+    fn on_response(&self, request: &Request, response: &mut Response) {
+        let id = request.local_cache(|| RequestId::of(request));
+        response.set_header(Header::new(REQUEST_ID_HEADER, id.0.clone()));
+        let span = &request.local_cache(|| RequestSpan(Span::none())).0;
+        if let Some(route) = request.route() {
+            span.record("route", &route.uri.path());
+        }
+        if let Some(draft) = draft_id(request) {
+            span.record("draft", &draft.as_str());
+        }
+        let status = response.status().code;
+        span.record("status", &status);
+        if status >= 500 {
+            tracing::error!(parent: span, status, "request failed");
+        } else {
+            tracing::info!(parent: span, status, "request finished");
+        }
+        if let Some(id) = span.id() {
+            tracing::dispatcher::get_default(|dispatch| dispatch.exit(&id));
+        }
+    }
+}
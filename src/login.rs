@@ -0,0 +1,198 @@
+use crate::config::AppConfig;
+use crate::i18n::{self, Locale};
+use crate::{events, generate_token, mail, qr, show_ticket, Drafts, Member};
+use chrono::{DateTime, Duration, Utc};
+use rocket::http::{Cookie, Cookies};
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::templates::Template;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Minutes a login link stays valid.
+pub const LINK_MINUTES: i64 = 15;
+
+struct Link {
+    draft: usize,
+    name: String,
+    expires: DateTime<Utc>,
+}
+
+// Login links sent but not used yet. They are only kept in memory, a
+// restart just means requesting a new one.
+#[derive(Default)]
+pub struct Links(Mutex<HashMap<String, Link>>);
+
+impl Links {
+    fn issue(&self, draft: usize, name: String) -> String {
+        let mut links = self.0.lock().unwrap();
+        let now = Utc::now();
+        links.retain(|_, link| link.expires > now);
+        let token = generate_token();
+        links.insert(
+            token.clone(),
+            Link {
+                draft,
+                name,
+                expires: now + Duration::minutes(LINK_MINUTES),
+            },
+        );
+        token
+    }
+
+    // The member the link signs in, once.
+    fn redeem(&self, draft: usize, token: &str) -> Option<String> {
+        let link = self.0.lock().unwrap().remove(token)?;
+        if link.draft != draft || link.expires <= Utc::now() {
+            return None;
+        }
+        Some(link.name)
+    }
+}
+
+fn cookie(id: usize) -> String {
+    format!("participant-{}", id)
+}
+
+fn has_email(member: &Member) -> bool {
+    member
+        .email
+        .as_deref()
+        .map_or(false, |email| !email.trim().is_empty())
+}
+
+// Once emails can be sent, a member with an address only opens their
+// ticket after signing in by login link, so a forwarded ticket link stops
+// being enough.
+pub fn required(member: &Member, config: &AppConfig) -> bool {
+    config.smtp.is_some() && has_email(member)
+}
+
+// The name of the member signed in to the draft.
+pub fn signed_in(id: usize, cookies: &mut Cookies) -> Option<String> {
+    cookies
+        .get_private(&cookie(id))
+        .map(|cookie| cookie.value().to_string())
+}
+
+#[derive(Serialize)]
+struct RequestContext {
+    id: usize,
+    title: String,
+    sent: bool,
+    unavailable: bool,
+}
+
+#[derive(FromForm)]
+pub struct LoginRequest {
+    email: String,
+}
+
+#[get("/draft/<id>/login")]
+pub fn show_request(id: usize, locale: Locale, drafts: State<Drafts>) -> Option<Template> {
+    let entry = drafts.get(id)?;
+    let title = entry.read().title.clone();
+    Some(i18n::render(
+        "ticket_request",
+        locale,
+        RequestContext {
+            id,
+            title,
+            sent: false,
+            unavailable: false,
+        },
+    ))
+}
+
+// Answers the same whether or not the address belongs to a member, so the
+// form doesn't tell who takes part.
+#[post("/draft/<id>/ticket/request", data = "<request>")]
+pub fn request(
+    id: usize,
+    request: Form<LoginRequest>,
+    locale: Locale,
+    origin: qr::Origin,
+    config: State<AppConfig>,
+    links: State<Links>,
+    drafts: State<Drafts>,
+) -> Option<Template> {
+    let entry = drafts.get(id)?;
+    let draft = entry.read();
+    let email = request.email.trim().to_lowercase();
+    let member = draft.members.iter().find(|member| {
+        member
+            .email
+            .as_deref()
+            .map_or(false, |address| address.trim().to_lowercase() == email)
+    });
+    let mut unavailable = config.smtp.is_none();
+    if let (Some(member), false) = (member, unavailable) {
+        let token = links.issue(id, member.name.clone());
+        let url = origin.login_url(id, &token);
+        let locale = member.locale(locale);
+        let message = mail::Message {
+            subject: locale
+                .text("mail_login_subject")
+                .replace("{title}", &draft.title),
+            body: locale
+                .text("mail_login_body")
+                .replace("{name}", &member.name)
+                .replace("{url}", &url)
+                .replace("{minutes}", &LINK_MINUTES.to_string()),
+        };
+        unavailable = !mail::send(&config, request.email.trim().to_string(), message);
+    }
+    Some(i18n::render(
+        "ticket_request",
+        locale,
+        RequestContext {
+            id,
+            title: draft.title.clone(),
+            sent: !unavailable,
+            unavailable,
+        },
+    ))
+}
+
+#[get("/draft/<id>/login/<token>")]
+pub fn redeem(id: usize, token: String, links: State<Links>, mut cookies: Cookies) -> Redirect {
+    match links.redeem(id, &token) {
+        Some(name) => {
+            cookies.add_private(
+                Cookie::build(cookie(id), name)
+                    .path("/")
+                    .http_only(true)
+                    .finish(),
+            );
+            Redirect::to(format!("/draft/{}/ticket", id))
+        }
+        None => Redirect::to(format!("/draft/{}/login", id)),
+    }
+}
+
+// The ticket of the member signed in through a login link.
+#[get("/draft/<id>/ticket")]
+pub fn show_own_ticket(
+    id: usize,
+    locale: Locale,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+    mut cookies: Cookies,
+) -> Result<Template, Redirect> {
+    let name = signed_in(id, &mut cookies);
+    let token = drafts.get(id).and_then(|entry| {
+        let draft = entry.read();
+        draft
+            .members
+            .iter()
+            .find(|member| Some(&member.name) == name.as_ref())
+            .map(|member| member.token.clone())
+    });
+    match token {
+        Some(token) => show_ticket(id, token, locale, config, events, drafts, cookies),
+        None => Err(Redirect::to(format!("/draft/{}/login", id))),
+    }
+}
@@ -0,0 +1,326 @@
+use crate::config::{AppConfig, SmtpConfig};
+use crate::delivery::{self, Kind, Target};
+use crate::i18n::Locale;
+use crate::outbox::{self, Link, Notification};
+use crate::{
+    calendar, elephant, export, preview, push, Draft, DraftError, Drafts, Member, Organizer,
+};
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{SmtpTransport, Transport};
+use rocket::http::Status;
+use rocket::response::status;
+use rocket::State;
+use rocket_contrib::json::Json;
+use rocket_contrib::templates::tera::{Context, Tera};
+use serde::{Deserialize, Serialize};
+
+pub use api::mail::{Emails, Message, Templates};
+
+// The name of the first template that doesn't render, e.g. because it uses
+// a variable other than `giver`, `ticket_url` and `budget`.
+pub fn invalid(templates: &Templates) -> Option<&'static str> {
+    let sample = Variables {
+        giver: "Anna".to_string(),
+        ticket_url: "/draft/0/ticket/token".to_string(),
+        budget: Some(20),
+    };
+    let rendered = [
+        (
+            "ticket_subject",
+            templates.ticket.as_ref().map(|m| &m.subject),
+        ),
+        ("ticket_body", templates.ticket.as_ref().map(|m| &m.body)),
+        (
+            "reminder_subject",
+            templates.reminder.as_ref().map(|m| &m.subject),
+        ),
+        (
+            "reminder_body",
+            templates.reminder.as_ref().map(|m| &m.body),
+        ),
+    ];
+    rendered.iter().find_map(|(name, template)| {
+        template
+            .filter(|template| render(template, &sample).is_err())
+            .map(|_| *name)
+    })
+}
+
+#[derive(Serialize)]
+struct Variables {
+    giver: String,
+    ticket_url: String,
+    budget: Option<u32>,
+}
+
+fn render(template: &str, variables: &Variables) -> Result<String, String> {
+    let mut context = Context::new();
+    context.insert("giver", &variables.giver);
+    context.insert("ticket_url", &variables.ticket_url);
+    context.insert("budget", &variables.budget);
+    Tera::one_off(template, &context, false).map_err(|e| e.to_string())
+}
+
+fn message(
+    template: Option<&Message>,
+    subject: &str,
+    body: &str,
+    variables: &Variables,
+) -> Result<Message, String> {
+    let (subject, body) = match template {
+        Some(template) => (template.subject.as_str(), template.body.as_str()),
+        None => (subject, body),
+    };
+    Ok(Message {
+        subject: render(subject, variables)?,
+        body: render(body, variables)?,
+    })
+}
+
+// A file sent along with a message, e.g. the event of the draft.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct File {
+    pub filename: String,
+    pub content_type: String,
+    pub body: String,
+}
+
+pub fn deliver(
+    config: &SmtpConfig,
+    to: &str,
+    message: &Message,
+    file: Option<&File>,
+) -> Result<(), delivery::Error> {
+    let builder = lettre::Message::builder()
+        .from(config.from.parse::<Mailbox>().map_err(|e| e.to_string())?)
+        .to(to.parse::<Mailbox>().map_err(|e| e.to_string())?)
+        .subject(message.subject.as_str());
+    let email = match file {
+        Some(file) => {
+            let content_type = ContentType::parse(&file.content_type).map_err(|e| e.to_string())?;
+            builder.multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::plain(message.body.clone()))
+                    .singlepart(
+                        Attachment::new(file.filename.clone())
+                            .body(file.body.clone(), content_type),
+                    ),
+            )
+        }
+        None => builder.body(message.body.clone()),
+    }
+    .map_err(|e| e.to_string())?;
+    let mut transport = SmtpTransport::starttls_relay(&config.host)
+        .map_err(|e| e.to_string())?
+        .port(config.port);
+    if let (Some(username), Some(password)) = (&config.username, &config.password) {
+        transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+    transport
+        .build()
+        .send(&email)
+        .map(|_| ())
+        .map_err(|e| delivery::Error {
+            // The server refused the address or the message for good.
+            bounced: e.is_permanent(),
+            message: e.to_string(),
+        })
+}
+
+// Sends the message through the outbox like the push notifications.
+// Returns false if no mail server is configured.
+pub fn send(config: &AppConfig, to: String, message: Message) -> bool {
+    send_with(config, to, message, None, None, None)
+}
+
+// With a file attached, its delivery tracked for the organizer if it is
+// for a member, and `outbox::LINK` in the body standing for `link`.
+pub fn send_with(
+    config: &AppConfig,
+    to: String,
+    message: Message,
+    file: Option<File>,
+    target: Option<Target>,
+    link: Option<Link>,
+) -> bool {
+    if config.smtp.is_none() {
+        return false;
+    }
+    let notification = Notification::Email {
+        to,
+        message,
+        file,
+        link,
+    };
+    outbox::enqueue(notification, target);
+    true
+}
+
+// The emails `member` gets, from the organizer's templates or else the
+// default wording.
+pub fn emails(
+    draft: &Draft,
+    member: &Member,
+    ticket_url: String,
+    locale: Locale,
+) -> Result<Emails, String> {
+    let locale = member.locale(locale);
+    let variables = Variables {
+        giver: member.name.clone(),
+        ticket_url,
+        budget: draft.budget_for(member),
+    };
+    Ok(Emails {
+        ticket: message(
+            draft.emails.ticket.as_ref(),
+            locale.text("mail_ticket_subject"),
+            locale.text("mail_ticket_body"),
+            &variables,
+        )?,
+        reminder: message(
+            draft.emails.reminder.as_ref(),
+            locale.text("mail_reminder_subject"),
+            locale.text("mail_reminder_body"),
+            &variables,
+        )?,
+    })
+}
+
+// The event of the draft, for the members to add it to their calendar.
+fn event(id: usize, draft: &Draft, locale: Locale) -> File {
+    File {
+        filename: export::filename(draft, "ics"),
+        content_type: "text/calendar; charset=utf-8; method=PUBLISH".to_string(),
+        body: calendar::event(id, draft, locale),
+    }
+}
+
+// The ticket emails of every member with an email once the draft is
+// drawn, with the event attached, like the push notifications. Built
+// while the draft is locked, and sent with `notify` after.
+pub fn drawn(config: &AppConfig, id: usize, draft: &Draft) -> Vec<(Notification, Option<Target>)> {
+    if config.smtp.is_none() {
+        return Vec::new();
+    }
+    draft
+        .members
+        .iter()
+        .filter_map(|member| ticket_email(config, id, draft, member))
+        .collect()
+}
+
+// Hands the emails to the outbox at once.
+pub fn notify(emails: Vec<(Notification, Option<Target>)>) {
+    outbox::enqueue_all(emails);
+}
+
+// The ticket email of one member, if they have an email address.
+fn ticket_email(
+    config: &AppConfig,
+    id: usize,
+    draft: &Draft,
+    member: &Member,
+) -> Option<(Notification, Option<Target>)> {
+    // A white elephant game has no tickets to look at.
+    if draft.mode == elephant::Mode::WhiteElephant {
+        return None;
+    }
+    let email = member
+        .email
+        .as_deref()
+        .map(str::trim)
+        .filter(|email| !email.is_empty())?;
+    match emails(
+        draft,
+        member,
+        outbox::LINK.to_string(),
+        config.default_locale(),
+    ) {
+        Ok(emails) => {
+            let target = Target {
+                draft: id,
+                member: member.name.clone(),
+                kind: Kind::Ticket,
+            };
+            let notification = Notification::Email {
+                to: email.to_string(),
+                message: emails.ticket,
+                file: Some(event(id, draft, member.locale(config.default_locale()))),
+                link: Some(push::ticket_link(config, id, member)),
+            };
+            Some((notification, Some(target)))
+        }
+        Err(e) => {
+            tracing::warn!(id, error = e.as_str(), "could not render ticket email");
+            None
+        }
+    }
+}
+
+// Sends the ticket email of one member again, e.g. after a reassignment.
+pub fn ticket(config: &AppConfig, id: usize, draft: &Draft, member: &Member) {
+    if config.smtp.is_none() {
+        return;
+    }
+    if let Some(email) = ticket_email(config, id, draft, member) {
+        outbox::enqueue_all(vec![email]);
+    }
+}
+
+#[put("/api/draft/<id>/emails", format = "json", data = "<templates>")]
+pub fn api_save(
+    id: usize,
+    templates: Json<Templates>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Status, status::Custom<String>> {
+    let entry = drafts
+        .get(id)
+        .ok_or_else(|| status::Custom(Status::NotFound, String::new()))?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(status::Custom(Status::Forbidden, String::new()));
+    }
+    if draft.is_archived() {
+        return Err(status::Custom(Status::Conflict, String::new()));
+    }
+    let templates = templates.into_inner();
+    if let Some(template) = invalid(&templates) {
+        let e = DraftError::InvalidEmailTemplate { template };
+        return Err(status::Custom(Status::UnprocessableEntity, e.to_string()));
+    }
+    draft.emails = templates;
+    Ok(Status::NoContent)
+}
+
+// The emails of one member as they would be sent, for the organizer to
+// check the wording. The link carries a placeholder instead of the
+// member's token, like in `preview`.
+#[get("/api/draft/<id>/emails/<name>")]
+pub fn api_preview(
+    id: usize,
+    name: String,
+    locale: Locale,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Json<Emails>, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    let member = draft.find_member(&name).ok_or(Status::NotFound)?;
+    let mut placeholder = member.clone();
+    placeholder.token = preview::PLACEHOLDER.to_string();
+    emails(
+        &draft,
+        member,
+        push::ticket_url(&config, id, &placeholder),
+        locale,
+    )
+    .map(Json)
+    .map_err(|_| Status::UnprocessableEntity)
+}
@@ -2,27 +2,124 @@
 #[macro_use]
 extern crate rocket;
 
-use rand::seq::SliceRandom;
-use rocket::request::{Form, FormItems, FromForm};
-use rocket::response::Redirect;
+mod address;
+mod admin;
+mod archive;
+mod assets;
+mod audit;
+mod avatar;
+mod avoid;
+mod backup;
+mod bulk;
+mod bundle;
+mod calendar;
+mod cards;
+mod ceremony;
+mod cli;
+mod comment;
+mod compression;
+mod config;
+mod crypto;
+mod deadline;
+mod delivery;
+mod elephant;
+mod events;
+mod export;
+mod external;
+mod feed;
+mod gift;
+mod google;
+mod health;
+mod i18n;
+mod idempotency;
+mod import;
+mod invite;
+mod language;
+mod limit;
+mod listing;
+mod logging;
+mod login;
+mod mail;
+mod matcher;
+mod metrics;
+mod organizers;
+mod outbox;
+mod passphrase;
+mod persistence;
+mod plain;
+mod poll;
+mod pool;
+mod preset;
+mod preview;
+mod push;
+mod qr;
+mod reassign;
+mod retention;
+mod sanitize;
+#[cfg(any(test, feature = "solver-debug"))]
+mod scenarios;
+mod security;
+mod series;
+mod share;
+mod shutdown;
+mod simulate;
+#[cfg(feature = "solver-debug")]
+mod solver_debug;
+mod spending;
+mod stats;
+mod store;
+mod suggest;
+mod thanks;
+mod theme;
+mod timeline;
+mod validation;
+mod verify;
+
+use api::draft::{CloneRequest, CreatedDraft, SwapRequest};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use config::AppConfig;
+use i18n::Locale;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use rocket::http::{ContentType, Cookie, Cookies, Status};
+use rocket::request::{self, Form, FormDataError, FormItems, FromForm, FromRequest, Request};
+use rocket::response::content::Content;
+use rocket::response::{status, Redirect, Stream};
 use rocket::State;
 use rocket_contrib::json::Json;
-use rocket_contrib::serve::StaticFiles;
 use rocket_contrib::templates::Template;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
-use std::sync::RwLock;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 //use std::cmp::{Eq, PartialEq};
 use std::hash::{Hash, Hasher};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum DraftError {
     InvalidData,
-    MemberAlreadyDefined,
+    NameMissing,
+    NameTooLong { max: usize },
+    InvalidTeam { name: String },
+    MemberAlreadyDefined { name: String },
     NotEnoughPossibilities,
     NoTeamOrNameDefined,
-    CalculateAgain,
+    Infeasible { attempts: u32, member: String },
+    InvalidDate,
+    InvalidTimezone,
+    DateInPast,
+    TooManyMembers { max: usize },
+    TitleMissing,
+    TitleTooLong { max: usize },
+    TooFewTeams { min: usize },
+    TeamTooLarge { team: u32 },
+    InvalidBudget,
+    UnknownBudgetTeam { team: u32 },
+    DuplicatePool { name: String },
+    InvalidEmailTemplate { template: &'static str },
+    PassphraseMissing { name: String },
 }
 
 impl std::error::Error for DraftError {}
@@ -31,30 +128,232 @@ impl fmt::Display for DraftError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DraftError::InvalidData => f.write_str("Invalid form data"),
-            DraftError::MemberAlreadyDefined => f.write_str("Member was already defined"),
-            DraftError::NotEnoughPossibilities => f.write_str("Not enough possibilites"),
+            DraftError::NameMissing => f.write_str("A member has no name"),
+            DraftError::NameTooLong { max } => {
+                write!(f, "A name is longer than {} characters", max)
+            }
+            DraftError::InvalidTeam { name } => write!(f, "The team of {} is not a number", name),
+            DraftError::MemberAlreadyDefined { name } => {
+                write!(f, "Member {} was already defined", name)
+            }
+            DraftError::NotEnoughPossibilities => f.write_str("Not enough possibilities"),
             DraftError::NoTeamOrNameDefined => f.write_str("No team or name defined"),
-            DraftError::CalculateAgain => {
-                f.write_str("Took wrong path on caluclation, calculate again")
+            DraftError::Infeasible { attempts, member } => write!(
+                f,
+                "No draw found in {} attempts, {} was left without a ticket",
+                attempts, member
+            ),
+            DraftError::InvalidDate => f.write_str("Invalid date"),
+            DraftError::InvalidTimezone => f.write_str("Unknown timezone"),
+            DraftError::DateInPast => f.write_str("The date of the draft lies in the past"),
+            DraftError::TooManyMembers { max } => write!(f, "More than {} members", max),
+            DraftError::TitleMissing => f.write_str("No title defined"),
+            DraftError::TitleTooLong { max } => {
+                write!(f, "The title is longer than {} characters", max)
+            }
+            DraftError::TooFewTeams { min } => write!(f, "Less than {} teams", min),
+            DraftError::TeamTooLarge { team } => {
+                write!(f, "Team {} has more than half of the members", team)
+            }
+            DraftError::InvalidBudget => f.write_str("A budget has to be more than zero"),
+            DraftError::UnknownBudgetTeam { team } => {
+                write!(f, "Budget for team {} which has no members", team)
+            }
+            DraftError::DuplicatePool { name } => write!(f, "Pool {} is defined twice", name),
+            DraftError::InvalidEmailTemplate { template } => write!(
+                f,
+                "The email template {} has errors or unknown variables",
+                template
+            ),
+            DraftError::PassphraseMissing { name } => {
+                write!(f, "{} has not set a passphrase yet", name)
+            }
+        }
+    }
+}
+
+impl DraftError {
+    // Status the API answers with, 413 for drafts larger than the instance
+    // allows.
+    fn status(&self) -> Status {
+        match self {
+            DraftError::TooManyMembers { .. } => Status::PayloadTooLarge,
+            _ => Status::UnprocessableEntity,
+        }
+    }
+
+    // The form field the error is shown at.
+    fn field(&self) -> &'static str {
+        match self {
+            DraftError::TitleMissing | DraftError::TitleTooLong { .. } => "title",
+            DraftError::InvalidDate | DraftError::InvalidTimezone | DraftError::DateInPast => {
+                "date"
             }
+            DraftError::InvalidData => "form",
+            DraftError::InvalidBudget | DraftError::UnknownBudgetTeam { .. } => "budget",
+            DraftError::DuplicatePool { .. } => "pools",
+            DraftError::InvalidEmailTemplate { .. } => "emails",
+            _ => "members",
         }
     }
+
+    fn localized(&self, locale: Locale) -> String {
+        let key = match self {
+            DraftError::InvalidData => "error_invalid_data",
+            DraftError::NameMissing => "error_name_missing",
+            DraftError::NameTooLong { max } => {
+                return locale
+                    .text("error_name_too_long")
+                    .replace("{max}", &max.to_string())
+            }
+            DraftError::InvalidTeam { name } => {
+                return locale.text("error_invalid_team").replace("{name}", name)
+            }
+            DraftError::MemberAlreadyDefined { name } => {
+                return locale
+                    .text("error_member_already_defined")
+                    .replace("{name}", name)
+            }
+            DraftError::NotEnoughPossibilities => "error_not_enough_possibilities",
+            DraftError::NoTeamOrNameDefined => "error_no_team_or_name",
+            DraftError::Infeasible { attempts, member } => {
+                return locale
+                    .text("error_infeasible")
+                    .replace("{attempts}", &attempts.to_string())
+                    .replace("{name}", member)
+            }
+            DraftError::InvalidDate => "error_invalid_date",
+            DraftError::InvalidTimezone => "error_invalid_timezone",
+            DraftError::DateInPast => "error_date_in_past",
+            DraftError::TooManyMembers { max } => {
+                return locale
+                    .text("error_too_many_members")
+                    .replace("{max}", &max.to_string())
+            }
+            DraftError::TitleMissing => "error_title_missing",
+            DraftError::TitleTooLong { max } => {
+                return locale
+                    .text("error_title_too_long")
+                    .replace("{max}", &max.to_string())
+            }
+            DraftError::TooFewTeams { min } => {
+                return locale
+                    .text("error_too_few_teams")
+                    .replace("{min}", &min.to_string())
+            }
+            DraftError::TeamTooLarge { team } => {
+                return locale
+                    .text("error_team_too_large")
+                    .replace("{team}", &team.to_string())
+            }
+            DraftError::InvalidBudget => "error_invalid_budget",
+            DraftError::UnknownBudgetTeam { team } => {
+                return locale
+                    .text("error_unknown_budget_team")
+                    .replace("{team}", &team.to_string())
+            }
+            DraftError::DuplicatePool { name } => {
+                return locale.text("error_duplicate_pool").replace("{name}", name)
+            }
+            DraftError::InvalidEmailTemplate { template } => {
+                return locale
+                    .text("error_invalid_email_template")
+                    .replace("{template}", template)
+            }
+            DraftError::PassphraseMissing { name } => {
+                return locale
+                    .text("error_passphrase_missing")
+                    .replace("{name}", name)
+            }
+        };
+        locale.text(key).to_string()
+    }
 }
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 struct Member {
     name: String,
     team: u32,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pool: Option<String>,
     ticket: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    token: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    push: Vec<push::Subscription>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    avatar: Option<avatar::Avatar>,
+    // Only set in the zero-knowledge mode, see `passphrase`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    keys: Option<passphrase::Keys>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    locked: Option<passphrase::Locked>,
+    // When the member confirmed they got their gift.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    received: Option<DateTime<Utc>>,
+    // The thank-you note the member wrote to their santa.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thanks: Option<thanks::Note>,
+    // Only set for members invited by email, see `invite`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    invitation: Option<invite::Invitation>,
+    // What the member wished for when accepting, for their giver.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wishlist: Option<String>,
+    // The language the member gets their ticket page and messages in,
+    // whatever the organizer's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    language: Option<Locale>,
+    // Where the member's gift is mailed to, sealed like the ticket. See
+    // `address`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    // What the member spent on their gift, in whole euros. See `spending`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    spent: Option<u32>,
+}
+
+// Names are compared sanitized and case-folded, so that "Anna" and
+// " anna" are the same member.
+fn name_key(name: &str) -> String {
+    sanitize::line(name).to_lowercase()
 }
 
 impl Member {
     fn new(name: String, team: u32) -> Member {
         Member {
-            name,
+            name: sanitize::line(&name),
             team,
+            email: None,
+            pool: None,
             ticket: None,
+            token: generate_token(),
+            push: Vec::new(),
+            avatar: None,
+            keys: None,
+            locked: None,
+            received: None,
+            thanks: None,
+            invitation: None,
+            wishlist: None,
+            language: None,
+            address: None,
+            spent: None,
         }
     }
+
+    // The member's own language, else the one of the request or instance.
+    fn locale(&self, fallback: Locale) -> Locale {
+        self.language.unwrap_or(fallback)
+    }
+
+    // The name of the recipient, as the ticket is stored encrypted if a
+    // key is configured.
+    fn recipient(&self) -> Option<String> {
+        self.ticket.as_deref().and_then(crypto::open)
+    }
 }
 
 impl Hash for Member {
@@ -66,8 +365,85 @@ impl Hash for Member {
 #[derive(Deserialize, Serialize, Debug, Clone)]
 struct Draft {
     title: String,
-    date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    slug: Option<String>,
+    #[serde(default)]
+    mode: elephant::Mode,
+    // Turn order and steal rules of a white elephant game.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    turns: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rules: Option<elephant::Rules>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pools: Vec<pool::Pool>,
+    // Spending limits in whole euros, for everyone and for single teams.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    budget: Option<u32>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    team_budgets: BTreeMap<u32, u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    polls: Vec<poll::Poll>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    comments: Vec<comment::Comment>,
+    #[serde(default, skip_serializing_if = "mail::Templates::is_empty")]
+    emails: mail::Templates,
+    // Zero-knowledge mode: tickets are encrypted for the passphrase every
+    // member sets before the draw, so nobody else can read them.
+    #[serde(default)]
+    passphrases: bool,
+    // Organizer-blind mode: tickets open only on the members' own links, so
+    // the organizer sees nothing but the progress.
+    #[serde(default)]
+    blind: bool,
+    // See `theme`, none for the default look.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    theme: Option<String>,
+    // Pairs drawn only if nothing else works, and those of them the last
+    // draw had to take. See `avoid`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    avoid: Vec<avoid::Avoid>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    violated: Vec<avoid::Avoid>,
+    // Whether the members are asked what they spent, see `spending`.
+    #[serde(default)]
+    spending: bool,
+    // Members asking for another recipient, see `reassign`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    reassign: Vec<reassign::Request>,
+    // Whether everyone may see who gave to whom, once the draft is archived.
+    #[serde(default)]
+    revealed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    series: Option<series::Series>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    google: Option<google::Grant>,
+    // Members can add themselves until then, then the draft is drawn.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    draw_at: Option<DateTime<Utc>>,
+    date: DateTime<FixedOffset>,
+    timezone: Tz,
     members: HashSet<Member>,
+    #[serde(default = "Utc::now")]
+    created: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    retain_until: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    trashed: Option<DateTime<Utc>>,
+    // Whether the push reminder before the exchange was sent.
+    #[serde(default)]
+    reminded: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    organizer_token: String,
+    // Organizers next to the owner and those invited, see `organizers`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    co_organizers: Vec<organizers::CoOrganizer>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    organizer_invitations: Vec<organizers::Invitation>,
+    // Token of the read-only link the organizer can post, see `share`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    share_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    audit: Vec<audit::Entry>,
 }
 
 impl fmt::Display for Draft {
@@ -80,152 +456,895 @@ impl fmt::Display for Draft {
                 None => format!("{}", member.name),
             })
             .collect::<Vec<String>>();
-        f.write_str(&format!("{}\n{}\n{:?}", self.title, self.date, members))
+        f.write_str(&format!(
+            "{}\n{}\n{:?}",
+            self.title,
+            self.local_date(),
+            members
+        ))
     }
 }
 
 impl Draft {
-    fn team_possibilities(&self, team: u32) -> u32 {
-        self.members
+    // The draft as the API answers it to anyone, without tokens and
+    // tickets. Whether it is drawn is told apart.
+    fn public(&self) -> api::draft::Draft {
+        let mut members = self
+            .members
             .iter()
-            .fold(0, |x, member| if member.team != team { x + 1 } else { x })
+            .map(|member| api::draft::Member {
+                name: member.name.clone(),
+                team: member.team,
+                email: member.email.clone(),
+                pool: member.pool.clone(),
+                ticket: None,
+            })
+            .collect::<Vec<api::draft::Member>>();
+        members.sort_by(|a, b| a.name.cmp(&b.name));
+        api::draft::Draft {
+            title: self.title.clone(),
+            slug: self.slug.clone(),
+            mode: self.mode,
+            pools: self.pools.clone(),
+            budget: self.budget,
+            team_budgets: self.team_budgets.clone(),
+            passphrases: self.passphrases,
+            blind: self.blind,
+            theme: self.theme.clone(),
+            revealed: self.revealed,
+            draw_at: self.draw_at,
+            date: self.date,
+            timezone: self.timezone,
+            members,
+            created: self.created,
+            drawn: self.is_drawn(),
+        }
+    }
+    // The draft as anyone may see it, without tokens and tickets.
+    fn redacted(&self) -> Draft {
+        let mut draft = self.organizer_view();
+        draft.members = draft
+            .members
+            .into_iter()
+            .map(|mut member| {
+                member.ticket = None;
+                member
+            })
+            .collect();
+        draft
     }
-    fn team_len(&self, team: u32) -> u32 {
+    // Like `redacted`, but with the tickets, unless the organizer is blind
+    // to them. Only for the organizer's export of the assignments.
+    fn organizer_view(&self) -> Draft {
+        let mut draft = self.clone();
+        draft.organizer_token = String::new();
+        draft.co_organizers = Vec::new();
+        draft.organizer_invitations = Vec::new();
+        draft.share_token = None;
+        draft.avoid = Vec::new();
+        draft.violated = Vec::new();
+        draft.reassign = Vec::new();
+        draft.google = None;
+        draft.audit = Vec::new();
+        for poll in &mut draft.polls {
+            poll.votes.clear();
+        }
+        if let Some(series) = draft.series.as_mut() {
+            series.history.clear();
+        }
+        let blind = draft.blind;
+        draft.members = draft
+            .members
+            .into_iter()
+            .map(|mut member| {
+                if blind {
+                    member.ticket = None;
+                }
+                member.token = String::new();
+                member.push = Vec::new();
+                member.keys = None;
+                member.locked = None;
+                member.thanks = None;
+                member.wishlist = None;
+                member.address = None;
+                member.spent = None;
+                member.avatar = avatar::of(&member);
+                member
+            })
+            .collect();
+        draft
+    }
+    fn record(&mut self, actor: audit::Actor, action: audit::Action) {
+        self.audit.push(audit::Entry::new(actor, action));
+    }
+    fn trash(&mut self, actor: audit::Actor) {
+        self.trashed = Some(Utc::now());
+        self.record(actor, audit::Action::Trashed);
+    }
+    // Takes the draft out of the trash, keeping it for another retention
+    // period if it would be swept again right away.
+    fn restore(&mut self, actor: audit::Actor, config: &AppConfig) {
+        self.trashed = None;
+        if let Some(days) = config.retention_days {
+            if self
+                .expires(Some(days))
+                .map_or(false, |date| date <= Utc::now())
+            {
+                self.retain_until = Some(Utc::now() + Duration::days(days));
+            }
+        }
+        self.record(actor, audit::Action::Restored);
+    }
+    // The member whose token the key is, given who is signed in by login
+    // link. Names never open a ticket, only the member's private link.
+    fn view_ticket(
+        &mut self,
+        key: &str,
+        signed_in: Option<&str>,
+        config: &AppConfig,
+    ) -> Option<Member> {
+        let member = self.member_by_token(key)?.clone();
+        if login::required(&member, config) && signed_in != Some(member.name.as_str()) {
+            return None;
+        }
+        if member.ticket.is_some() || member.locked.is_some() {
+            self.record(
+                audit::Actor::Participant(member.name.clone()),
+                audit::Action::TicketViewed {
+                    name: member.name.clone(),
+                },
+            );
+        }
+        Some(member)
+    }
+    // What to spend on a gift for the member: the budget of their pool,
+    // else of their team, else of the draft.
+    fn budget_for(&self, member: &Member) -> Option<u32> {
+        let pool = self
+            .pools
+            .iter()
+            .find(|pool| Some(&pool.name) == member.pool.as_ref())
+            .and_then(|pool| pool.budget);
+        pool.or_else(|| self.team_budgets.get(&member.team).copied())
+            .or(self.budget)
+    }
+    fn member_by_token(&self, token: &str) -> Option<&Member> {
         self.members
             .iter()
-            .fold(0, |x, member| if member.team == team { x + 1 } else { x })
+            .find(|member| !member.token.is_empty() && member.token == token)
     }
-    fn find_ticket(&self, member: &Member, used: &Vec<&Member>) -> Option<&Member> {
-        let entries = self
+    // By token or name, for the organizer's paths and the recipients of
+    // tickets. The members' own paths go by `member_by_token`.
+    fn find_member(&self, key: &str) -> Option<&Member> {
+        match self
             .members
             .iter()
-            .filter(|other| {
-                if *member != **other && member.team != other.team && !used.contains(other) {
-                    true
-                } else {
-                    false
-                }
+            .find(|member| !member.token.is_empty() && member.token == key)
+        {
+            Some(member) => Some(member),
+            None => self
+                .members
+                .iter()
+                .find(|member| name_key(&member.name) == name_key(key)),
+        }
+    }
+    fn without_tickets(&self) -> Draft {
+        let mut draft = self.redacted();
+        draft.members = draft
+            .members
+            .into_iter()
+            .map(|mut member| {
+                member.ticket = None;
+                member.locked = None;
+                member.received = None;
+                member.thanks = None;
+                member
             })
-            .collect::<Vec<&Member>>();
-        match entries.choose(&mut rand::thread_rng()) {
-            Some(e) => Some(*e),
-            None => None,
+            .collect();
+        draft.turns = Vec::new();
+        draft
+    }
+    fn validate(&self, config: &AppConfig) -> Result<(), DraftError> {
+        match validation::violations(self, config).into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(()),
         }
     }
-    fn calculate_tickets(&mut self) -> Result<(), DraftError> {
-        let filtered_members = self.members.iter().find(|member| {
-            if self.team_possibilities(member.team) < self.team_len(member.team) {
-                true
-            } else {
-                false
+    fn is_drawn(&self) -> bool {
+        match self.mode {
+            elephant::Mode::SecretSanta => {
+                !self.members.is_empty()
+                    && self
+                        .members
+                        .iter()
+                        .all(|member| member.ticket.is_some() || member.locked.is_some())
             }
-        });
-        if let Some(_) = filtered_members {
-            return Err(DraftError::NotEnoughPossibilities);
+            elephant::Mode::WhiteElephant => !self.turns.is_empty(),
         }
-        let mut used = Vec::new();
-        let calulated_members = self
+    }
+    // A fresh undrawn copy for another date. Built from the draft itself,
+    // not `redacted`, so the members get new tokens of their own.
+    fn cloned_for(&self, date: DateTime<FixedOffset>, timezone: Tz) -> Draft {
+        let mut draft = self.clone();
+        draft.members = draft
             .members
-            .iter()
-            .map(|member| {
-                let ticket = match self.find_ticket(member, &used) {
-                    Some(ticket) => ticket,
-                    None => return Err(DraftError::CalculateAgain),
-                };
-                used.push(ticket);
-                let mut new_member = member.clone();
-                new_member.ticket = Some(ticket.name.clone());
-                Ok(new_member)
+            .into_iter()
+            .map(|mut member| {
+                member.token = generate_token();
+                member.ticket = None;
+                member.locked = None;
+                member.keys = None;
+                member.push = Vec::new();
+                member.received = None;
+                member.thanks = None;
+                member.wishlist = None;
+                member.address = None;
+                member.spent = None;
+                member
+            })
+            .collect();
+        draft.turns = Vec::new();
+        draft.violated = Vec::new();
+        draft.date = date;
+        draft.timezone = timezone;
+        draft.slug = None;
+        draft.polls = Vec::new();
+        draft.comments = Vec::new();
+        draft.revealed = false;
+        draft.series = None;
+        draft.google = None;
+        draft.draw_at = None;
+        draft.created = Utc::now();
+        draft.retain_until = None;
+        draft.trashed = None;
+        draft.reminded = false;
+        draft.organizer_token = generate_token();
+        draft.co_organizers = Vec::new();
+        draft.organizer_invitations = Vec::new();
+        draft.share_token = None;
+        draft.avoid = self.avoid.clone();
+        draft.reassign = Vec::new();
+        draft.audit = Vec::new();
+        draft
+    }
+    // The draft as it was entered, without the tokens and time generated
+    // for it, to tell a retried request from another one.
+    fn fingerprint(&self) -> String {
+        let mut draft = self.clone();
+        draft.organizer_token = String::new();
+        draft.created = DateTime::<Utc>::from(std::time::UNIX_EPOCH);
+        let mut members = draft
+            .members
+            .drain()
+            .map(|mut member| {
+                member.token = String::new();
+                member
             })
-            .collect::<Result<HashSet<Member>, _>>();
-        match calulated_members {
-            Ok(members) => {
-                self.members = members;
-                Ok(())
+            .collect::<Vec<Member>>();
+        members.sort_by(|a, b| a.name.cmp(&b.name));
+        idempotency::fingerprint(&(draft, members))
+    }
+    fn local_date(&self) -> DateTime<Tz> {
+        self.date.with_timezone(&self.timezone)
+    }
+    fn is_upcoming(&self) -> bool {
+        self.date > Utc::now()
+    }
+    // Past drafts are read-only.
+    fn is_archived(&self) -> bool {
+        !self.is_upcoming()
+    }
+    // When the draft is removed under a retention of `days` after its date.
+    fn expires(&self, days: Option<i64>) -> Option<DateTime<Utc>> {
+        let expires = self.date.with_timezone(&Utc) + Duration::days(days?);
+        match self.retain_until {
+            Some(until) if until > expires => Some(until),
+            _ => Some(expires),
+        }
+    }
+    fn parse_date(value: &str, timezone: Tz) -> Result<DateTime<FixedOffset>, DraftError> {
+        let naive = match NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M") {
+            Ok(naive) => naive,
+            Err(_) => match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                Ok(date) => date.and_hms(0, 0, 0),
+                Err(_) => return Err(DraftError::InvalidDate),
+            },
+        };
+        match timezone.from_local_datetime(&naive).earliest() {
+            Some(date) => Ok(date.with_timezone(&date.offset().fix())),
+            None => Err(DraftError::InvalidDate),
+        }
+    }
+    // Repeats the draw until it succeeds, giving up after `max_attempts`
+    // dead ends.
+    fn calculate_tickets(&mut self, max_attempts: u32) -> Result<(), DraftError> {
+        if self.mode == elephant::Mode::WhiteElephant {
+            let names = self
+                .members
+                .iter()
+                .map(|member| member.name.clone())
+                .collect();
+            self.turns = elephant::turn_order(names, &mut rand::thread_rng());
+            return Ok(());
+        }
+        if self.passphrases {
+            let mut missing = self.members.iter().filter(|member| member.keys.is_none());
+            if let Some(member) = missing.min_by(|a, b| a.name.cmp(&b.name)) {
+                return Err(DraftError::PassphraseMissing {
+                    name: member.name.clone(),
+                });
             }
-            Err(DraftError::CalculateAgain) => self.calculate_tickets(),
-            Err(e) => Err(e),
         }
+        tracing::debug!(members = self.members.len(), "drawing tickets");
+        // Pairs of past editions and those the organizer would rather not
+        // see weigh against a draw.
+        let mut weights = self
+            .series
+            .as_ref()
+            .map(series::Series::weights)
+            .unwrap_or_default();
+        avoid::add_weights(&self.avoid, &mut weights);
+        let mut drawn = HashSet::new();
+        for (_, members) in pool::group(&self.members) {
+            let members = members.into_iter().cloned().collect::<Vec<Member>>();
+            drawn.extend(series::draw_weighted(&weights, &members, || {
+                Draft::draw_pool(members.clone(), max_attempts)
+            })?);
+        }
+        self.violated = avoid::violated(self, &drawn);
+        if self.passphrases {
+            drawn = drawn
+                .into_iter()
+                .map(|mut member| {
+                    let keys = member.keys.as_ref().ok_or(DraftError::InvalidData)?;
+                    let recipient = member.recipient().ok_or(DraftError::InvalidData)?;
+                    member.locked = Some(
+                        passphrase::lock(keys, &recipient).map_err(|_| DraftError::InvalidData)?,
+                    );
+                    member.ticket = None;
+                    Ok(member)
+                })
+                .collect::<Result<HashSet<Member>, DraftError>>()?;
+        }
+        self.members = drawn;
+        Ok(())
     }
+    // Every pool is drawn on its own, so tickets never cross pools.
+    fn draw_pool(members: Vec<Member>, max_attempts: u32) -> Result<Vec<Member>, DraftError> {
+        Draft::draw_pool_counted(members, max_attempts, &mut DrawStats::default())
+    }
+    // Like `draw_pool`, counting the attempts and dead ends into `stats`.
+    fn draw_pool_counted(
+        members: Vec<Member>,
+        max_attempts: u32,
+        stats: &mut DrawStats,
+    ) -> Result<Vec<Member>, DraftError> {
+        let teams = members
+            .iter()
+            .map(|member| member.team)
+            .collect::<Vec<u32>>();
+        if let Some(team) = matcher::infeasible_team(&teams) {
+            tracing::warn!(team, "not enough possibilities for team");
+            return Err(DraftError::NotEnoughPossibilities);
+        }
+        let mut rng = rand::thread_rng();
+        let mut stuck = 0;
+        for attempt in 1..=max_attempts.max(1) {
+            metrics::DRAW_ATTEMPTS.inc();
+            stats.attempts += 1;
+            match matcher::draw(&teams, &mut rng) {
+                Ok(recipients) => return Ok(Draft::assign(&members, recipients)),
+                Err(member) => {
+                    metrics::DRAW_RETRIES.inc();
+                    stats.dead_ends += 1;
+                    tracing::debug!(attempt, "draw took a wrong path, retrying");
+                    stuck = member;
+                }
+            }
+        }
+        tracing::warn!(
+            attempts = max_attempts,
+            member = members[stuck].name.as_str(),
+            "giving up on the draw"
+        );
+        Err(DraftError::Infeasible {
+            attempts: max_attempts,
+            member: members[stuck].name.clone(),
+        })
+    }
+    // The members with the tickets of `recipients`, holding the index of
+    // every member's recipient.
+    fn assign(members: &[Member], recipients: Vec<usize>) -> Vec<Member> {
+        members
+            .iter()
+            .zip(recipients)
+            .map(|(member, recipient)| {
+                let mut member = member.clone();
+                member.ticket = Some(crypto::seal(&members[recipient].name));
+                member
+            })
+            .collect()
+    }
+}
+
+// What a draw took, for tuning `max_draw_attempts`.
+#[derive(Serialize, Debug, Default, Clone, Copy)]
+struct DrawStats {
+    attempts: u32,
+    dead_ends: u32,
+}
+
+// A member row of the insertion form as it was entered.
+#[derive(Debug, Default)]
+struct MemberInput {
+    name: String,
+    team: String,
+    pool: Option<String>,
+    email: Option<String>,
+    error: Option<DraftError>,
+}
+
+// The insertion form as it was sent, to fill it in again when it is
+// rejected.
+#[derive(Debug, Default)]
+pub struct FormInput {
+    title: String,
+    date: String,
+    members: Vec<MemberInput>,
+    pools: Vec<pool::Pool>,
+    budget: Option<u32>,
+    team_budgets: BTreeMap<u32, u32>,
+    passphrases: bool,
+    blind: bool,
+    invite: bool,
+}
+
+// All errors of a rejected insertion form, not only the first one.
+#[derive(Debug)]
+pub struct FormErrors {
+    pub errors: Vec<DraftError>,
+    pub input: FormInput,
 }
 
 impl<'f> FromForm<'f> for Draft {
-    type Error = DraftError;
+    type Error = FormErrors;
 
     fn from_form(items: &mut FormItems<'f>, _strict: bool) -> Result<Self, Self::Error> {
-        let mut draft = Draft {
-            title: String::new(),
-            date: String::new(),
-            members: HashSet::new(),
-        };
-        let mut name = None;
+        let mut input = FormInput::default();
+        let mut errors = Vec::new();
+        let mut timezone = None;
+        let mut mode = elephant::Mode::default();
+        let mut max_steals = None;
+        let mut budget_team = None;
         for item in items {
             let key: &str = &*item.key;
-            let value = item.value.to_string();
-            if value == "" {
-                return Err(Self::Error::InvalidData);
-            }
-            //println!("{}", value);
+            let value = match item.value.url_decode() {
+                Ok(value) => value,
+                Err(_) => {
+                    errors.push(DraftError::InvalidData);
+                    continue;
+                }
+            };
             match key {
-                "title" => draft.title = value,
-                "date" => draft.date = value,
-                "name" => name = Some(value),
-                "team" => match name {
-                    Some(n) => {
-                        draft
-                            .members
-                            .insert(Member::new(n, u32::from_str_radix(&value, 10).unwrap()));
-                        name = None;
+                "title" => input.title = sanitize::line(&value),
+                "date" => input.date = value,
+                "timezone" => match value.parse::<Tz>() {
+                    Ok(tz) => timezone = Some(tz),
+                    Err(_) => errors.push(DraftError::InvalidTimezone),
+                },
+                "mode" => match elephant::Mode::from_form(&value) {
+                    Some(value) => mode = value,
+                    None => errors.push(DraftError::InvalidData),
+                },
+                "max_steals" => match value.parse::<u32>() {
+                    Ok(value) => max_steals = Some(value),
+                    Err(_) => errors.push(DraftError::InvalidData),
+                },
+                "name" => input.members.push(MemberInput {
+                    name: value,
+                    ..MemberInput::default()
+                }),
+                // The team and pool of the member before.
+                "team" => match input.members.last_mut() {
+                    Some(member) => member.team = value,
+                    None => errors.push(DraftError::InvalidData),
+                },
+                "pool" => match input.members.last_mut() {
+                    Some(member) => {
+                        member.pool = Some(sanitize::line(&value)).filter(|pool| pool != "")
+                    }
+                    None => errors.push(DraftError::InvalidData),
+                },
+                "email" => match input.members.last_mut() {
+                    Some(member) => {
+                        member.email = Some(sanitize::line(&value)).filter(|email| email != "")
                     }
-                    None => {
-                        return Err(Self::Error::InvalidData);
+                    None => errors.push(DraftError::InvalidData),
+                },
+                // Drafts without a budget leave it empty.
+                "budget" => match value.as_str() {
+                    "" => input.budget = None,
+                    value => match value.parse::<u32>() {
+                        Ok(value) => input.budget = Some(value),
+                        Err(_) => errors.push(DraftError::InvalidBudget),
+                    },
+                },
+                "budget_team" => match value.parse::<u32>() {
+                    Ok(team) => budget_team = Some(team),
+                    Err(_) => errors.push(DraftError::InvalidBudget),
+                },
+                "budget_amount" => match (budget_team.take(), value.parse::<u32>()) {
+                    (Some(team), Ok(amount)) => {
+                        input.team_budgets.insert(team, amount);
                     }
+                    _ => errors.push(DraftError::InvalidBudget),
+                },
+                "passphrases" => input.passphrases = value == "on",
+                "blind" => input.blind = value == "on",
+                "invite" => input.invite = value == "on",
+                "pool_name" => input.pools.push(pool::new(value)),
+                "pool_budget" => match (input.pools.last_mut(), value.parse::<u32>()) {
+                    (Some(pool), Ok(budget)) => pool.budget = Some(budget),
+                    _ => errors.push(DraftError::InvalidBudget),
                 },
                 e => {
-                    println!("Error: Could not parse {}", e);
-                    return Err(Self::Error::InvalidData);
+                    tracing::warn!(key = e, "could not parse form field");
+                    errors.push(DraftError::InvalidData);
                 }
             }
         }
-        draft.calculate_tickets()?;
-        println!("{}", draft);
+        if input.title.trim().is_empty() {
+            errors.push(DraftError::TitleMissing);
+        }
+        let timezone = timezone.unwrap_or(DEFAULT_TIMEZONE);
+        let date = match Draft::parse_date(&input.date, timezone) {
+            Ok(date) => Some(date),
+            Err(e) => {
+                errors.push(e);
+                None
+            }
+        };
+        // Every member row gets its own error, so all of them can be shown
+        // next to the rows at once.
+        let mut members = Vec::new();
+        let mut names = HashSet::new();
+        for member in &mut input.members {
+            let name = sanitize::line(&member.name);
+            let error = match member.team.trim().parse::<u32>() {
+                _ if name.is_empty() => Some(DraftError::NameMissing),
+                Err(_) => Some(DraftError::InvalidTeam { name }),
+                Ok(_) if !names.insert(name_key(&name)) => {
+                    Some(DraftError::MemberAlreadyDefined { name })
+                }
+                Ok(team) => {
+                    let mut new = Member::new(member.name.clone(), team);
+                    new.pool = member.pool.clone();
+                    new.email = member.email.clone();
+                    members.push(new);
+                    None
+                }
+            };
+            if let Some(error) = error {
+                errors.push(error.clone());
+                member.error = Some(error);
+            }
+        }
+        let date = match date {
+            Some(date) if errors.is_empty() => date,
+            _ => return Err(FormErrors { errors, input }),
+        };
+        let mut pools = input.pools;
+        pool::complete(&mut pools, &members);
+        let rules = match mode {
+            elephant::Mode::SecretSanta => None,
+            elephant::Mode::WhiteElephant => {
+                let mut rules = elephant::Rules::default();
+                if let Some(max_steals) = max_steals {
+                    rules.max_steals = max_steals;
+                }
+                Some(rules)
+            }
+        };
+        let mut draft = Draft {
+            title: input.title,
+            slug: None,
+            mode,
+            turns: Vec::new(),
+            rules,
+            pools,
+            budget: input.budget,
+            team_budgets: input.team_budgets,
+            polls: Vec::new(),
+            comments: Vec::new(),
+            emails: mail::Templates::default(),
+            passphrases: input.passphrases,
+            blind: input.blind,
+            theme: None,
+            avoid: Vec::new(),
+            violated: Vec::new(),
+            spending: false,
+            reassign: Vec::new(),
+            revealed: false,
+            series: None,
+            google: None,
+            draw_at: None,
+            date,
+            timezone,
+            members: members.into_iter().collect(),
+            created: Utc::now(),
+            retain_until: None,
+            trashed: None,
+            reminded: false,
+            organizer_token: generate_token(),
+            co_organizers: Vec::new(),
+            organizer_invitations: Vec::new(),
+            share_token: None,
+            audit: Vec::new(),
+        };
+        if input.invite {
+            invite::invite_all(&mut draft);
+        }
         Ok(draft)
     }
 }
 
-type Drafts = RwLock<Vec<Draft>>;
+type Drafts = Arc<store::DraftStore>;
+
+fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+// Tokens presented by the client, either as header or as the cookies
+// set when a draft was created through the web form.
+struct Organizer(Vec<String>);
+
+impl Organizer {
+    // Whether the client may organize the draft, as its owner or as a
+    // co-organizer.
+    fn owns(&self, draft: &Draft) -> bool {
+        self.is_owner(draft)
+            || draft
+                .co_organizers
+                .iter()
+                .any(|organizer| self.0.contains(&organizer.token))
+    }
+
+    // Only the owner manages the organizers.
+    fn is_owner(&self, draft: &Draft) -> bool {
+        !draft.organizer_token.is_empty() && self.0.contains(&draft.organizer_token)
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Organizer {
+    type Error = ();
 
-#[get("/api/draft")]
-fn api_drafts(drafts: State<Drafts>) -> Json<Option<Vec<Draft>>> {
-    match drafts.read() {
-        Ok(drafts) => Json(Some(drafts.to_vec())),
-        Err(_) => Json(None),
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        let mut tokens = request
+            .headers()
+            .get("X-Organizer-Token")
+            .map(|token| token.to_string())
+            .collect::<Vec<String>>();
+        tokens.extend(
+            request
+                .cookies()
+                .iter()
+                .filter(|cookie| cookie.name().starts_with("organizer-"))
+                .map(|cookie| cookie.value().to_string()),
+        );
+        request::Outcome::Success(Organizer(tokens))
     }
 }
 
-#[post("/api/draft", data = "<draft_form>")]
-fn api_post_draft(draft_form: Form<Draft>, drafts: State<Drafts>) -> Json<Option<usize>> {
-    match drafts.write() {
-        Ok(mut drafts) => {
-            let draft = draft_form.into_inner();
-            drafts.push(draft);
-            Json(Some(drafts.len() - 1))
+// Operator of the instance, authenticated by the `admin_token` from the
+// app config, sent as header or as the cookie set by the admin login.
+// Without a configured token nobody is admitted.
+struct Admin;
+
+const ADMIN_COOKIE: &str = "admin-token";
+
+impl<'a, 'r> FromRequest<'a, 'r> for Admin {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        let expected = match request.guard::<State<AppConfig>>() {
+            request::Outcome::Success(config) => config.admin_token.clone(),
+            _ => None,
+        };
+        let token = match request.headers().get_one("X-Admin-Token") {
+            Some(token) => Some(token.to_string()),
+            None => request
+                .cookies()
+                .get(ADMIN_COOKIE)
+                .map(|cookie| cookie.value().to_string()),
+        };
+        match (expected, token) {
+            (Some(expected), Some(token)) if expected == token => request::Outcome::Success(Admin),
+            _ => request::Outcome::Failure((Status::Unauthorized, ())),
         }
-        Err(_) => Json(None),
     }
 }
 
-#[get("/api/draft/<draft>")]
-fn api_draft(draft: usize, drafts: State<Drafts>) -> Json<Option<Draft>> {
-    match drafts.read() {
-        Ok(drafts) => match drafts.get(draft) {
-            Some(draft) => Json(Some(draft.clone())),
-            None => Json(None),
-        },
-        Err(_) => Json(None),
+// What the API answers for a new draft, with the slug it got.
+fn created_draft(id: usize, organizer_token: String, drafts: &Drafts) -> CreatedDraft {
+    CreatedDraft {
+        id,
+        slug: drafts
+            .get_any(id)
+            .and_then(|entry| entry.read().slug.clone()),
+        organizer_token,
     }
 }
 
+#[derive(FromForm)]
+struct ImportForm {
+    csv: String,
+}
+
+#[derive(Serialize)]
+struct ImportContext {
+    id: usize,
+    draft: Draft,
+    csv: String,
+    errors: Vec<import::RowError>,
+}
+
+const DEFAULT_TIMEZONE: Tz = chrono_tz::Europe::Berlin;
+
+#[derive(Serialize)]
+struct DraftEntry {
+    id: usize,
+    drawn: bool,
+    draft: Draft,
+}
+
+impl DraftEntry {
+    fn new(id: usize, draft: &Draft) -> DraftEntry {
+        DraftEntry {
+            id,
+            drawn: draft.is_drawn(),
+            draft: draft.redacted(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct IndexContext {
+    drafts: Vec<DraftEntry>,
+    stats: stats::Stats,
+}
+
+#[derive(Serialize)]
+struct DraftContext {
+    #[serde(flatten)]
+    entry: DraftEntry,
+    organizer: bool,
+    // Only shown to the organizer.
+    timeline: Vec<timeline::Day>,
+    progress: Option<timeline::Progress>,
+    expires: Option<DateTime<Utc>>,
+    expiring: bool,
+    ceremony: bool,
+    pools: Vec<pool::View>,
+    polls: Vec<poll::PollView>,
+    comments: Vec<comment::CommentView>,
+    // Members yet to set a passphrase in the zero-knowledge mode.
+    waiting: Vec<String>,
+    archived: bool,
+    pairs: Vec<archive::Pair>,
+    // Whether the organizer can connect their Google Calendar, and did.
+    google: bool,
+    google_connected: bool,
+    // Only shown to the organizer, after the exchange.
+    outstanding: Vec<String>,
+    // Whether members can still add themselves.
+    registration: bool,
+    // Who accepted or declined their invitation, for the organizer.
+    invitations: Option<invite::Overview>,
+    theme: Option<String>,
+    current_theme: &'static str,
+    themes: Vec<theme::Choice>,
+    // The read-only link, for the organizer.
+    share_url: Option<String>,
+    // The pairs to avoid and those the last draw took anyway, for the
+    // organizer.
+    avoided: Vec<avoid::Avoid>,
+    violated: Vec<avoid::Avoid>,
+    // What members asked to get another recipient for, for the organizer.
+    reassign: Vec<reassign::View>,
+    // What the members spent, for the organizer after the exchange.
+    spending: Option<spending::Summary>,
+    // What happened to the notifications of every member, for the
+    // organizer.
+    deliveries: Vec<delivery::View>,
+    // The co-organizers and invited ones, managed by the owner.
+    organizers: Vec<organizers::View>,
+    owner: bool,
+}
+
+#[get("/api/draft?<fields>")]
+fn api_drafts(
+    fields: Option<String>,
+    drafts: State<Drafts>,
+) -> Content<Stream<listing::DraftStream>> {
+    let entries = drafts
+        .active()
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .collect();
+    Content(
+        ContentType::JSON,
+        Stream::from(listing::DraftStream::new(entries, fields.as_deref())),
+    )
+}
+
+// Stores a validated draft, drawing it unless it waits for passphrases or
+// invitations. Shared by the API and the form, which drew it already.
+fn create_draft(
+    mut draft: Draft,
+    locale: Locale,
+    origin: &qr::Origin,
+    config: &AppConfig,
+    drafts: &Drafts,
+) -> Result<CreatedDraft, status::Custom<String>> {
+    let rejected = |e: DraftError| status::Custom(e.status(), e.to_string());
+    draft.validate(config).map_err(rejected)?;
+    // Drafts in the zero-knowledge mode are drawn once every member set a
+    // passphrase, drafts with invitations once everyone answered.
+    if !draft.is_drawn() && !draft.passphrases && !invite::pending(&draft) {
+        draft
+            .calculate_tickets(config.max_draw_attempts)
+            .map_err(rejected)?;
+    }
+    tracing::debug!(
+        title = draft.title.as_str(),
+        members = draft.members.len(),
+        "draft created"
+    );
+    draft.record(audit::Actor::Organizer, audit::Action::Created);
+    if draft.is_drawn() {
+        draft.record(audit::Actor::System, audit::Action::Drawn);
+    }
+    let organizer_token = draft.organizer_token.clone();
+    let id = drafts.insert(draft);
+    if let Some(entry) = drafts.get(id) {
+        invite::send(&entry.read(), id, origin, config, locale);
+    }
+    Ok(created_draft(id, organizer_token, drafts))
+}
+
+#[post("/api/draft", data = "<draft_form>")]
+fn api_post_draft(
+    draft_form: Form<Draft>,
+    key: idempotency::Key,
+    locale: Locale,
+    origin: qr::Origin,
+    config: State<AppConfig>,
+    responses: State<idempotency::Responses>,
+    drafts: State<Drafts>,
+) -> Result<Json<CreatedDraft>, status::Custom<String>> {
+    let body = draft_form.fingerprint();
+    let created = responses
+        .run(&key, &body, || {
+            create_draft(draft_form.into_inner(), locale, &origin, &config, &drafts)
+        })
+        .map_err(|status| status::Custom(status, String::new()))??;
+    Ok(Json(created))
+}
+
+#[get("/api/draft/<draft>")]
+fn api_draft(draft: store::Key, drafts: State<Drafts>) -> Json<Option<api::draft::Draft>> {
+    Json(
+        drafts
+            .resolve(&draft)
+            .and_then(|id| drafts.get(id))
+            .map(|entry| entry.read().public()),
+    )
+}
+
 // #[get("/api/draft/<draft>/ticket")]
 // fn api_draft_tickets(draft: usize, drafts: State<Drafts>) -> Json<Option<HashMap<String, String>>> {
 //     match drafts.read() {
@@ -248,71 +1367,1173 @@ fn api_draft(draft: usize, drafts: State<Drafts>) -> Json<Option<Draft>> {
 //     }
 // }
 
-#[get("/api/draft/<draft>/ticket/<name>")]
-fn api_draft_ticket(draft: usize, name: String, drafts: State<Drafts>) -> Json<Option<String>> {
-    match drafts.read() {
-        Ok(drafts) => match drafts.get(draft) {
-            Some(draft) => match draft.members.iter().find(|member| member.name == name) {
-                Some(member) => Json(member.ticket.clone()),
-                None => Json(None),
+#[post("/api/draft/<id>/clone", format = "json", data = "<request>")]
+fn api_clone_draft(
+    id: usize,
+    request: Json<CloneRequest>,
+    key: idempotency::Key,
+    organizer: Organizer,
+    responses: State<idempotency::Responses>,
+    drafts: State<Drafts>,
+) -> Result<Json<CreatedDraft>, Status> {
+    let created = responses.run(&key, &idempotency::fingerprint(&*request), || {
+        let entry = drafts.get(id).ok_or(Status::NotFound)?;
+        let draft = entry.read();
+        if !organizer.owns(&draft) {
+            return Err(Status::Forbidden);
+        }
+        let timezone = request.timezone.unwrap_or(draft.timezone);
+        let date =
+            Draft::parse_date(&request.date, timezone).map_err(|_| Status::UnprocessableEntity)?;
+        if date <= Utc::now() {
+            return Err(Status::UnprocessableEntity);
+        }
+        let mut clone = draft.cloned_for(date, timezone);
+        clone.record(audit::Actor::Organizer, audit::Action::Cloned { from: id });
+        let organizer_token = clone.organizer_token.clone();
+        drop(draft);
+        let id = drafts.insert(clone);
+        Ok(created_draft(id, organizer_token, &drafts))
+    })??;
+    Ok(Json(created))
+}
+
+// Draws the draft, none if it isn't the organizer's or can't be drawn
+// anymore. Answers 409 while another draw of the draft runs.
+fn draw(
+    id: usize,
+    organizer: &Organizer,
+    config: &AppConfig,
+    events: &events::Events,
+    drafts: &Drafts,
+) -> Result<Option<bool>, Status> {
+    let entry = match drafts.get(id) {
+        Some(entry) => entry,
+        None => return Ok(None),
+    };
+    let _operation = entry.operation().ok_or(Status::Conflict)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) || draft.is_drawn() || draft.is_archived() {
+        return Ok(None);
+    }
+    let drawn = draft.calculate_tickets(config.max_draw_attempts).is_ok();
+    if drawn {
+        draft.record(audit::Actor::Organizer, audit::Action::Drawn);
+        events.publish(id, events::Event::DrawComplete);
+        let messages = push::drawn(&draft, id, config);
+        let emails = mail::drawn(config, id, &draft);
+        google::create_event(config, id, &draft, drafts);
+        // Queueing writes the outbox file, not while holding the draft.
+        drop(draft);
+        push::notify(config, messages);
+        mail::notify(emails);
+    }
+    Ok(Some(drawn))
+}
+
+#[post("/api/draft/<id>/draw")]
+fn api_draw_draft(
+    id: usize,
+    key: idempotency::Key,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    responses: State<idempotency::Responses>,
+    drafts: State<Drafts>,
+) -> Result<Json<Option<bool>>, Status> {
+    let drawn = responses.run(&key, "", || draw(id, &organizer, &config, &events, &drafts))??;
+    Ok(Json(drawn))
+}
+
+// Exchanges the tickets of two givers, as long as neither ends up with
+// someone of their own team.
+#[post("/api/draft/<id>/swap", format = "json", data = "<request>")]
+fn api_swap_tickets(
+    id: usize,
+    request: Json<SwapRequest>,
+    key: idempotency::Key,
+    organizer: Organizer,
+    responses: State<idempotency::Responses>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    responses.run(&key, &idempotency::fingerprint(&*request), || {
+        let entry = drafts.get(id).ok_or(Status::NotFound)?;
+        let _operation = entry.operation().ok_or(Status::Conflict)?;
+        let mut draft = entry.write();
+        if !organizer.owns(&draft) {
+            return Err(Status::Forbidden);
+        }
+        // Nobody can read the tickets in the zero-knowledge mode.
+        if !draft.is_drawn() || draft.passphrases || draft.is_archived() {
+            return Err(Status::Conflict);
+        }
+        let mut first = draft
+            .find_member(&request.first)
+            .cloned()
+            .ok_or(Status::NotFound)?;
+        let mut second = draft
+            .find_member(&request.second)
+            .cloned()
+            .ok_or(Status::NotFound)?;
+        if first.name == second.name || first.pool != second.pool {
+            return Err(Status::UnprocessableEntity);
+        }
+        let team = |name: Option<String>| {
+            draft
+                .members
+                .iter()
+                .find(|member| Some(&member.name) == name.as_ref())
+                .map(|member| member.team)
+        };
+        if team(second.recipient()) == Some(first.team)
+            || team(first.recipient()) == Some(second.team)
+        {
+            return Err(Status::UnprocessableEntity);
+        }
+        draft.members.remove(&first);
+        draft.members.remove(&second);
+        std::mem::swap(&mut first.ticket, &mut second.ticket);
+        let names = (first.name.clone(), second.name.clone());
+        draft.members.insert(first);
+        draft.members.insert(second);
+        draft.record(
+            audit::Actor::Organizer,
+            audit::Action::Swapped {
+                first: names.0,
+                second: names.1,
             },
-            None => Json(None),
-        },
-        Err(_) => Json(None),
+        );
+        let violated = avoid::violated(&draft, &draft.members);
+        draft.violated = violated;
+        tracing::info!(draft = id, "tickets swapped");
+        Ok(())
+    })??;
+    Ok(Status::NoContent)
+}
+
+// Draws a new recipient for one giver, leaving the other assignments
+// alone where possible. Returns the names of the givers whose ticket
+// changed.
+fn redraw_ticket(draft: &mut Draft, name: &str) -> Result<Vec<String>, Status> {
+    let (giver, giver_pool) = draft
+        .find_member(name)
+        .map(|member| (member.name.clone(), member.pool.clone()))
+        .ok_or(Status::NotFound)?;
+    // Only the giver's pool takes part in the repair.
+    let mut members = draft
+        .members
+        .iter()
+        .filter(|member| member.pool == giver_pool)
+        .cloned()
+        .collect::<Vec<Member>>();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    let index = |name: &str| members.iter().position(|member| member.name == name);
+    let teams = members
+        .iter()
+        .map(|member| member.team)
+        .collect::<Vec<u32>>();
+    let recipients = members
+        .iter()
+        .map(|member| member.recipient().and_then(|name| index(&name)))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or(Status::InternalServerError)?;
+    let giver = index(&giver).ok_or(Status::NotFound)?;
+    let repaired = matcher::repair(&teams, &recipients, giver, &mut rand::thread_rng())
+        .ok_or(Status::UnprocessableEntity)?;
+
+    let changed = repaired
+        .iter()
+        .enumerate()
+        .filter(|(index, recipient)| recipients[*index] != **recipient)
+        .map(|(index, _)| members[index].name.clone())
+        .collect::<Vec<String>>();
+    for (member, recipient) in members.iter().zip(&repaired) {
+        let mut member = draft
+            .members
+            .take(member)
+            .ok_or(Status::InternalServerError)?;
+        member.ticket = Some(crypto::seal(&members[*recipient].name));
+        draft.members.insert(member);
+    }
+    let violated = avoid::violated(draft, &draft.members);
+    draft.violated = violated;
+    Ok(changed)
+}
+
+// Draws a new recipient for one giver, e.g. when their recipient left.
+#[post("/api/draft/<id>/ticket/<name>/redraw")]
+fn api_redraw_ticket(
+    id: usize,
+    name: String,
+    key: idempotency::Key,
+    organizer: Organizer,
+    responses: State<idempotency::Responses>,
+    drafts: State<Drafts>,
+) -> Result<Json<Vec<String>>, Status> {
+    let changed = responses.run(&key, "", || {
+        let entry = drafts.get(id).ok_or(Status::NotFound)?;
+        let _operation = entry.operation().ok_or(Status::Conflict)?;
+        let mut draft = entry.write();
+        if !organizer.owns(&draft) {
+            return Err(Status::Forbidden);
+        }
+        if !draft.is_drawn() || draft.passphrases || draft.is_archived() {
+            return Err(Status::Conflict);
+        }
+        let giver = draft
+            .find_member(&name)
+            .map(|member| member.name.clone())
+            .ok_or(Status::NotFound)?;
+        let changed = redraw_ticket(&mut draft, &giver)?;
+        draft.record(
+            audit::Actor::Organizer,
+            audit::Action::TicketRedrawn {
+                name: giver,
+                changed: changed.len(),
+            },
+        );
+        tracing::info!(draft = id, changed = changed.len(), "ticket redrawn");
+        Ok(changed)
+    })??;
+    Ok(Json(changed))
+}
+
+// Keeps the draft for another retention period from now or from its
+// current expiry, whichever is later.
+#[post("/api/draft/<id>/extend")]
+fn api_extend_draft(
+    id: usize,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Json<DateTime<Utc>>, Status> {
+    let days = config.retention_days.ok_or(Status::NotFound)?;
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    let now = Utc::now();
+    let start = match draft.expires(Some(days)) {
+        Some(expires) if expires > now => expires,
+        _ => now,
+    };
+    let until = start + Duration::days(days);
+    draft.retain_until = Some(until);
+    draft.record(
+        audit::Actor::Organizer,
+        audit::Action::RetentionExtended { until },
+    );
+    Ok(Json(until))
+}
+
+// Restores a deleted draft within the restore window, for its organizer
+// or the admin.
+#[post("/api/draft/<id>/restore")]
+fn api_restore_draft(
+    id: usize,
+    organizer: Organizer,
+    admin: Option<Admin>,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    let entry = drafts.get_any(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    match draft.trashed {
+        Some(date) if date + Duration::days(config.trash_days) > Utc::now() => (),
+        _ => return Err(Status::NotFound),
+    }
+    let actor = if admin.is_some() {
+        audit::Actor::Admin
+    } else if organizer.owns(&draft) {
+        audit::Actor::Organizer
+    } else {
+        return Err(Status::Forbidden);
+    };
+    draft.restore(actor, &config);
+    tracing::info!(id, "draft restored");
+    Ok(Status::NoContent)
+}
+
+#[get("/api/draft/<id>/audit")]
+fn api_draft_audit(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Json<Vec<audit::Entry>>, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    Ok(Json(draft.audit.clone()))
+}
+
+#[get("/api/draft/<id>/export?<format>&<assignments>")]
+fn api_export_draft(
+    id: usize,
+    format: Option<String>,
+    assignments: Option<bool>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<export::Download, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    let assignments = assignments.unwrap_or(false);
+    if assignments && (!organizer.owns(&draft) || draft.blind) {
+        return Err(Status::Forbidden);
+    }
+    let draft = if assignments {
+        draft.organizer_view()
+    } else {
+        draft.without_tickets()
+    };
+    match format.as_ref().map(String::as_str).unwrap_or("json") {
+        "json" => Ok(export::Download {
+            filename: export::filename(&draft, "json"),
+            content_type: ContentType::JSON,
+            body: serde_json::to_vec_pretty(&draft).map_err(|_| Status::InternalServerError)?,
+        }),
+        "csv" => Ok(export::Download {
+            filename: export::filename(&draft, "csv"),
+            content_type: ContentType::CSV,
+            body: export::csv(&draft, assignments).map_err(|_| Status::InternalServerError)?,
+        }),
+        _ => Err(Status::BadRequest),
+    }
+}
+
+#[get("/api/backup")]
+fn api_backup(_admin: Admin, drafts: State<Drafts>) -> Json<Option<backup::Backup>> {
+    Json(Some(backup::Backup::new(drafts.snapshot())))
+}
+
+#[post("/api/restore", format = "json", data = "<backup>")]
+fn api_restore(
+    _admin: Admin,
+    backup: Json<backup::Backup>,
+    drafts: State<Drafts>,
+) -> Result<Json<usize>, Status> {
+    let backup = backup.into_inner();
+    if backup.version != backup::VERSION {
+        return Err(Status::UnprocessableEntity);
+    }
+    drafts.replace(backup.drafts);
+    Ok(Json(drafts.active().len()))
+}
+
+#[get("/api/draft/<id>/ticket/<name>")]
+fn api_draft_ticket(
+    id: usize,
+    name: String,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+    mut cookies: Cookies,
+) -> Json<Option<String>> {
+    let signed_in = login::signed_in(id, &mut cookies);
+    let member = drafts.get(id).and_then(|entry| {
+        entry
+            .write()
+            .view_ticket(&name, signed_in.as_deref(), &config)
+    });
+    match member {
+        Some(member) => {
+            let recipient = member.recipient();
+            if recipient.is_some() {
+                events.publish(id, events::Event::TicketViewed { name: member.name });
+            }
+            Json(recipient)
+        }
+        None => Json(None),
+    }
+}
+
+#[post(
+    "/api/draft/<id>/ticket/<name>/push",
+    format = "json",
+    data = "<subscription>"
+)]
+fn api_subscribe_push(
+    id: usize,
+    name: String,
+    subscription: Json<push::Subscription>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    // The notifications carry the ticket link, so only the member's own
+    // link may subscribe.
+    let member = draft
+        .member_by_token(&name)
+        .cloned()
+        .ok_or(Status::NotFound)?;
+    let mut member = draft.members.take(&member).ok_or(Status::NotFound)?;
+    let subscription = subscription.into_inner();
+    if !member.push.contains(&subscription) {
+        member.push.push(subscription);
+        let excess = member.push.len().saturating_sub(push::MAX_SUBSCRIPTIONS);
+        member.push.drain(..excess);
     }
+    draft.members.insert(member);
+    Ok(Status::NoContent)
 }
 
 #[get("/error/500")]
-fn show_internal_error() -> Template {
+fn show_internal_error(locale: Locale) -> Template {
     let context: HashMap<&str, &str> = HashMap::new();
-    Template::render("500", context)
+    i18n::render("500", locale, context)
+}
+
+#[get("/lang/<code>")]
+fn change_language(code: String, mut cookies: Cookies) -> Redirect {
+    if let Some(locale) = Locale::from_code(&code) {
+        cookies.add(Cookie::build("lang", locale.code()).path("/").finish());
+    }
+    Redirect::to("/")
 }
 
 #[get("/")]
-fn show_index(drafts: State<Drafts>) -> Template {
-    let mut context = HashMap::new();
-    context.insert("drafts", drafts.read().unwrap().to_vec());
-    Template::render("index", context)
+fn show_index(locale: Locale, drafts: State<Drafts>) -> Template {
+    let mut entries = drafts
+        .active()
+        .iter()
+        .map(|(id, entry)| DraftEntry::new(*id, &entry.read()))
+        .filter(|entry| entry.draft.is_upcoming())
+        .collect::<Vec<DraftEntry>>();
+    entries.sort_by_key(|entry| entry.draft.date);
+    i18n::render(
+        "index",
+        locale,
+        IndexContext {
+            drafts: entries,
+            stats: stats::collect(&drafts),
+        },
+    )
 }
 
-#[get("/draft")]
-fn show_insert_draft() -> Template {
-    let context: HashMap<&str, &str> = HashMap::new();
-    Template::render("draft_insertion", context)
+// The insertion form, filled with the values of a rejected draft and the
+// errors of its fields.
+#[derive(Serialize, Default)]
+struct InsertionContext {
+    errors: BTreeMap<&'static str, String>,
+    title: String,
+    date: String,
+    members: Vec<MemberField>,
+    pools: Vec<pool::Pool>,
+    budget: Option<u32>,
+    team_budgets: Vec<TeamBudgetField>,
+    passphrases: bool,
+    blind: bool,
+    invite: bool,
+    mode: elephant::Mode,
+    // The setups to start from, only on the empty form.
+    presets: Vec<preset::Preset>,
+}
+
+#[derive(Serialize)]
+struct TeamBudgetField {
+    team: u32,
+    amount: u32,
+}
+
+#[derive(Serialize)]
+struct MemberField {
+    name: String,
+    team: String,
+    pool: Option<String>,
+    email: Option<String>,
+    error: Option<String>,
+}
+
+impl InsertionContext {
+    fn new(draft: Option<&Draft>, errors: &[DraftError], locale: Locale) -> InsertionContext {
+        let mut context = InsertionContext {
+            errors: validation::field_errors(errors, locale),
+            ..InsertionContext::default()
+        };
+        if let Some(draft) = draft {
+            context.title = draft.title.clone();
+            context.date = draft.local_date().format("%Y-%m-%dT%H:%M").to_string();
+            context.members = draft
+                .members
+                .iter()
+                .map(|member| MemberField {
+                    name: member.name.clone(),
+                    team: member.team.to_string(),
+                    pool: member.pool.clone(),
+                    email: member.email.clone(),
+                    error: None,
+                })
+                .collect();
+            context.members.sort_by(|a, b| a.name.cmp(&b.name));
+            context.pools = draft.pools.clone();
+            context.budget = draft.budget;
+            context.passphrases = draft.passphrases;
+            context.blind = draft.blind;
+            context.invite = invite::pending(draft);
+            context.mode = draft.mode;
+            context.team_budgets = draft
+                .team_budgets
+                .iter()
+                .map(|(team, amount)| TeamBudgetField {
+                    team: *team,
+                    amount: *amount,
+                })
+                .collect();
+        }
+        context
+    }
+
+    // The form as it was entered, with the errors of each member row.
+    fn from_input(errors: FormErrors, locale: Locale) -> InsertionContext {
+        let input = errors.input;
+        InsertionContext {
+            errors: validation::field_errors(&errors.errors, locale),
+            title: input.title,
+            date: input.date,
+            members: input
+                .members
+                .into_iter()
+                .map(|member| MemberField {
+                    name: member.name,
+                    team: member.team,
+                    pool: member.pool,
+                    email: member.email,
+                    error: member.error.map(|e| e.localized(locale)),
+                })
+                .collect(),
+            pools: input.pools,
+            budget: input.budget,
+            team_budgets: input
+                .team_budgets
+                .into_iter()
+                .map(|(team, amount)| TeamBudgetField { team, amount })
+                .collect(),
+            passphrases: input.passphrases,
+            blind: input.blind,
+            invite: input.invite,
+            ..InsertionContext::default()
+        }
+    }
+
+    // The empty form, filled in from the chosen preset.
+    fn from_preset(preset: Option<preset::Preset>, locale: Locale) -> InsertionContext {
+        let mut context = InsertionContext {
+            presets: preset::all(locale),
+            ..InsertionContext::default()
+        };
+        if let Some(preset) = preset {
+            context.mode = preset.mode;
+            context.members = preset
+                .members
+                .into_iter()
+                .map(|row| MemberField {
+                    name: String::new(),
+                    team: row.team.to_string(),
+                    pool: row.pool,
+                    email: None,
+                    error: None,
+                })
+                .collect();
+            context.pools = preset.pools;
+            context.budget = preset.budget;
+            context.team_budgets = preset
+                .team_budgets
+                .into_iter()
+                .map(|(team, amount)| TeamBudgetField { team, amount })
+                .collect();
+        }
+        context
+    }
+}
+
+#[get("/draft?<template>")]
+fn show_insert_draft(template: Option<String>, locale: Locale) -> Template {
+    let preset = template.and_then(|id| preset::find(&id, locale));
+    i18n::render(
+        "draft_insertion",
+        locale,
+        InsertionContext::from_preset(preset, locale),
+    )
 }
 
 #[post("/draft", data = "<draft>")]
-fn insert_draft(draft: Form<Draft>, drafts: State<Drafts>) -> Redirect {
-    match api_post_draft(draft, drafts).0 {
-        Some(id) => Redirect::to(uri!(show_draft: id)),
-        None => Redirect::to(uri!(show_internal_error)),
+fn insert_draft(
+    mut draft: Result<Form<Draft>, FormDataError<FormErrors>>,
+    locale: Locale,
+    origin: qr::Origin,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+    mut cookies: Cookies,
+) -> Result<Redirect, Template> {
+    if let Ok(draft) = &mut draft {
+        let violations = validation::violations(draft, &config);
+        let errors = if violations.is_empty() && !draft.passphrases && !invite::pending(draft) {
+            match draft.calculate_tickets(config.max_draw_attempts) {
+                Ok(()) => Vec::new(),
+                Err(e) => vec![e],
+            }
+        } else {
+            violations
+        };
+        if !errors.is_empty() {
+            let context = InsertionContext::new(Some(draft), &errors, locale);
+            return Err(i18n::render("draft_insertion", locale, context));
+        }
+    }
+    match draft {
+        Ok(draft) => match create_draft(draft.into_inner(), locale, &origin, &config, &drafts) {
+            Ok(CreatedDraft {
+                id,
+                slug,
+                organizer_token,
+            }) => {
+                cookies.add(
+                    Cookie::build(format!("organizer-{}", id), organizer_token)
+                        .path("/")
+                        .http_only(true)
+                        .finish(),
+                );
+                let key = slug.map_or(store::Key::Id(id), store::Key::Slug);
+                Ok(Redirect::to(uri!(show_draft: key)))
+            }
+            Err(_) => Ok(Redirect::to(uri!(show_internal_error))),
+        },
+        Err(FormDataError::Parse(errors, _)) => {
+            let context = InsertionContext::from_input(errors, locale);
+            Err(i18n::render("draft_insertion", locale, context))
+        }
+        Err(_) => Ok(Redirect::to(uri!(show_internal_error))),
     }
 }
 
-#[get("/draft/<id>")]
-fn show_draft(id: usize, drafts: State<Drafts>) -> Template {
-    let mut context = HashMap::new();
-    match api_draft(id, drafts).0 {
-        Some(draft) => {
-            context.insert("draft", draft);
-            Template::render("draft", context)
+#[get("/draft/<key>")]
+fn show_draft(
+    key: store::Key,
+    locale: Locale,
+    organizer: Organizer,
+    origin: qr::Origin,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Template {
+    let draft = drafts
+        .resolve(&key)
+        .and_then(|id| Some((id, drafts.get(id)?.read().clone())));
+    match draft {
+        Some((id, draft)) => {
+            let owns = organizer.owns(&draft);
+            let (timeline, progress) = if owns {
+                (
+                    timeline::build(&draft, locale),
+                    Some(timeline::progress(&draft)),
+                )
+            } else {
+                (Vec::new(), None)
+            };
+            let expires = draft.expires(config.retention_days);
+            let expiring = expires.map_or(false, |date| {
+                date - Utc::now() < Duration::days(retention::WARNING_DAYS)
+            });
+            let template = match draft.mode {
+                elephant::Mode::SecretSanta => "draft",
+                elephant::Mode::WhiteElephant => "draft_white_elephant",
+            };
+            let pools = pool::views(&draft.pools, &draft.members);
+            let polls = poll::views(&draft, None);
+            let comments = draft
+                .comments
+                .iter()
+                .map(|comment| comment.view())
+                .collect();
+            let mut waiting = draft
+                .members
+                .iter()
+                .filter(|member| draft.passphrases && member.keys.is_none())
+                .map(|member| member.name.clone())
+                .collect::<Vec<String>>();
+            waiting.sort();
+            let deliveries = if owns {
+                let mut names = draft
+                    .members
+                    .iter()
+                    .map(|member| member.name.clone())
+                    .collect::<Vec<String>>();
+                names.sort();
+                delivery::views(id, &names)
+            } else {
+                Vec::new()
+            };
+            i18n::render(
+                template,
+                locale,
+                DraftContext {
+                    pools,
+                    polls,
+                    comments,
+                    waiting,
+                    archived: draft.is_archived(),
+                    pairs: archive::pairs(&draft),
+                    google: owns && google::available(&config),
+                    google_connected: owns && draft.google.is_some(),
+                    registration: deadline::open(&draft),
+                    invitations: if owns { invite::overview(&draft) } else { None },
+                    theme: draft.theme.clone(),
+                    current_theme: theme::of(&draft).id,
+                    themes: theme::choices(locale),
+                    share_url: if owns {
+                        share::url(id, draft.share_token.as_deref(), &origin)
+                    } else {
+                        None
+                    },
+                    deliveries,
+                    organizers: if owns {
+                        organizers::views(&draft)
+                    } else {
+                        Vec::new()
+                    },
+                    owner: organizer.is_owner(&draft),
+                    spending: if owns && draft.spending && draft.is_archived() {
+                        Some(spending::summary(&draft))
+                    } else {
+                        None
+                    },
+                    reassign: if owns {
+                        reassign::pending(&draft)
+                    } else {
+                        Vec::new()
+                    },
+                    avoided: if owns {
+                        draft.avoid.clone()
+                    } else {
+                        Vec::new()
+                    },
+                    violated: if owns {
+                        draft.violated.clone()
+                    } else {
+                        Vec::new()
+                    },
+                    outstanding: if owns {
+                        gift::outstanding(&draft)
+                    } else {
+                        Vec::new()
+                    },
+                    entry: DraftEntry::new(id, &draft),
+                    organizer: owns,
+                    timeline,
+                    progress,
+                    expires,
+                    expiring,
+                    ceremony: config.ceremony_port.is_some(),
+                },
+            )
+        }
+        None => {
+            let context: HashMap<&str, &str> = HashMap::new();
+            i18n::render("draft_not_found", locale, context)
+        }
+    }
+}
+
+#[post("/draft/<id>/draw")]
+fn draw_draft(
+    id: usize,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    match draw(id, &organizer, &config, &events, &drafts)? {
+        Some(true) => Ok(Redirect::to(uri!(show_draft: id))),
+        _ => Ok(Redirect::to(uri!(show_internal_error))),
+    }
+}
+
+#[post("/draft/<id>/extend")]
+fn extend_draft(
+    id: usize,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    api_extend_draft(id, organizer, config, drafts)?;
+    Ok(Redirect::to(uri!(show_draft: id)))
+}
+
+// Live updates for the organizer's draft page.
+#[get("/draft/<id>/events")]
+fn draft_events(
+    id: usize,
+    organizer: Organizer,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+) -> Result<Content<Stream<events::EventStream>>, Status> {
+    {
+        let entry = drafts.get(id).ok_or(Status::NotFound)?;
+        let draft = entry.read();
+        if !organizer.owns(&draft) {
+            return Err(Status::Forbidden);
         }
-        None => Template::render("draft_not_found", context),
     }
+    // The page falls back to reloading itself.
+    let stream = events.subscribe(id).ok_or(Status::ServiceUnavailable)?;
+    // Rocket fills a whole chunk before writing it, so a larger chunk
+    // would hold back the events.
+    Ok(Content(
+        ContentType::new("text", "event-stream"),
+        Stream::chunked(stream, 1),
+    ))
+}
+
+#[derive(Serialize)]
+struct CeremonyContext {
+    id: usize,
+    draft: Draft,
+    port: u16,
+}
+
+#[get("/draft/<id>/ceremony")]
+fn show_ceremony(
+    id: usize,
+    locale: Locale,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Template, Status> {
+    let port = config.ceremony_port.ok_or(Status::NotFound)?;
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    Ok(i18n::render(
+        "draft_ceremony",
+        locale,
+        CeremonyContext {
+            id,
+            draft: draft.without_tickets(),
+            port,
+        },
+    ))
+}
+
+#[get("/draft/<id>/calendar.ics")]
+fn show_draft_calendar(
+    id: usize,
+    locale: Locale,
+    drafts: State<Drafts>,
+) -> Option<Content<String>> {
+    let draft = drafts.get(id)?.read().redacted();
+    Some(Content(
+        ContentType::Calendar,
+        calendar::event(id, &draft, locale),
+    ))
+}
+
+#[get("/draft/<id>/import")]
+fn show_import(
+    id: usize,
+    locale: Locale,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Template, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    if draft.is_drawn() || draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    Ok(i18n::render(
+        "draft_import",
+        locale,
+        ImportContext {
+            id,
+            draft: draft.redacted(),
+            csv: String::new(),
+            errors: Vec::new(),
+        },
+    ))
 }
 
-#[get("/draft/<id>/ticket/<name>")]
-fn show_ticket(id: usize, name: String, drafts: State<Drafts>) -> Template {
+// Adds the members of the CSV to a draft not drawn yet and draws it, unless
+// it waits for passphrases, invitations or its deadline like a new draft.
+#[post("/draft/<id>/import", data = "<import_form>")]
+fn import_members(
+    id: usize,
+    import_form: Form<ImportForm>,
+    locale: Locale,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+) -> Result<Result<Redirect, Template>, Status> {
+    let csv = import_form.into_inner().csv;
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let _operation = entry.operation().ok_or(Status::Conflict)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    // Drawing again would replace tickets members may have seen already.
+    if draft.is_drawn() || draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    let errors = match import::parse_members(&draft.members, &csv, locale) {
+        Ok(members) => {
+            let mut imported = draft.clone();
+            let names = members
+                .iter()
+                .map(|member| member.name.clone())
+                .collect::<Vec<String>>();
+            imported.members.extend(members);
+            pool::complete(&mut imported.pools, &imported.members);
+            imported.record(
+                audit::Actor::Organizer,
+                audit::Action::MembersAdded {
+                    names: names.clone(),
+                },
+            );
+            let drawing =
+                !imported.passphrases && !invite::pending(&imported) && imported.draw_at.is_none();
+            match imported.validate(&config).and_then(|_| {
+                if drawing {
+                    imported.calculate_tickets(config.max_draw_attempts)
+                } else {
+                    Ok(())
+                }
+            }) {
+                Ok(()) => {
+                    *draft = imported;
+                    events.publish(id, events::Event::MemberJoined { names });
+                    if drawing {
+                        draft.record(audit::Actor::System, audit::Action::Drawn);
+                        events.publish(id, events::Event::DrawComplete);
+                        let messages = push::drawn(&draft, id, &config);
+                        let emails = mail::drawn(&config, id, &draft);
+                        google::create_event(&config, id, &draft, &drafts);
+                        drop(draft);
+                        push::notify(&config, messages);
+                        mail::notify(emails);
+                    }
+                    return Ok(Ok(Redirect::to(uri!(show_draft: id))));
+                }
+                Err(e) => vec![import::RowError {
+                    row: 0,
+                    message: e.localized(locale),
+                }],
+            }
+        }
+        Err(errors) => errors,
+    };
+    Ok(Err(i18n::render(
+        "draft_import",
+        locale,
+        ImportContext {
+            id,
+            draft: draft.redacted(),
+            csv,
+            errors,
+        },
+    )))
+}
+
+#[get("/draft/<id>/cards.pdf")]
+fn show_draft_cards(
+    id: usize,
+    locale: Locale,
+    organizer: Organizer,
+    origin: qr::Origin,
+    drafts: State<Drafts>,
+) -> Result<export::Download, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    // The cards carry the tickets.
+    if !organizer.owns(&draft) || draft.blind {
+        return Err(Status::Forbidden);
+    }
+    Ok(export::Download {
+        filename: export::filename(&draft, "pdf"),
+        content_type: ContentType::PDF,
+        body: cards::pdf(id, &draft, &origin, locale).map_err(|_| Status::InternalServerError)?,
+    })
+}
+
+#[get("/draft/<id>/tickets.zip")]
+fn show_draft_bundle(
+    id: usize,
+    locale: Locale,
+    organizer: Organizer,
+    origin: qr::Origin,
+    drafts: State<Drafts>,
+) -> Result<export::Download, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    // Every page of the bundle shows a ticket.
+    if !organizer.owns(&draft) || draft.blind {
+        return Err(Status::Forbidden);
+    }
+    Ok(export::Download {
+        filename: export::filename(&draft, "zip"),
+        content_type: ContentType::new("application", "zip"),
+        body: bundle::zip(id, &draft, &origin, locale).map_err(|_| Status::InternalServerError)?,
+    })
+}
+
+// Ranked behind the plain text version, see `plain`.
+#[get("/draft/<id>/ticket/<name>", rank = 2)]
+fn show_ticket(
+    id: usize,
+    name: String,
+    locale: Locale,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+    mut cookies: Cookies,
+) -> Result<Template, Redirect> {
+    let signed_in = login::signed_in(id, &mut cookies);
+    // A member who has to sign in is sent to request a login link.
+    let sign_in = drafts.get(id).map_or(false, |entry| {
+        let draft = entry.read();
+        draft.member_by_token(&name).map_or(false, |member| {
+            login::required(member, &config) && signed_in.as_ref() != Some(&member.name)
+        })
+    });
+    if sign_in {
+        return Err(Redirect::to(format!("/draft/{}/login", id)));
+    }
+    let locale = language::for_key(id, &name, locale, &drafts);
+    if let Some(page) = passphrase::page(id, &name, locale, &drafts) {
+        return Ok(page);
+    }
     let mut context = HashMap::new();
     context.insert("id", id.to_string());
-    match api_draft_ticket(id, name.clone(), drafts).0 {
-        Some(ticket) => {
+    let viewed = drafts.get(id).and_then(|entry| {
+        let mut draft = entry.write();
+        let member = draft.view_ticket(&name, signed_in.as_deref(), &config)?;
+        if let Some(theme) = &draft.theme {
+            context.insert("theme", theme.clone());
+        }
+        // The budget depends on who the gift is for.
+        let recipient = member.recipient();
+        let recipient_member = recipient
+            .as_ref()
+            .and_then(|ticket| draft.find_member(ticket));
+        let budget = recipient_member.and_then(|recipient| draft.budget_for(recipient));
+        // What the recipient wished for when accepting their invitation.
+        if let Some(wishlist) = recipient_member.and_then(|recipient| recipient.wishlist.clone()) {
+            context.insert("wishlist", wishlist);
+        }
+        // Only the member's own link leads to their votes.
+        if !draft.polls.is_empty() && draft.member_by_token(&name).is_some() {
+            context.insert("polls", format!("/draft/{}/polls/{}", id, name));
+        }
+        if draft.member_by_token(&name).is_some() {
+            context.insert("avatar_key", name.clone());
+            context.insert(
+                "language",
+                member.language.map_or("", Locale::code).to_string(),
+            );
+        }
+        if let Some(avatar) = avatar::of(&member) {
+            match (avatar.url(), avatar) {
+                (Some(url), _) => context.insert("avatar_url", url),
+                (None, avatar::Avatar::Emoji(emoji)) => context.insert("avatar_emoji", emoji),
+                _ => None,
+            };
+        }
+        if member.avatar.is_some() {
+            context.insert("avatar_own", String::new());
+        }
+        thanks::context(&draft, &name, &mut context);
+        address::context(&draft, &name, &mut context);
+        reassign::context(&draft, &name, &mut context);
+        spending::context(&draft, &name, &mut context);
+        // After the exchange the member can confirm they got their gift.
+        if draft.is_archived() && draft.member_by_token(&name).is_some() {
+            match member.received {
+                Some(_) => context.insert("received", String::new()),
+                None => context.insert("received_key", name.clone()),
+            };
+        }
+        Some((member.name, recipient, budget))
+    });
+    if let (Some(_), Some(push)) = (&viewed, &config.push) {
+        context.insert("key", name.clone());
+        context.insert("push_key", push.public_key.clone());
+    }
+    match viewed {
+        Some((name, Some(ticket), budget)) => {
+            events.publish(id, events::Event::TicketViewed { name: name.clone() });
             context.insert("name", name);
             context.insert("ticket", ticket);
-            Template::render("ticket", context)
+            if let Some(budget) = budget {
+                context.insert("budget", budget.to_string());
+            }
+            Ok(i18n::render("ticket", locale, context))
         }
-        None => Template::render("ticket_not_found", context),
+        _ => Ok(i18n::render("ticket_not_found", locale, context)),
+    }
+}
+
+#[derive(Serialize)]
+struct DistributionEntry {
+    name: String,
+    url: String,
+    qr: String,
+}
+
+#[derive(Serialize)]
+struct DistributionContext {
+    id: usize,
+    draft: Draft,
+    members: Vec<DistributionEntry>,
+}
+
+#[get("/draft/<id>/distribute")]
+fn show_distribution(
+    id: usize,
+    locale: Locale,
+    organizer: Organizer,
+    origin: qr::Origin,
+    drafts: State<Drafts>,
+) -> Result<Template, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    // The links would open every ticket, which the organizer of a blind
+    // draft must not see.
+    if !organizer.owns(&draft) || draft.blind {
+        return Err(Status::Forbidden);
     }
+    let mut members = draft
+        .members
+        .iter()
+        .map(|member| {
+            let url = origin.ticket_url(id, member);
+            DistributionEntry {
+                name: member.name.clone(),
+                qr: qr::svg(&url).unwrap_or_default(),
+                url,
+            }
+        })
+        .collect::<Vec<DistributionEntry>>();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(i18n::render(
+        "draft_distribute",
+        locale,
+        DistributionContext {
+            id,
+            draft: draft.without_tickets(),
+            members,
+        },
+    ))
+}
+
+#[get("/img/<file..>")]
+fn show_image(file: PathBuf, config: State<AppConfig>) -> Option<assets::Asset> {
+    assets::load::<assets::Images>(&config.img_dir, &file)
+}
+
+#[get("/css/<file..>")]
+fn show_style(file: PathBuf, config: State<AppConfig>) -> Option<assets::Asset> {
+    assets::load::<assets::Styles>(&config.css_dir, &file)
 }
 
 // #[post("/draft/<id>/ticket", data = "<name>")]
@@ -332,29 +2553,318 @@ fn show_ticket(id: usize, name: String, drafts: State<Drafts>) -> Template {
 // }
 
 fn main() {
-    rocket::ignite()
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    match args.first().map(String::as_str) {
+        None | Some("serve") => serve(),
+        Some("draw") => std::process::exit(cli::draw(&args[1..])),
+        Some("help") | Some("--help") | Some("-h") => println!("{}", cli::USAGE),
+        Some(command) => {
+            eprintln!("unknown command {}\n{}", command, cli::USAGE);
+            std::process::exit(2);
+        }
+    }
+}
+
+fn serve() {
+    let extracted =
+        if std::env::var("ROCKET_TEMPLATE_DIR").is_err() && !Path::new("templates").is_dir() {
+            let extracted = assets::extract_templates();
+            if let Ok(dir) = &extracted {
+                std::env::set_var("ROCKET_TEMPLATE_DIR", dir);
+            }
+            Some(extracted)
+        } else {
+            None
+        };
+    let rocket = rocket::ignite();
+    let (config, config_error) = AppConfig::load(rocket.config());
+    logging::init(&config);
+    if let Some(e) = config_error {
+        tracing::error!(
+            error = e.as_str(),
+            "invalid app configuration, using defaults"
+        );
+    }
+    if let Some(key) = &config.ticket_key {
+        if let Err(e) = crypto::init(key) {
+            tracing::error!(
+                error = e.as_str(),
+                "invalid ticket key, storing tickets in plain"
+            );
+        }
+    }
+    match extracted {
+        Some(Ok(dir)) => tracing::info!(dir = %dir.display(), "using embedded templates"),
+        Some(Err(e)) => tracing::error!(error = %e, "could not extract templates"),
+        None => (),
+    }
+    let drafts = match &config.data_file {
+        Some(path) => match persistence::load(path) {
+            Ok(drafts) => drafts,
+            Err(e) => {
+                tracing::error!(path = path.as_str(), error = %e, "could not load drafts");
+                Vec::new()
+            }
+        },
+        None => Vec::new(),
+    };
+    let drafts = Drafts::new(store::DraftStore::new(drafts));
+    let lifecycle = Arc::new(shutdown::Lifecycle::default());
+    shutdown::install(lifecycle.clone(), drafts.clone(), config.data_file.clone());
+    outbox::spawn(&config, drafts.clone());
+    retention::spawn(drafts.clone(), &config);
+    push::spawn(drafts.clone(), &config);
+    let events = events::Events::new(config.max_event_streams);
+    let creations = limit::Creations::default();
+    events::spawn(events.clone());
+    deadline::spawn(drafts.clone(), &config, events.clone());
+    ceremony::spawn(
+        &rocket.config().address,
+        &config,
+        drafts.clone(),
+        events.clone(),
+    );
+    #[cfg(feature = "solver-debug")]
+    let rocket = rocket.mount(
+        "/",
+        routes![solver_debug::api_scenarios, solver_debug::api_draw],
+    );
+    rocket
         .mount(
             "/",
             routes![
                 api_drafts,
                 api_post_draft,
+                bulk::create,
                 api_draft,
                 // api_draft_tickets,
                 // api_post_draft_ticket,
+                api_clone_draft,
+                api_draft_audit,
+                api_extend_draft,
+                api_restore_draft,
+                api_draw_draft,
+                api_swap_tickets,
+                api_redraw_ticket,
+                poll::api_create,
+                poll::create,
+                poll::api_results,
+                poll::api_vote,
+                poll::cast_vote,
+                poll::show,
+                archive::show,
+                series::show,
+                series::api_start,
+                series::start_series,
+                series::api_stats,
+                google::connect,
+                google::callback,
+                google::disconnect,
+                gift::api_confirm,
+                gift::confirm_received,
+                thanks::write,
+                thanks::sign,
+                simulate::api_simulate,
+                deadline::api_set,
+                deadline::set_deadline,
+                deadline::join,
+                preset::api_templates,
+                invite::show,
+                invite::api_respond,
+                invite::answer,
+                language::api_set,
+                language::set_language,
+                theme::api_themes,
+                theme::stylesheet,
+                theme::api_set,
+                theme::set_theme,
+                share::api_create,
+                share::api_delete,
+                share::create,
+                share::delete,
+                share::show,
+                avoid::api_list,
+                avoid::api_set,
+                avoid::add,
+                avoid::remove,
+                verify::api_verify,
+                external::api_import,
+                delivery::api_list,
+                delivery::api_retry,
+                delivery::retry_delivery,
+                reassign::api_ask,
+                reassign::ask_reassign,
+                reassign::api_list,
+                reassign::api_approve,
+                reassign::api_deny,
+                reassign::approve,
+                reassign::deny,
+                organizers::api_invite,
+                organizers::invite_form,
+                organizers::api_list,
+                organizers::api_remove,
+                organizers::remove_form,
+                organizers::api_accept,
+                organizers::accept_form,
+                organizers::show,
+                spending::api_record,
+                spending::record_spent,
+                spending::api_enable,
+                spending::set_spending,
+                spending::api_summary,
+                address::api_set,
+                address::set_address,
+                address::api_labels,
+                feed::show,
+                archive::api_reveal,
+                archive::reveal_draft,
+                archive::api_assignments,
+                archive::show_reveal,
+                passphrase::api_set_keys,
+                passphrase::api_locked_ticket,
+                login::show_request,
+                login::request,
+                login::redeem,
+                login::show_own_ticket,
+                comment::api_create,
+                comment::create,
+                comment::api_delete,
+                comment::remove,
+                mail::api_save,
+                mail::api_preview,
+                preview::api_preview,
+                api_export_draft,
+                api_backup,
+                api_restore,
                 api_draft_ticket,
+                api_subscribe_push,
+                suggest::api_suggest,
+                avatar::upload,
+                avatar::remove,
+                avatar::api_delete,
                 show_internal_error,
+                change_language,
                 show_index,
                 show_insert_draft,
                 insert_draft,
                 show_draft,
+                draw_draft,
+                extend_draft,
+                draft_events,
+                show_ceremony,
+                show_draft_calendar,
+                show_draft_cards,
+                show_draft_bundle,
+                show_import,
+                import_members,
                 show_ticket,
+                plain::show_ticket,
+                show_distribution,
+                admin::dashboard,
+                admin::show_login,
+                admin::login,
+                admin::logout,
+                admin::delete_draft,
+                admin::restore_draft,
+                admin::api_delete_draft,
+                admin::api_drafts,
+                admin::api_storage,
+                admin::api_cleanup,
+                admin::api_export,
+                outbox::api_list,
+                outbox::api_retry,
+                outbox::api_discard,
+                outbox::retry_job,
+                outbox::discard_job,
                 // insert_ticket,
                 // retry_ticket,
             ],
         )
-        .attach(Template::fairing())
-        .manage(Drafts::new(Vec::new()))
-        .mount("/img", StaticFiles::from("img"))
-        .mount("/css", StaticFiles::from("css"))
+        .attach(Template::custom(|engines| {
+            engines.tera.register_filter("localdate", i18n::date_filter);
+        }))
+        .register(catchers![logging::internal_error])
+        .mount(
+            "/",
+            routes![
+                show_image,
+                show_style,
+                avatar::show,
+                shutdown::unavailable,
+                limit::limited,
+                health::healthz,
+                health::readyz,
+                metrics::metrics,
+                stats::api_stats,
+                push::worker
+            ],
+        )
+        .attach(shutdown::LifecycleFairing(lifecycle))
+        .attach(limit::RateLimitFairing::new(creations.clone()))
+        .attach(security::SecurityHeaders::new(&config))
+        .attach(compression::CompressionFairing)
+        .attach(metrics::MetricsFairing)
+        .attach(logging::TracingFairing)
+        .manage(config)
+        .manage(drafts)
+        .manage(login::Links::default())
+        .manage(creations)
+        .manage(idempotency::Responses::default())
+        .manage(events)
         .launch();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(teams: &[u32]) -> Vec<Member> {
+        teams
+            .iter()
+            .enumerate()
+            .map(|(index, team)| Member::new(format!("member {}", index), *team))
+            .collect()
+    }
+
+    #[test]
+    fn turns_down_infeasible_pools_without_drawing() {
+        for scenario in scenarios::scenarios().into_iter().filter(|s| !s.drawable) {
+            let mut stats = DrawStats::default();
+            let result = Draft::draw_pool_counted(members(&scenario.teams), 100, &mut stats);
+            assert!(
+                matches!(result, Err(DraftError::NotEnoughPossibilities)),
+                "{}",
+                scenario.name
+            );
+            assert_eq!(stats.attempts, 0, "{}", scenario.name);
+        }
+    }
+
+    #[test]
+    fn draws_a_ticket_for_everyone() {
+        let teams = [0, 0, 1, 1, 2, 3];
+        let drawn = Draft::draw_pool(members(&teams), 100).expect("drawn");
+        let mut recipients = drawn
+            .iter()
+            .map(|member| member.recipient().expect("a ticket"))
+            .collect::<Vec<String>>();
+        recipients.sort();
+        let mut names = members(&teams)
+            .into_iter()
+            .map(|member| member.name)
+            .collect::<Vec<String>>();
+        names.sort();
+        assert_eq!(recipients, names);
+        for member in &drawn {
+            let recipient = drawn
+                .iter()
+                .find(|other| Some(&other.name) == member.recipient().as_ref())
+                .expect("a member");
+            assert_ne!(
+                member.team, recipient.team,
+                "{} drew a teammate",
+                member.name
+            );
+        }
+    }
+}
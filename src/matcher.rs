@@ -0,0 +1,325 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashMap, VecDeque};
+
+// The first team with more members than there are members outside of it
+// to draw, if any.
+pub fn infeasible_team(teams: &[u32]) -> Option<u32> {
+    let mut sizes = HashMap::new();
+    for team in teams {
+        *sizes.entry(*team).or_insert(0usize) += 1;
+    }
+    teams
+        .iter()
+        .find(|team| sizes[*team] * 2 > teams.len())
+        .copied()
+}
+
+// Draws a recipient for every member, never from the member's own team.
+// `teams[i]` is the team of member `i`, the result holds the index of the
+// recipient of member `i`. Returns the member left without a recipient
+// when the draw ran into a dead end and has to be repeated.
+//
+// Every team draws from its own shuffled pool of the members outside of
+// it, skipping those already drawn by another team. As the pools are
+// shuffled independently, the next free member of a pool is a uniformly
+// random choice among the free ones, and each pool is walked only once.
+pub fn draw<R: Rng>(teams: &[u32], rng: &mut R) -> Result<Vec<usize>, usize> {
+    let mut members = HashMap::<u32, Vec<usize>>::new();
+    for (index, team) in teams.iter().enumerate() {
+        members.entry(*team).or_insert_with(Vec::new).push(index);
+    }
+    let mut pools = members
+        .keys()
+        .map(|team| {
+            let mut pool = (0..teams.len())
+                .filter(|index| teams[*index] != *team)
+                .collect::<Vec<usize>>();
+            pool.shuffle(rng);
+            (*team, (pool, 0))
+        })
+        .collect::<HashMap<u32, (Vec<usize>, usize)>>();
+
+    // Large teams have the fewest candidates, so they draw first.
+    let mut order = members.into_iter().collect::<Vec<(u32, Vec<usize>)>>();
+    order.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+
+    let mut taken = vec![false; teams.len()];
+    let mut recipients = vec![0; teams.len()];
+    for (team, givers) in order {
+        let (pool, cursor) = pools.get_mut(&team).expect("pool of every team");
+        for giver in givers {
+            while *cursor < pool.len() && taken[pool[*cursor]] {
+                *cursor += 1;
+            }
+            let recipient = *pool.get(*cursor).ok_or(giver)?;
+            *cursor += 1;
+            taken[recipient] = true;
+            recipients[giver] = recipient;
+        }
+    }
+    Ok(recipients)
+}
+
+// Gives `giver` a new recipient while changing as few other assignments
+// as possible. Searches breadth-first for a chain of givers where each
+// takes over the recipient of the next and the last one takes the
+// recipient `giver` gave up. Returns the new recipients of all members,
+// or `None` if no such chain exists.
+pub fn repair<R: Rng>(
+    teams: &[u32],
+    recipients: &[usize],
+    giver: usize,
+    rng: &mut R,
+) -> Option<Vec<usize>> {
+    let freed = recipients[giver];
+    let mut givers = vec![0; teams.len()];
+    for (index, recipient) in recipients.iter().enumerate() {
+        givers[*recipient] = index;
+    }
+    // The giver that handed its recipient on and which recipient it took.
+    let mut parent = vec![None; teams.len()];
+    let mut visited = vec![false; teams.len()];
+    visited[giver] = true;
+    let mut queue = VecDeque::new();
+    queue.push_back(giver);
+    while let Some(current) = queue.pop_front() {
+        if current != giver && teams[freed] != teams[current] {
+            let mut result = recipients.to_vec();
+            result[current] = freed;
+            let mut next = current;
+            while let Some((previous, recipient)) = parent[next] {
+                result[previous] = recipient;
+                next = previous;
+            }
+            return Some(result);
+        }
+        let mut candidates = (0..teams.len())
+            .filter(|candidate| {
+                teams[*candidate] != teams[current]
+                    && *candidate != recipients[current]
+                    && *candidate != freed
+                    && !visited[givers[*candidate]]
+            })
+            .collect::<Vec<usize>>();
+        candidates.shuffle(rng);
+        for candidate in candidates {
+            let next = givers[candidate];
+            if !visited[next] {
+                visited[next] = true;
+                parent[next] = Some((current, candidate));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+// Above any total of allowed pairs, for the pairs that must not be drawn.
+const FORBIDDEN: i128 = 1 << 100;
+// Room below a unit of weight for the random order among equal draws.
+const SCALE: i128 = 1 << 32;
+
+// A random but fixed number per pair, so ties between draws of the same
+// weight aren't always broken the same way.
+fn noise(seed: u64, giver: usize, recipient: usize, limit: u64) -> i128 {
+    let mut x = seed ^ (((giver as u64) << 32) | recipient as u64);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^= x >> 31;
+    (x % limit.max(1)) as i128
+}
+
+// Draws the recipients with the lowest total `weight` of all draws that
+// keep members out of their own team, as an assignment problem solved by
+// the Hungarian method in O(n³). Members of the same team, the giver
+// included, can't be drawn at all. Among draws of the same weight, a
+// random one is taken. `None` if there is no draw at all.
+pub fn cheapest<R: Rng, F>(teams: &[u32], weight: F, rng: &mut R) -> Option<Vec<usize>>
+where
+    F: Fn(usize, usize) -> u32,
+{
+    let n = teams.len();
+    if n < 2 {
+        return None;
+    }
+    // The noise of all pairs of a draw together stays below one unit of
+    // weight, so it never outweighs a lighter draw.
+    let seed = rng.gen::<u64>();
+    let limit = (SCALE / (n as i128 + 1)) as u64;
+    let cost = |giver: usize, recipient: usize| {
+        if teams[giver] == teams[recipient] {
+            FORBIDDEN
+        } else {
+            weight(giver, recipient) as i128 * SCALE + noise(seed, giver, recipient, limit)
+        }
+    };
+
+    // Rows are givers and columns recipients, both counted from 1 as
+    // index 0 is the column the augmenting path starts at.
+    let mut u = vec![0i128; n + 1];
+    let mut v = vec![0i128; n + 1];
+    let mut row_of = vec![0usize; n + 1];
+    let mut way = vec![0usize; n + 1];
+    for row in 1..=n {
+        row_of[0] = row;
+        let mut column = 0;
+        let mut min = vec![i128::MAX; n + 1];
+        let mut used = vec![false; n + 1];
+        loop {
+            used[column] = true;
+            let current = row_of[column];
+            let mut delta = i128::MAX;
+            let mut next = 0;
+            for candidate in 1..=n {
+                if used[candidate] {
+                    continue;
+                }
+                let reduced = cost(current - 1, candidate - 1) - u[current] - v[candidate];
+                if reduced < min[candidate] {
+                    min[candidate] = reduced;
+                    way[candidate] = column;
+                }
+                if min[candidate] < delta {
+                    delta = min[candidate];
+                    next = candidate;
+                }
+            }
+            for candidate in 0..=n {
+                if used[candidate] {
+                    u[row_of[candidate]] += delta;
+                    v[candidate] -= delta;
+                } else {
+                    min[candidate] -= delta;
+                }
+            }
+            column = next;
+            if row_of[column] == 0 {
+                break;
+            }
+        }
+        while column != 0 {
+            let previous = way[column];
+            row_of[column] = row_of[previous];
+            column = previous;
+        }
+    }
+    let mut recipients = vec![0; n];
+    for column in 1..=n {
+        recipients[row_of[column] - 1] = column - 1;
+    }
+    if (0..n).any(|giver| teams[giver] == teams[recipients[giver]]) {
+        return None;
+    }
+    Some(recipients)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenarios::scenarios;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // Draws until a draw doesn't run into a dead end, like the draft does.
+    fn drawn(teams: &[u32], rng: &mut StdRng) -> Vec<usize> {
+        (0..100)
+            .find_map(|_| draw(teams, rng).ok())
+            .expect("a draw within 100 attempts")
+    }
+
+    // Every member gives and receives exactly once, never within the team.
+    fn assert_valid(teams: &[u32], recipients: &[usize]) {
+        let mut received = vec![false; teams.len()];
+        for (giver, recipient) in recipients.iter().enumerate() {
+            assert_ne!(teams[giver], teams[*recipient], "{} drew a teammate", giver);
+            assert!(!received[*recipient], "{} was drawn twice", recipient);
+            received[*recipient] = true;
+        }
+        assert_eq!(recipients.len(), teams.len());
+    }
+
+    // `thousands` is left to the benchmark, unoptimized it takes long.
+    #[test]
+    fn draws_every_drawable_scenario() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let drawable = scenarios()
+            .into_iter()
+            .filter(|scenario| scenario.drawable && scenario.name != "thousands");
+        for scenario in drawable {
+            assert_eq!(infeasible_team(&scenario.teams), None, "{}", scenario.name);
+            assert_valid(&scenario.teams, &drawn(&scenario.teams, &mut rng));
+        }
+    }
+
+    #[test]
+    fn draws_small_teams() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for teams in &[
+            vec![0, 1],
+            vec![0, 0, 1, 1],
+            vec![0, 0, 1, 2],
+            vec![3, 1, 2, 1, 3],
+        ] {
+            for _ in 0..50 {
+                assert_valid(teams, &drawn(teams, &mut rng));
+            }
+        }
+    }
+
+    #[test]
+    fn turns_down_infeasible_scenarios() {
+        for scenario in scenarios().into_iter().filter(|s| !s.drawable) {
+            assert!(
+                infeasible_team(&scenario.teams).is_some(),
+                "{}",
+                scenario.name
+            );
+        }
+        assert_eq!(infeasible_team(&[0, 0, 0, 1]), Some(0));
+        assert_eq!(infeasible_team(&[0, 0, 1, 1]), None);
+    }
+
+    #[test]
+    fn repair_keeps_the_other_pairs() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let teams = (0..20).map(|index| index / 2).collect::<Vec<u32>>();
+        for giver in 0..teams.len() {
+            let recipients = drawn(&teams, &mut rng);
+            let repaired = repair(&teams, &recipients, giver, &mut rng).expect("a chain");
+            assert_valid(&teams, &repaired);
+            assert_ne!(repaired[giver], recipients[giver]);
+            // Only the chain changes: each of its givers takes over the
+            // recipient of another one of it, or the one `giver` gave up.
+            let changed = (0..teams.len())
+                .filter(|index| repaired[*index] != recipients[*index])
+                .collect::<Vec<usize>>();
+            for index in &changed {
+                assert!(
+                    repaired[*index] == recipients[giver]
+                        || changed
+                            .iter()
+                            .any(|other| recipients[*other] == repaired[*index])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn repair_swaps_when_everyone_is_alone() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let teams = (0..10).collect::<Vec<u32>>();
+        let recipients = drawn(&teams, &mut rng);
+        let repaired = repair(&teams, &recipients, 0, &mut rng).expect("a chain");
+        let changed = (0..teams.len())
+            .filter(|index| repaired[*index] != recipients[*index])
+            .count();
+        assert_eq!(changed, 2);
+    }
+
+    #[test]
+    fn repair_without_a_chain() {
+        let mut rng = StdRng::seed_from_u64(5);
+        assert_eq!(repair(&[0, 1], &[1, 0], 0, &mut rng), None);
+    }
+}
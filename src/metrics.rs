@@ -0,0 +1,95 @@
+use crate::Drafts;
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    Encoder, HistogramVec, IntCounter, IntCounterVec, IntGauge, TextEncoder,
+};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::ContentType;
+use rocket::response::content::Content;
+use rocket::{Data, Request, Response, State};
+use std::time::Instant;
+
+lazy_static! {
+    pub static ref HTTP_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "http_requests_total",
+        "Number of handled requests by route and status",
+        &["method", "route", "status"]
+    )
+    .unwrap();
+    pub static ref HTTP_DURATION: HistogramVec = register_histogram_vec!(
+        "http_request_duration_seconds",
+        "Duration of handled requests by route",
+        &["method", "route"]
+    )
+    .unwrap();
+    pub static ref DRAW_ATTEMPTS: IntCounter = register_int_counter!(
+        "draw_attempts_total",
+        "Number of attempts to calculate the tickets of a draft"
+    )
+    .unwrap();
+    pub static ref DRAW_RETRIES: IntCounter = register_int_counter!(
+        "draw_retries_total",
+        "Number of attempts that took a wrong path and had to be retried"
+    )
+    .unwrap();
+    pub static ref NOTIFICATIONS: IntCounterVec = register_int_counter_vec!(
+        "notifications_total",
+        "Number of sent notifications by result",
+        &["result"]
+    )
+    .unwrap();
+    static ref DRAFTS: IntGauge = register_int_gauge!("drafts", "Number of stored drafts").unwrap();
+    static ref MEMBERS: IntGauge =
+        register_int_gauge!("members", "Number of members over all drafts").unwrap();
+}
+
+struct RequestStart(Instant);
+
+pub struct MetricsFairing;
+
+impl Fairing for MetricsFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Metrics",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    fn on_request(&self, request: &mut Request, _: &Data) {
+        request.local_cache(|| RequestStart(Instant::now()));
+    }
+
+    fn on_response(&self, request: &Request, response: &mut Response) {
+        let start = request.local_cache(|| RequestStart(Instant::now()));
+        let method = request.method().as_str();
+        let route = request
+            .route()
+            .map(|route| route.uri.path())
+            .unwrap_or("unmatched");
+        HTTP_REQUESTS
+            .with_label_values(&[method, route, &response.status().code.to_string()])
+            .inc();
+        HTTP_DURATION
+            .with_label_values(&[method, route])
+            .observe(start.0.elapsed().as_secs_f64());
+    }
+}
+
+#[get("/metrics")]
+pub fn metrics(drafts: State<Drafts>) -> Content<String> {
+    let drafts = drafts.active();
+    DRAFTS.set(drafts.len() as i64);
+    MEMBERS.set(
+        drafts
+            .iter()
+            .map(|(_, entry)| entry.read().members.len() as i64)
+            .sum(),
+    );
+    let mut buffer = Vec::new();
+    let _ = TextEncoder::new().encode(&prometheus::gather(), &mut buffer);
+    Content(
+        ContentType::Plain,
+        String::from_utf8(buffer).unwrap_or_default(),
+    )
+}
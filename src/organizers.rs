@@ -0,0 +1,351 @@
+use crate::config::AppConfig;
+use crate::i18n::{self, Locale};
+use crate::{audit, generate_token, mail, qr, Draft, Drafts, Organizer};
+use chrono::{DateTime, Duration, Utc};
+use rocket::http::{Cookie, Cookies, Status};
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use rocket_contrib::templates::Template;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub use api::organizers::{Accepted, Invite, Invited, Role, View};
+
+// Days an invitation can be accepted.
+const INVITATION_DAYS: i64 = 7;
+const MAX_EMAIL: usize = 254;
+const MAX_ORGANIZERS: usize = 10;
+
+// An organizer invited by email. Nothing changes until they accept from
+// the link sent to them.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Invitation {
+    id: u32,
+    email: String,
+    role: Role,
+    code: String,
+    created: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CoOrganizer {
+    id: u32,
+    email: String,
+    // Presented like the owner's organizer token.
+    pub token: String,
+    added: DateTime<Utc>,
+}
+
+pub fn views(draft: &Draft) -> Vec<View> {
+    let organizers = draft.co_organizers.iter().map(|organizer| View {
+        id: organizer.id,
+        email: organizer.email.clone(),
+        role: Role::CoOrganizer,
+        pending: false,
+        since: organizer.added,
+    });
+    let invitations = draft.organizer_invitations.iter().map(|invitation| View {
+        id: invitation.id,
+        email: invitation.email.clone(),
+        role: invitation.role,
+        pending: true,
+        since: invitation.created,
+    });
+    organizers.chain(invitations).collect()
+}
+
+fn expired(invitation: &Invitation) -> bool {
+    invitation.created + Duration::days(INVITATION_DAYS) <= Utc::now()
+}
+
+// Invites someone by email, replacing an earlier invitation of them. Only
+// one ownership transfer can be pending at a time.
+fn issue(
+    id: usize,
+    invite: &Invite,
+    organizer: &Organizer,
+    origin: &qr::Origin,
+    config: &AppConfig,
+    locale: Locale,
+    drafts: &Drafts,
+) -> Result<Invited, Status> {
+    let role = invite.role();
+    let email = invite.email.trim().to_string();
+    if !email.contains('@') || email.contains(char::is_whitespace) || email.len() > MAX_EMAIL {
+        return Err(Status::UnprocessableEntity);
+    }
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.is_owner(&draft) {
+        return Err(Status::Forbidden);
+    }
+    draft.organizer_invitations.retain(|invitation| {
+        !expired(invitation)
+            && !invitation.email.eq_ignore_ascii_case(&email)
+            && !(role == Role::Owner && invitation.role == Role::Owner)
+    });
+    if role == Role::CoOrganizer
+        && draft
+            .co_organizers
+            .iter()
+            .any(|known| known.email.eq_ignore_ascii_case(&email))
+    {
+        return Err(Status::Conflict);
+    }
+    if draft.co_organizers.len() + draft.organizer_invitations.len() >= MAX_ORGANIZERS {
+        return Err(Status::UnprocessableEntity);
+    }
+    let next = draft
+        .co_organizers
+        .iter()
+        .map(|known| known.id)
+        .chain(
+            draft
+                .organizer_invitations
+                .iter()
+                .map(|invitation| invitation.id),
+        )
+        .max()
+        .map_or(1, |id| id + 1);
+    let code = generate_token();
+    let url = origin.organizer_url(id, &code);
+    let subject = match role {
+        Role::CoOrganizer => "mail_organizer_subject",
+        Role::Owner => "mail_owner_subject",
+    };
+    let message = mail::Message {
+        subject: locale.text(subject).replace("{title}", &draft.title),
+        body: locale
+            .text("mail_organizer_body")
+            .replace("{title}", &draft.title)
+            .replace("{url}", &url)
+            .replace("{days}", &INVITATION_DAYS.to_string()),
+    };
+    let sent = mail::send(config, email.clone(), message);
+    draft.organizer_invitations.push(Invitation {
+        id: next,
+        email,
+        role,
+        code,
+        created: Utc::now(),
+    });
+    tracing::info!(draft = id, ?role, "organizer invited");
+    Ok(Invited {
+        id: next,
+        url,
+        sent,
+    })
+}
+
+#[post("/api/draft/<id>/organizers", format = "json", data = "<invite>")]
+pub fn api_invite(
+    id: usize,
+    invite: Json<Invite>,
+    organizer: Organizer,
+    origin: qr::Origin,
+    locale: Locale,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Json<Invited>, Status> {
+    let invited = issue(id, &invite, &organizer, &origin, &config, locale, &drafts)?;
+    Ok(Json(invited))
+}
+
+#[post("/draft/<id>/organizers", data = "<invite>")]
+pub fn invite_form(
+    id: usize,
+    invite: Form<Invite>,
+    organizer: Organizer,
+    origin: qr::Origin,
+    locale: Locale,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    issue(id, &invite, &organizer, &origin, &config, locale, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+#[get("/api/draft/<id>/organizers")]
+pub fn api_list(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Json<Vec<View>>, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    Ok(Json(views(&draft)))
+}
+
+// Removes a co-organizer, whose token stops working right away, or
+// withdraws an invitation.
+fn remove(
+    id: usize,
+    organizer_id: u32,
+    organizer: &Organizer,
+    drafts: &Drafts,
+) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.is_owner(&draft) {
+        return Err(Status::Forbidden);
+    }
+    if let Some(index) = draft
+        .organizer_invitations
+        .iter()
+        .position(|invitation| invitation.id == organizer_id)
+    {
+        draft.organizer_invitations.remove(index);
+        return Ok(());
+    }
+    let index = draft
+        .co_organizers
+        .iter()
+        .position(|known| known.id == organizer_id)
+        .ok_or(Status::NotFound)?;
+    let removed = draft.co_organizers.remove(index);
+    draft.record(
+        audit::Actor::Organizer,
+        audit::Action::OrganizerRemoved {
+            email: removed.email,
+        },
+    );
+    Ok(())
+}
+
+#[delete("/api/draft/<id>/organizers/<organizer_id>")]
+pub fn api_remove(
+    id: usize,
+    organizer_id: u32,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    remove(id, organizer_id, &organizer, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/organizers/<organizer_id>/remove")]
+pub fn remove_form(
+    id: usize,
+    organizer_id: u32,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    remove(id, organizer_id, &organizer, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+// Accepts the invitation with the code, answering the organizer token the
+// invited one signs in with from now on. Taking over the ownership gives
+// the draft a new owner token, so the previous owner is signed out.
+fn accept(id: usize, code: &str, drafts: &Drafts) -> Result<String, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    let index = draft
+        .organizer_invitations
+        .iter()
+        .position(|invitation| invitation.code == code && !expired(invitation))
+        .ok_or(Status::NotFound)?;
+    let invitation = draft.organizer_invitations.remove(index);
+    let token = generate_token();
+    match invitation.role {
+        Role::CoOrganizer => {
+            draft.co_organizers.push(CoOrganizer {
+                id: invitation.id,
+                email: invitation.email.clone(),
+                token: token.clone(),
+                added: Utc::now(),
+            });
+            draft.record(
+                audit::Actor::Organizer,
+                audit::Action::OrganizerAdded {
+                    email: invitation.email,
+                },
+            );
+        }
+        Role::Owner => {
+            draft.organizer_token = token.clone();
+            draft
+                .co_organizers
+                .retain(|organizer| !organizer.email.eq_ignore_ascii_case(&invitation.email));
+            draft.record(
+                audit::Actor::Organizer,
+                audit::Action::OwnershipTransferred {
+                    email: invitation.email,
+                },
+            );
+        }
+    }
+    tracing::info!(draft = id, role = ?invitation.role, "organizer invitation accepted");
+    Ok(token)
+}
+
+#[post("/api/draft/<id>/organizers/<code>")]
+pub fn api_accept(
+    id: usize,
+    code: String,
+    drafts: State<Drafts>,
+) -> Result<Json<Accepted>, Status> {
+    let organizer_token = accept(id, &code, &drafts)?;
+    Ok(Json(Accepted { organizer_token }))
+}
+
+// Signs the browser in with the new token, like creating a draft does.
+#[post("/draft/<id>/organizers/<code>")]
+pub fn accept_form(
+    id: usize,
+    code: String,
+    drafts: State<Drafts>,
+    mut cookies: Cookies,
+) -> Result<Redirect, Status> {
+    let token = accept(id, &code, &drafts)?;
+    cookies.add(
+        Cookie::build(format!("organizer-{}", id), token)
+            .path("/")
+            .http_only(true)
+            .finish(),
+    );
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+#[derive(Serialize)]
+struct InvitationContext {
+    id: usize,
+    code: String,
+    title: String,
+    email: String,
+    transfer: bool,
+    theme: Option<String>,
+}
+
+// The confirmation page the emailed link leads to. Opening it changes
+// nothing, so link previews of mail clients don't accept by accident.
+#[get("/draft/<id>/organizers/<code>")]
+pub fn show(id: usize, code: String, locale: Locale, drafts: State<Drafts>) -> Template {
+    let context = drafts.get(id).and_then(|entry| {
+        let draft = entry.read();
+        let invitation = draft
+            .organizer_invitations
+            .iter()
+            .find(|invitation| invitation.code == code && !expired(invitation))?;
+        Some(InvitationContext {
+            id,
+            code: code.clone(),
+            title: draft.title.clone(),
+            email: invitation.email.clone(),
+            transfer: invitation.role == Role::Owner,
+            theme: draft.theme.clone(),
+        })
+    });
+    match context {
+        Some(context) => i18n::render("organizer_invitation", locale, context),
+        None => {
+            let context: HashMap<&str, &str> = HashMap::new();
+            i18n::render("draft_not_found", locale, context)
+        }
+    }
+}
@@ -0,0 +1,592 @@
+use crate::config::AppConfig;
+use crate::delivery::{self, Channel, Target};
+use crate::metrics::NOTIFICATIONS;
+use crate::{admin, mail, push, Admin, Drafts};
+use chrono::{DateTime, Duration, Utc};
+use lazy_static::lazy_static;
+use rocket::http::Status;
+use rocket::response::Redirect;
+use rocket_contrib::json::Json;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::thread;
+
+pub use api::outbox::Entry;
+
+// How long the worker sleeps when nothing is due.
+const POLL: std::time::Duration = std::time::Duration::from_secs(5);
+// Delay before the first retry, doubled with every further attempt.
+const BACKOFF_SECONDS: i64 = 30;
+const MAX_BACKOFF_SECONDS: i64 = 6 * 3600;
+// Lines the outbox file holds at least before it is compacted.
+const COMPACT_LINES: usize = 1000;
+
+// Stands in for the link of a member in a notification, as the link holds
+// the member's token. The outbox file only keeps whose link it is, the
+// link itself is put in when the notification is sent.
+pub const LINK: &str = "{link}";
+
+// The page of their own a member's link leads to.
+#[derive(Deserialize, Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Page {
+    Ticket,
+    Invite,
+}
+
+// Whose link `LINK` stands for.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Link {
+    // The origin the link starts with, empty for a relative one.
+    pub base: String,
+    pub draft: usize,
+    pub member: String,
+    pub page: Page,
+}
+
+impl Link {
+    // None if the member left the draft meanwhile.
+    fn url(&self, drafts: &Drafts) -> Option<String> {
+        let entry = drafts.get(self.draft)?;
+        let draft = entry.read();
+        let member = draft
+            .members
+            .iter()
+            .find(|member| member.name == self.member)?;
+        let page = match self.page {
+            Page::Ticket => "ticket",
+            Page::Invite => "invite",
+        };
+        Some(format!(
+            "{}/draft/{}/{}/{}",
+            self.base, self.draft, page, member.token
+        ))
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(tag = "channel", rename_all = "lowercase")]
+pub enum Notification {
+    Email {
+        to: String,
+        message: mail::Message,
+        #[serde(default)]
+        file: Option<mail::File>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        link: Option<Link>,
+    },
+    Push {
+        subscription: push::Subscription,
+        message: push::Message,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        link: Option<Link>,
+    },
+}
+
+impl Notification {
+    fn channel(&self) -> Channel {
+        match self {
+            Notification::Email { .. } => Channel::Email,
+            Notification::Push { .. } => Channel::Push,
+        }
+    }
+
+    // With the member's link put in for `LINK`, failing for good if the
+    // member is gone.
+    fn linked(&self, drafts: &Drafts) -> Result<Notification, delivery::Error> {
+        let mut notification = self.clone();
+        let (link, text) = match &mut notification {
+            Notification::Email {
+                link: Some(link),
+                message,
+                ..
+            } => (link, &mut message.body),
+            Notification::Push {
+                link: Some(link),
+                message,
+                ..
+            } => (link, &mut message.url),
+            _ => return Ok(notification),
+        };
+        let url = link.url(drafts).ok_or_else(|| delivery::Error {
+            bounced: true,
+            message: "the member is no longer in the draft".to_string(),
+        })?;
+        *text = text.replace(LINK, &url);
+        Ok(notification)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+struct Job {
+    id: u64,
+    notification: Notification,
+    #[serde(default)]
+    target: Option<Target>,
+    // The number of its entry in the delivery log, which is lost on a
+    // restart.
+    #[serde(skip)]
+    delivery: Option<u64>,
+    attempts: u32,
+    created: DateTime<Utc>,
+    due: DateTime<Utc>,
+    #[serde(default)]
+    error: Option<String>,
+    // Given up, waiting for the admin to send it again or discard it.
+    #[serde(default)]
+    dead: bool,
+}
+
+// A change of the queue, appended to the outbox file as a line of its own
+// instead of writing the whole queue again.
+#[derive(Deserialize, Serialize)]
+#[serde(tag = "change", rename_all = "lowercase")]
+enum Change {
+    // A new job or the new state of one.
+    Put { job: Job },
+    Remove { id: u64 },
+}
+
+fn lines(changes: &[Change]) -> io::Result<Vec<u8>> {
+    let mut lines = Vec::new();
+    for change in changes {
+        serde_json::to_writer(&mut lines, change)?;
+        lines.push(b'\n');
+    }
+    Ok(lines)
+}
+
+// The outbox file as it was written whole before.
+#[derive(Deserialize)]
+struct Saved {
+    next: u64,
+    jobs: Vec<Job>,
+}
+
+#[derive(Default)]
+struct Queue {
+    next: u64,
+    jobs: Vec<Job>,
+    path: Option<String>,
+    // Lines in the file, most of them outdated once it is far more than
+    // there are jobs.
+    lines: usize,
+}
+
+impl Queue {
+    fn apply(&mut self, change: Change) {
+        match change {
+            Change::Put { job } => {
+                self.next = self.next.max(job.id);
+                match self.find(job.id) {
+                    Some(existing) => *existing = job,
+                    None => self.jobs.push(job),
+                }
+            }
+            Change::Remove { id } => self.jobs.retain(|job| job.id != id),
+        }
+    }
+
+    // Appends the changes to the file in one write, or compacts it if it
+    // holds mostly outdated lines.
+    fn record(&mut self, changes: Vec<Change>) {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => return,
+        };
+        self.lines += changes.len();
+        let result = if self.lines > COMPACT_LINES.max(self.jobs.len() * 2) {
+            self.compact(&path)
+        } else {
+            lines(&changes).and_then(|lines| {
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)?
+                    .write_all(&lines)
+            })
+        };
+        if let Err(e) = result {
+            tracing::error!(path = path.as_str(), error = %e, "could not save the outbox");
+        }
+    }
+
+    // Writes the jobs alone, like the data file to a temporary file first.
+    fn compact(&mut self, path: &str) -> io::Result<()> {
+        let changes = self
+            .jobs
+            .iter()
+            .map(|job| Change::Put { job: job.clone() })
+            .collect::<Vec<Change>>();
+        let temporary = format!("{}.tmp", path);
+        fs::write(&temporary, lines(&changes)?)?;
+        fs::rename(&temporary, path)?;
+        self.lines = changes.len();
+        Ok(())
+    }
+
+    fn find(&mut self, id: u64) -> Option<&mut Job> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+}
+
+lazy_static! {
+    static ref QUEUE: Mutex<Queue> = Mutex::new(Queue::default());
+    static ref WAKE: Condvar = Condvar::new();
+}
+
+fn queue() -> MutexGuard<'static, Queue> {
+    QUEUE.lock().unwrap()
+}
+
+// Hands the notification to the worker, so no request waits for the mail
+// server or the push services.
+pub fn enqueue(notification: Notification, target: Option<Target>) {
+    enqueue_all(vec![(notification, target)]);
+}
+
+// Like `enqueue`, writing the queue file once for all of them, e.g. the
+// notifications of a draw.
+pub fn enqueue_all(notifications: Vec<(Notification, Option<Target>)>) {
+    if notifications.is_empty() {
+        return;
+    }
+    let mut queue = queue();
+    let now = Utc::now();
+    let mut changes = Vec::new();
+    for (notification, target) in notifications {
+        let delivery = target
+            .as_ref()
+            .map(|target| delivery::queue(target, notification.channel()));
+        queue.next += 1;
+        let job = Job {
+            id: queue.next,
+            notification,
+            target,
+            delivery,
+            attempts: 0,
+            created: now,
+            due: now,
+            error: None,
+            dead: false,
+        };
+        changes.push(Change::Put { job: job.clone() });
+        queue.jobs.push(job);
+    }
+    queue.record(changes);
+    WAKE.notify_one();
+}
+
+fn backoff(attempts: u32) -> Duration {
+    let seconds = BACKOFF_SECONDS.saturating_mul(1 << attempts.saturating_sub(1).min(20));
+    Duration::seconds(seconds.min(MAX_BACKOFF_SECONDS))
+}
+
+fn send(config: &AppConfig, notification: &Notification) -> Result<(), delivery::Error> {
+    match notification {
+        Notification::Email {
+            to, message, file, ..
+        } => match &config.smtp {
+            Some(smtp) => mail::deliver(smtp, to, message, file.as_ref()),
+            None => Err("no mail server configured".to_string().into()),
+        },
+        Notification::Push {
+            subscription,
+            message,
+            ..
+        } => match &config.push {
+            Some(vapid) => push::send(vapid, subscription, message),
+            None => Err("push notifications are not configured".to_string().into()),
+        },
+    }
+}
+
+// Removes a sent job, schedules the next attempt of a failed one or gives
+// it up, if it was rejected for good or had its last attempt.
+fn settle(config: &AppConfig, id: u64, result: Result<(), delivery::Error>) {
+    let mut queue = queue();
+    let index = match queue.jobs.iter().position(|job| job.id == id) {
+        Some(index) => index,
+        // Discarded by the admin meanwhile.
+        None => return,
+    };
+    let change = match &result {
+        Ok(()) => {
+            NOTIFICATIONS.with_label_values(&["sent"]).inc();
+            let job = queue.jobs.remove(index);
+            if let (Some(target), Some(number)) = (&job.target, job.delivery) {
+                delivery::finish(target, number, &result);
+            }
+            Change::Remove { id }
+        }
+        Err(e) => {
+            NOTIFICATIONS.with_label_values(&["failed"]).inc();
+            let job = &mut queue.jobs[index];
+            job.attempts += 1;
+            job.error = Some(e.message.clone());
+            if e.bounced || job.attempts >= config.max_send_attempts {
+                job.dead = true;
+                tracing::warn!(
+                    job = id,
+                    attempts = job.attempts,
+                    error = e.message.as_str(),
+                    "gave up sending notification"
+                );
+                if let (Some(target), Some(number)) = (&job.target, job.delivery) {
+                    delivery::finish(target, number, &result);
+                }
+            } else {
+                job.due = Utc::now() + backoff(job.attempts);
+                tracing::info!(
+                    job = id,
+                    attempts = job.attempts,
+                    error = e.message.as_str(),
+                    "could not send notification, retrying later"
+                );
+            }
+            Change::Put { job: job.clone() }
+        }
+    };
+    queue.record(vec![change]);
+}
+
+// Sends the due jobs one after the other, the longest due first.
+fn work(config: AppConfig, drafts: Drafts) {
+    loop {
+        let job = {
+            let queue = queue();
+            let now = Utc::now();
+            let due = queue
+                .jobs
+                .iter()
+                .filter(|job| !job.dead && job.due <= now)
+                .min_by_key(|job| job.due)
+                .cloned();
+            match due {
+                Some(job) => job,
+                None => {
+                    drop(WAKE.wait_timeout(queue, POLL));
+                    continue;
+                }
+            }
+        };
+        let result = job
+            .notification
+            .linked(&drafts)
+            .and_then(|notification| send(&config, &notification));
+        settle(&config, job.id, result);
+    }
+}
+
+fn load(path: &str) -> io::Result<Queue> {
+    let mut queue = Queue::default();
+    if !Path::new(path).exists() {
+        return Ok(queue);
+    }
+    let content = fs::read(path)?;
+    let content = String::from_utf8_lossy(&content);
+    if let Ok(saved) = serde_json::from_str::<Saved>(&content) {
+        queue.next = saved.next;
+        queue.jobs = saved.jobs;
+        return Ok(queue);
+    }
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str::<Change>(line) {
+            Ok(change) => queue.apply(change),
+            // Cut off while it was written.
+            Err(e) => tracing::warn!(error = %e, "skipping a broken line of the outbox"),
+        }
+    }
+    Ok(queue)
+}
+
+// Picks up the jobs left from the last run and starts the worker.
+pub fn spawn(config: &AppConfig, drafts: Drafts) {
+    if let Some(path) = &config.outbox_file {
+        match load(path) {
+            Ok(mut loaded) => {
+                if let Err(e) = loaded.compact(path) {
+                    tracing::error!(path = path.as_str(), error = %e, "could not save the outbox");
+                }
+                loaded.path = Some(path.clone());
+                let pending = loaded.jobs.iter().filter(|job| !job.dead).count();
+                if pending > 0 {
+                    tracing::info!(pending, "resuming notifications");
+                }
+                *queue() = loaded;
+            }
+            Err(e) => {
+                tracing::error!(path = path.as_str(), error = %e, "could not load the outbox");
+                // Still saved there, the next notifications are appended.
+                queue().path = Some(path.clone());
+            }
+        }
+    }
+    let config = config.clone();
+    let result = thread::Builder::new()
+        .name("outbox".to_string())
+        .spawn(move || work(config, drafts));
+    if let Err(e) = result {
+        tracing::error!(error = %e, "could not start the outbox");
+    }
+}
+
+// The given up jobs first, then by creation.
+pub fn entries() -> Vec<Entry> {
+    let queue = queue();
+    let mut entries = queue
+        .jobs
+        .iter()
+        .map(|job| Entry {
+            id: job.id,
+            channel: job.notification.channel(),
+            draft: job.target.as_ref().map(|target| target.draft),
+            member: job.target.as_ref().map(|target| target.member.clone()),
+            attempts: job.attempts,
+            created: job.created,
+            due: job.due,
+            error: job.error.clone(),
+            dead: job.dead,
+        })
+        .collect::<Vec<Entry>>();
+    entries.sort_by_key(|entry| (!entry.dead, entry.created));
+    entries
+}
+
+// Sends a given up job again, with all attempts.
+fn retry(id: u64) -> Result<(), Status> {
+    let mut queue = queue();
+    let job = queue.find(id).ok_or(Status::NotFound)?;
+    if !job.dead {
+        return Err(Status::Conflict);
+    }
+    job.dead = false;
+    job.attempts = 0;
+    job.due = Utc::now();
+    job.delivery = job
+        .target
+        .as_ref()
+        .map(|target| delivery::queue(target, job.notification.channel()));
+    tracing::info!(job = id, "notification retried by admin");
+    let change = Change::Put { job: job.clone() };
+    queue.record(vec![change]);
+    WAKE.notify_one();
+    Ok(())
+}
+
+fn discard(id: u64) -> Result<(), Status> {
+    let mut queue = queue();
+    let index = queue
+        .jobs
+        .iter()
+        .position(|job| job.id == id)
+        .ok_or(Status::NotFound)?;
+    queue.jobs.remove(index);
+    tracing::info!(job = id, "notification discarded by admin");
+    queue.record(vec![Change::Remove { id }]);
+    Ok(())
+}
+
+#[get("/api/admin/outbox")]
+pub fn api_list(_admin: Admin) -> Json<Vec<Entry>> {
+    Json(entries())
+}
+
+#[post("/api/admin/outbox/<id>/retry")]
+pub fn api_retry(id: u64, _admin: Admin) -> Result<Status, Status> {
+    retry(id)?;
+    Ok(Status::Accepted)
+}
+
+#[delete("/api/admin/outbox/<id>")]
+pub fn api_discard(id: u64, _admin: Admin) -> Result<Status, Status> {
+    discard(id)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/admin/outbox/<id>/retry")]
+pub fn retry_job(id: u64, _admin: Admin) -> Result<Redirect, Status> {
+    retry(id)?;
+    Ok(Redirect::to(uri!(admin::dashboard)))
+}
+
+#[post("/admin/outbox/<id>/delete")]
+pub fn discard_job(id: u64, _admin: Admin) -> Result<Redirect, Status> {
+    discard(id)?;
+    Ok(Redirect::to(uri!(admin::dashboard)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: u64) -> Job {
+        Job {
+            id,
+            notification: Notification::Email {
+                to: "anna@example.com".to_string(),
+                message: mail::Message {
+                    subject: "Wichteln".to_string(),
+                    body: format!("Dein Los: {}", LINK),
+                },
+                file: None,
+                link: None,
+            },
+            target: None,
+            delivery: None,
+            attempts: 0,
+            created: Utc::now(),
+            due: Utc::now(),
+            error: None,
+            dead: false,
+        }
+    }
+
+    fn path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("outbox-{}-{}", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn replays_the_appended_changes() {
+        let path = path("replay");
+        let mut queue = Queue {
+            path: Some(path.clone()),
+            ..Queue::default()
+        };
+        queue.record(vec![
+            Change::Put { job: job(1) },
+            Change::Put { job: job(2) },
+        ]);
+        let mut failed = job(2);
+        failed.attempts = 1;
+        queue.record(vec![Change::Remove { id: 1 }, Change::Put { job: failed }]);
+        // Cut off while it was written.
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"change\":\"put\",\"jo")
+            .unwrap();
+        let loaded = load(&path).unwrap();
+        assert_eq!(loaded.next, 2);
+        assert_eq!(loaded.jobs.len(), 1);
+        assert_eq!((loaded.jobs[0].id, loaded.jobs[0].attempts), (2, 1));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reads_the_queue_written_whole() {
+        let path = path("whole");
+        let saved = serde_json::json!({ "next": 7, "jobs": [job(7)] });
+        fs::write(&path, serde_json::to_vec(&saved).unwrap()).unwrap();
+        let loaded = load(&path).unwrap();
+        assert_eq!(loaded.next, 7);
+        assert_eq!(loaded.jobs.len(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+}
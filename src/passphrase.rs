@@ -0,0 +1,228 @@
+use crate::config::AppConfig;
+use crate::i18n::{self, Locale};
+use crate::{events, login, Drafts};
+use aes_gcm::aead::{Aead, NewAead};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use p256::ecdh::EphemeralSecret;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::PublicKey;
+use rand::rngs::OsRng;
+use rand::Rng;
+use rocket::http::{Cookies, Status};
+use rocket::State;
+use rocket_contrib::json::Json;
+use rocket_contrib::templates::Template;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+pub use api::passphrase::{Keys, Locked, LockedTicket};
+
+// Whether the public key is a P-256 point and the rest is base64, as the
+// browser sends it.
+fn is_valid(keys: &Keys) -> bool {
+    let public_key = base64::decode(&keys.public_key).ok();
+    public_key.map_or(false, |key| PublicKey::from_sec1_bytes(&key).is_ok())
+        && [&keys.wrapped_key, &keys.salt, &keys.iv]
+            .iter()
+            .all(|value| base64::decode(value).is_ok())
+}
+
+// Encrypts the name of a recipient. Nothing but the giver's passphrase
+// opens it afterwards.
+pub fn lock(keys: &Keys, name: &str) -> Result<Locked, String> {
+    let public_key = base64::decode(&keys.public_key).map_err(|e| e.to_string())?;
+    let public_key = PublicKey::from_sec1_bytes(&public_key).map_err(|e| e.to_string())?;
+    let secret = EphemeralSecret::random(&mut OsRng);
+    let shared = secret.diffie_hellman(&public_key);
+    let key = Sha256::digest(shared.raw_secret_bytes());
+    let cipher = Aes256Gcm::new(Key::from_slice(&key));
+    let iv = rand::thread_rng().gen::<[u8; 12]>();
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&iv), name.as_bytes())
+        .map_err(|e| e.to_string())?;
+    Ok(Locked {
+        ephemeral_key: base64::encode(secret.public_key().to_encoded_point(false).as_bytes()),
+        iv: base64::encode(iv),
+        ciphertext: base64::encode(ciphertext),
+    })
+}
+
+// Keys can be replaced until the draw, e.g. after a forgotten passphrase.
+#[put("/api/draft/<id>/ticket/<key>/keys", format = "json", data = "<keys>")]
+pub fn api_set_keys(
+    id: usize,
+    key: String,
+    keys: Json<Keys>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !draft.passphrases {
+        return Err(Status::NotFound);
+    }
+    if draft.is_drawn() || draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    if !is_valid(&keys) {
+        return Err(Status::UnprocessableEntity);
+    }
+    let mut member = draft
+        .member_by_token(&key)
+        .cloned()
+        .ok_or(Status::NotFound)?;
+    draft.members.remove(&member);
+    member.keys = Some(keys.into_inner());
+    draft.members.insert(member);
+    Ok(Status::NoContent)
+}
+
+// Everything the ticket page needs to open the ticket in the browser.
+#[get("/api/draft/<id>/ticket/<key>/locked")]
+pub fn api_locked_ticket(
+    id: usize,
+    key: String,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+    mut cookies: Cookies,
+) -> Result<Json<LockedTicket>, Status> {
+    let signed_in = login::signed_in(id, &mut cookies);
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    let member = draft.member_by_token(&key).ok_or(Status::NotFound)?;
+    let locked = match (&member.keys, &member.locked) {
+        (Some(keys), Some(ticket)) => LockedTicket {
+            keys: keys.clone(),
+            ticket: ticket.clone(),
+        },
+        _ => return Err(Status::NotFound),
+    };
+    let member = draft
+        .view_ticket(&key, signed_in.as_deref(), &config)
+        .ok_or(Status::NotFound)?;
+    events.publish(id, events::Event::TicketViewed { name: member.name });
+    Ok(Json(locked))
+}
+
+#[derive(Serialize)]
+struct PageContext {
+    id: usize,
+    key: String,
+    name: String,
+    drawn: bool,
+    keys: bool,
+}
+
+// The ticket page of a draft in the zero-knowledge mode, none for other
+// drafts. Only a member's own link leads to it.
+pub fn page(id: usize, key: &str, locale: Locale, drafts: &Drafts) -> Option<Template> {
+    let entry = drafts.get(id)?;
+    let draft = entry.read();
+    if !draft.passphrases {
+        return None;
+    }
+    let context = match draft.member_by_token(key) {
+        Some(member) => PageContext {
+            id,
+            key: key.to_string(),
+            name: member.name.clone(),
+            drawn: draft.is_drawn(),
+            keys: member.keys.is_some(),
+        },
+        None => {
+            let mut context = HashMap::new();
+            context.insert("id", id.to_string());
+            return Some(i18n::render("ticket_not_found", locale, context));
+        }
+    };
+    Some(i18n::render("ticket_locked", locale, context))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdh::diffie_hellman;
+    use p256::SecretKey;
+
+    // Stands in for the PBKDF2 of the browser, which wraps the private key
+    // with a key derived from the passphrase the same way.
+    fn wrapping_key(passphrase: &str, salt: &[u8]) -> Aes256Gcm {
+        let key = Sha256::digest([salt, passphrase.as_bytes()].concat());
+        Aes256Gcm::new(Key::from_slice(&key))
+    }
+
+    // The keys the ticket page saves for a passphrase.
+    fn keys(passphrase: &str) -> Keys {
+        let secret = SecretKey::random(&mut OsRng);
+        let salt = rand::thread_rng().gen::<[u8; 16]>();
+        let iv = rand::thread_rng().gen::<[u8; 12]>();
+        let wrapped = wrapping_key(passphrase, &salt)
+            .encrypt(Nonce::from_slice(&iv), secret.to_be_bytes().as_slice())
+            .unwrap();
+        Keys {
+            public_key: base64::encode(secret.public_key().to_encoded_point(false).as_bytes()),
+            wrapped_key: base64::encode(wrapped),
+            salt: base64::encode(salt),
+            iv: base64::encode(iv),
+        }
+    }
+
+    // What the ticket page does to open a locked ticket.
+    fn unlock(keys: &Keys, locked: &Locked, passphrase: &str) -> Option<String> {
+        let salt = base64::decode(&keys.salt).ok()?;
+        let iv = base64::decode(&keys.iv).ok()?;
+        let secret = wrapping_key(passphrase, &salt)
+            .decrypt(
+                Nonce::from_slice(&iv),
+                base64::decode(&keys.wrapped_key).ok()?.as_slice(),
+            )
+            .ok()?;
+        let secret = SecretKey::from_be_bytes(&secret).ok()?;
+        let ephemeral = base64::decode(&locked.ephemeral_key).ok()?;
+        let ephemeral = PublicKey::from_sec1_bytes(&ephemeral).ok()?;
+        let shared = diffie_hellman(secret.to_nonzero_scalar(), ephemeral.as_affine());
+        let key = Sha256::digest(shared.raw_secret_bytes());
+        let name = Aes256Gcm::new(Key::from_slice(&key))
+            .decrypt(
+                Nonce::from_slice(&base64::decode(&locked.iv).ok()?),
+                base64::decode(&locked.ciphertext).ok()?.as_slice(),
+            )
+            .ok()?;
+        String::from_utf8(name).ok()
+    }
+
+    #[test]
+    fn opens_with_the_passphrase() {
+        let keys = keys("correct horse");
+        assert!(is_valid(&keys));
+        let locked = lock(&keys, "Anna").unwrap();
+        assert_eq!(
+            unlock(&keys, &locked, "correct horse").as_deref(),
+            Some("Anna")
+        );
+    }
+
+    #[test]
+    fn stays_locked_with_a_wrong_passphrase() {
+        let keys = keys("correct horse");
+        let locked = lock(&keys, "Anna").unwrap();
+        assert_eq!(unlock(&keys, &locked, "battery staple"), None);
+    }
+
+    #[test]
+    fn stays_locked_with_another_key() {
+        let mine = keys("correct horse");
+        let other = keys("correct horse");
+        let locked = lock(&other, "Anna").unwrap();
+        assert_eq!(unlock(&mine, &locked, "correct horse"), None);
+    }
+
+    #[test]
+    fn rejects_keys_that_are_no_point() {
+        let mut keys = keys("correct horse");
+        keys.public_key = base64::encode([4u8; 65]);
+        assert!(!is_valid(&keys));
+        assert!(lock(&keys, "Anna").is_err());
+    }
+}
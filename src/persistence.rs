@@ -0,0 +1,27 @@
+use crate::backup::{self, Backup};
+use crate::Draft;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub fn load(path: &str) -> io::Result<Vec<Option<Draft>>> {
+    if !Path::new(path).exists() {
+        return Ok(Vec::new());
+    }
+    let backup: Backup = serde_json::from_slice(&fs::read(path)?)?;
+    if backup.version != backup::VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unsupported data file version {}", backup.version),
+        ));
+    }
+    Ok(backup.drafts)
+}
+
+// Writes to a temporary file first, so an interrupted write never
+// destroys the previous state.
+pub fn save(path: &str, drafts: Vec<Option<Draft>>) -> io::Result<()> {
+    let temporary = format!("{}.tmp", path);
+    fs::write(&temporary, serde_json::to_vec(&Backup::new(drafts))?)?;
+    fs::rename(temporary, path)
+}
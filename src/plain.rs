@@ -0,0 +1,65 @@
+use crate::config::AppConfig;
+use crate::i18n::Locale;
+use crate::{address, events, language, login, Drafts};
+use rocket::http::Cookies;
+use rocket::response::status;
+use rocket::State;
+
+// The ticket page as plain text, for `Accept: text/plain`: nothing but the
+// facts, one per line, so it reads well with curl, a screen reader or on
+// devices without scripts.
+#[get("/draft/<id>/ticket/<name>", format = "text/plain", rank = 1)]
+pub fn show_ticket(
+    id: usize,
+    name: String,
+    locale: Locale,
+    config: State<AppConfig>,
+    events: State<events::Events>,
+    drafts: State<Drafts>,
+    mut cookies: Cookies,
+) -> Result<String, status::NotFound<String>> {
+    let signed_in = login::signed_in(id, &mut cookies);
+    let locale = language::for_key(id, &name, locale, &drafts);
+    let not_found = || status::NotFound(format!("{}\n", locale.text("ticket_not_found_heading")));
+    let entry = drafts.get(id).ok_or_else(not_found)?;
+    let mut draft = entry.write();
+    // The ticket only opens with the passphrase, which needs the page.
+    if draft.passphrases {
+        return match draft.member_by_token(&name) {
+            Some(_) => Ok(format!("{}\n", locale.text("ticket_plain_locked"))),
+            None => Err(not_found()),
+        };
+    }
+    let member = draft
+        .view_ticket(&name, signed_in.as_deref(), &config)
+        .ok_or_else(not_found)?;
+    let recipient = member.recipient().ok_or_else(not_found)?;
+    let recipient_member = draft.find_member(&recipient);
+
+    let mut lines = vec![
+        draft.title.clone(),
+        locale.format_date(&draft.local_date(), true),
+        String::new(),
+        format!(
+            "{}, {} {}",
+            member.name,
+            locale.text("ticket_yours"),
+            recipient
+        ),
+    ];
+    if let Some(budget) = recipient_member.and_then(|recipient| draft.budget_for(recipient)) {
+        lines.push(format!("{}: {} €", locale.text("ticket_budget"), budget));
+    }
+    if let Some(wishlist) = recipient_member.and_then(|recipient| recipient.wishlist.as_ref()) {
+        lines.push(String::new());
+        lines.push(format!("{}:", locale.text("ticket_wishlist")));
+        lines.push(wishlist.clone());
+    }
+    if let Some(address) = recipient_member.and_then(address::of) {
+        lines.push(String::new());
+        lines.push(format!("{}:", locale.text("address_recipient")));
+        lines.push(address);
+    }
+    events.publish(id, events::Event::TicketViewed { name: member.name });
+    Ok(lines.join("\n") + "\n")
+}
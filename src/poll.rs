@@ -0,0 +1,209 @@
+use crate::i18n::{self, Locale};
+use crate::{audit, sanitize, Draft, Drafts, Organizer};
+use rocket::http::Status;
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use rocket_contrib::templates::Template;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub use api::poll::{NewPoll, OptionView, PollView, Vote};
+
+// A question the organizer asks the members before the draw, e.g. about
+// the theme of the gifts.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Poll {
+    pub question: String,
+    pub options: Vec<String>,
+    // The option every member voted for, by the member's name.
+    #[serde(default)]
+    pub votes: BTreeMap<String, usize>,
+}
+
+impl Poll {
+    // The results, marking the choice of `voter` if given.
+    pub fn view(&self, index: usize, voter: Option<&str>) -> PollView {
+        let choice = voter.and_then(|voter| self.votes.get(voter));
+        PollView {
+            index,
+            question: self.question.clone(),
+            options: self
+                .options
+                .iter()
+                .enumerate()
+                .map(|(option, text)| OptionView {
+                    text: text.clone(),
+                    votes: self.votes.values().filter(|vote| **vote == option).count(),
+                    chosen: choice == Some(&option),
+                })
+                .collect(),
+            votes: self.votes.len(),
+        }
+    }
+}
+
+pub fn views(draft: &Draft, voter: Option<&str>) -> Vec<PollView> {
+    draft
+        .polls
+        .iter()
+        .enumerate()
+        .map(|(index, poll)| poll.view(index, voter))
+        .collect()
+}
+
+#[derive(FromForm)]
+pub struct PollForm {
+    question: String,
+    // One option per line.
+    options: String,
+}
+
+fn add(id: usize, poll: NewPoll, organizer: &Organizer, drafts: &Drafts) -> Result<usize, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    if draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    let question = sanitize::line(&poll.question);
+    let options = poll
+        .options
+        .iter()
+        .map(|option| sanitize::line(option))
+        .filter(|option| option != "")
+        .collect::<Vec<String>>();
+    if question == "" || options.len() < 2 {
+        return Err(Status::UnprocessableEntity);
+    }
+    draft.record(
+        audit::Actor::Organizer,
+        audit::Action::PollCreated {
+            question: question.clone(),
+        },
+    );
+    draft.polls.push(Poll {
+        question,
+        options,
+        votes: BTreeMap::new(),
+    });
+    Ok(draft.polls.len() - 1)
+}
+
+// Votes are only taken from a member's personal link, so nobody can vote
+// in the name of someone else.
+fn vote(id: usize, index: usize, key: &str, option: usize, drafts: &Drafts) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    let name = draft
+        .member_by_token(key)
+        .map(|member| member.name.clone())
+        .ok_or(Status::NotFound)?;
+    let poll = draft.polls.get_mut(index).ok_or(Status::NotFound)?;
+    if option >= poll.options.len() {
+        return Err(Status::UnprocessableEntity);
+    }
+    poll.votes.insert(name, option);
+    Ok(())
+}
+
+#[post("/api/draft/<id>/poll", format = "json", data = "<poll>")]
+pub fn api_create(
+    id: usize,
+    poll: Json<NewPoll>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Json<usize>, Status> {
+    add(id, poll.into_inner(), &organizer, &drafts).map(Json)
+}
+
+#[post("/draft/<id>/poll", data = "<poll>")]
+pub fn create(
+    id: usize,
+    poll: Form<PollForm>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    let poll = poll.into_inner();
+    let poll = NewPoll {
+        question: poll.question,
+        options: poll.options.lines().map(|line| line.to_string()).collect(),
+    };
+    add(id, poll, &organizer, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+#[get("/api/draft/<id>/poll")]
+pub fn api_results(id: usize, drafts: State<Drafts>) -> Result<Json<Vec<PollView>>, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    Ok(Json(views(&draft, None)))
+}
+
+#[post(
+    "/api/draft/<id>/poll/<index>/vote/<key>",
+    format = "json",
+    data = "<choice>"
+)]
+pub fn api_vote(
+    id: usize,
+    index: usize,
+    key: String,
+    choice: Json<Vote>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    vote(id, index, &key, choice.option, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/poll/<index>/vote/<key>", data = "<choice>")]
+pub fn cast_vote(
+    id: usize,
+    index: usize,
+    key: String,
+    choice: Form<Vote>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    vote(id, index, &key, choice.option, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}/polls/{}", id, key)))
+}
+
+#[derive(Serialize)]
+struct VotingContext {
+    id: usize,
+    key: String,
+    title: String,
+    polls: Vec<PollView>,
+}
+
+// The polls as seen by one member, with their votes.
+#[get("/draft/<id>/polls/<key>")]
+pub fn show(
+    id: usize,
+    key: String,
+    locale: Locale,
+    drafts: State<Drafts>,
+) -> Result<Template, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    let name = draft
+        .member_by_token(&key)
+        .map(|member| member.name.clone())
+        .ok_or(Status::NotFound)?;
+    Ok(i18n::render(
+        "polls",
+        locale,
+        VotingContext {
+            id,
+            key,
+            title: draft.title.clone(),
+            polls: views(&draft, Some(&name)),
+        },
+    ))
+}
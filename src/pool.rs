@@ -0,0 +1,95 @@
+use crate::{avatar, sanitize, Member};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+pub use api::pool::Pool;
+
+// A pool without budget, named as stored.
+pub fn new(name: String) -> Pool {
+    Pool {
+        name: sanitize::line(&name),
+        budget: None,
+    }
+}
+
+// The members of every pool, the main pool first.
+pub fn group<'a, I>(members: I) -> BTreeMap<Option<&'a str>, Vec<&'a Member>>
+where
+    I: IntoIterator<Item = &'a Member>,
+{
+    let mut pools = BTreeMap::new();
+    for member in members {
+        pools
+            .entry(member.pool.as_deref())
+            .or_insert_with(Vec::new)
+            .push(member);
+    }
+    pools
+}
+
+// Adds a pool without budget for every pool a member names that isn't
+// defined yet.
+pub fn complete<'a, I>(pools: &mut Vec<Pool>, members: I)
+where
+    I: IntoIterator<Item = &'a Member>,
+{
+    for member in members {
+        if let Some(name) = &member.pool {
+            if !pools.iter().any(|pool| &pool.name == name) {
+                pools.push(new(name.clone()));
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct View {
+    pub name: Option<String>,
+    pub budget: Option<u32>,
+    pub members: Vec<MemberView>,
+}
+
+#[derive(Serialize)]
+pub struct MemberView {
+    pub name: String,
+    pub avatar: Option<avatar::Avatar>,
+}
+
+// The pools of a draft with the names of their members, the main pool
+// first if it has any members.
+pub fn views(pools: &[Pool], members: &HashSet<Member>) -> Vec<View> {
+    let groups = group(members);
+    let names = |pool: Option<&str>| {
+        let mut names = groups
+            .get(&pool)
+            .map(|members| {
+                members
+                    .iter()
+                    .map(|member| MemberView {
+                        name: member.name.clone(),
+                        avatar: avatar::of(member),
+                    })
+                    .collect::<Vec<MemberView>>()
+            })
+            .unwrap_or_default();
+        names.sort_by(|a, b| a.name.cmp(&b.name));
+        names
+    };
+    let mut views = Vec::new();
+    let main = names(None);
+    if !main.is_empty() || pools.is_empty() {
+        views.push(View {
+            name: None,
+            budget: None,
+            members: main,
+        });
+    }
+    for pool in pools {
+        views.push(View {
+            name: Some(pool.name.clone()),
+            budget: pool.budget,
+            members: names(Some(&pool.name)),
+        });
+    }
+    views
+}
@@ -0,0 +1,79 @@
+use crate::i18n::Locale;
+use crate::{elephant, pool};
+use rocket_contrib::json::Json;
+use std::collections::BTreeMap;
+
+pub use api::preset::{Preset, Row};
+
+fn rows(teams: &[u32], pool: Option<&str>) -> Vec<Row> {
+    teams
+        .iter()
+        .map(|team| Row {
+            team: *team,
+            pool: pool.map(str::to_string),
+        })
+        .collect()
+}
+
+// Three couples, each one team so partners don't draw each other.
+fn couples(locale: Locale) -> Preset {
+    Preset {
+        id: "couples".to_string(),
+        title: locale.text("preset_couples").to_string(),
+        description: locale.text("preset_couples_help").to_string(),
+        mode: elephant::Mode::SecretSanta,
+        members: rows(&[1, 1, 2, 2, 3, 3], None),
+        pools: Vec::new(),
+        budget: Some(25),
+        team_budgets: BTreeMap::new(),
+    }
+}
+
+// Departments as teams, so everyone gives to someone they don't work with
+// every day.
+fn office(locale: Locale) -> Preset {
+    Preset {
+        id: "office".to_string(),
+        title: locale.text("preset_office").to_string(),
+        description: locale.text("preset_office_help").to_string(),
+        mode: elephant::Mode::SecretSanta,
+        members: rows(&[1, 1, 1, 2, 2, 2, 3, 3, 3], None),
+        pools: Vec::new(),
+        budget: Some(15),
+        team_budgets: BTreeMap::new(),
+    }
+}
+
+// Households as teams, the kids drawn among themselves with a budget of
+// their own.
+fn family(locale: Locale) -> Preset {
+    let kids = locale.text("preset_family_kids");
+    let mut members = rows(&[1, 1, 2, 2], None);
+    members.extend(rows(&[1, 2], Some(kids)));
+    Preset {
+        id: "family".to_string(),
+        title: locale.text("preset_family").to_string(),
+        description: locale.text("preset_family_help").to_string(),
+        mode: elephant::Mode::SecretSanta,
+        members,
+        pools: vec![pool::Pool {
+            name: kids.to_string(),
+            budget: Some(20),
+        }],
+        budget: Some(30),
+        team_budgets: BTreeMap::new(),
+    }
+}
+
+pub fn all(locale: Locale) -> Vec<Preset> {
+    vec![couples(locale), office(locale), family(locale)]
+}
+
+pub fn find(id: &str, locale: Locale) -> Option<Preset> {
+    all(locale).into_iter().find(|preset| preset.id == id)
+}
+
+#[get("/api/templates")]
+pub fn api_templates(locale: Locale) -> Json<Vec<Preset>> {
+    Json(all(locale))
+}
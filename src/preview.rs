@@ -0,0 +1,80 @@
+use crate::config::AppConfig;
+use crate::delivery::{Channel, Kind};
+use crate::{elephant, export, mail, push, Drafts, Organizer};
+use rocket::http::Status;
+use rocket::response::status;
+use rocket::State;
+use rocket_contrib::json::Json;
+
+pub use api::preview::Preview;
+
+// Stands in for the token in the links, so the preview opens no ticket.
+pub const PLACEHOLDER: &str = "preview";
+
+// Every email and push notification the draw and the reminder would send,
+// rendered from the organizer's templates for each member, in their
+// language. The links carry a placeholder instead of the members' tokens.
+#[get("/api/draft/<id>/notifications/preview")]
+pub fn api_preview(
+    id: usize,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Json<Vec<Preview>>, status::Custom<String>> {
+    let entry = drafts
+        .get(id)
+        .ok_or_else(|| status::Custom(Status::NotFound, String::new()))?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) {
+        return Err(status::Custom(Status::Forbidden, String::new()));
+    }
+    let mut previews = Vec::new();
+    if config.smtp.is_some() && draft.mode != elephant::Mode::WhiteElephant {
+        for member in &draft.members {
+            let email = match member.email.as_deref().map(str::trim) {
+                Some(email) if !email.is_empty() => email,
+                _ => continue,
+            };
+            let mut placeholder = member.clone();
+            placeholder.token = PLACEHOLDER.to_string();
+            let ticket_url = push::ticket_url(&config, id, &placeholder);
+            let emails = mail::emails(&draft, member, ticket_url, config.default_locale())
+                .map_err(|e| status::Custom(Status::UnprocessableEntity, e))?;
+            previews.push(Preview {
+                name: member.name.clone(),
+                channel: Channel::Email,
+                kind: Kind::Ticket,
+                to: Some(email.to_string()),
+                subject: emails.ticket.subject,
+                body: emails.ticket.body,
+                attachment: Some(export::filename(&draft, "ics")),
+            });
+        }
+    }
+    if config.push.is_some() {
+        let messages = push::drawn(&draft, id, &config)
+            .into_iter()
+            .chain(push::reminders(&draft, id, &config));
+        for (target, _, message) in messages {
+            // Once per member, however many devices they subscribed.
+            let known = previews.iter().any(|preview| {
+                preview.channel == Channel::Push
+                    && preview.kind == target.kind
+                    && preview.name == target.member
+            });
+            if !known {
+                previews.push(Preview {
+                    name: target.member,
+                    channel: Channel::Push,
+                    kind: target.kind,
+                    to: None,
+                    subject: message.title,
+                    body: message.body,
+                    attachment: None,
+                });
+            }
+        }
+    }
+    previews.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Json(previews))
+}
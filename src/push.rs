@@ -0,0 +1,226 @@
+use crate::config::{AppConfig, PushConfig};
+use crate::delivery::{self, Kind, Target};
+use crate::i18n::Locale;
+use crate::outbox::{self, Link, Notification, Page};
+use crate::{elephant, Draft, Drafts, Member};
+use chrono::{Duration, Utc};
+use rocket::http::ContentType;
+use rocket::response::content::Content;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::thread;
+use web_push::{
+    ContentEncoding, SubscriptionInfo, VapidSignatureBuilder, WebPushClient, WebPushError,
+    WebPushMessageBuilder,
+};
+
+pub use api::push::{Keys, Subscription};
+
+// Days before the exchange the reminder is sent.
+pub const REMINDER_DAYS: i64 = 3;
+// Browsers subscribed per member, the oldest one is dropped beyond.
+pub const MAX_SUBSCRIPTIONS: usize = 5;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Message {
+    pub title: String,
+    pub body: String,
+    pub url: String,
+}
+
+pub fn send(
+    config: &PushConfig,
+    subscription: &Subscription,
+    message: &Message,
+) -> Result<(), delivery::Error> {
+    let info = SubscriptionInfo::new(
+        &subscription.endpoint,
+        &subscription.keys.p256dh,
+        &subscription.keys.auth,
+    );
+    let key = File::open(&config.private_key).map_err(|e| e.to_string())?;
+    let mut signature = VapidSignatureBuilder::from_pem(key, &info).map_err(|e| e.to_string())?;
+    signature.add_claim("sub", config.subject.as_str());
+    let payload = serde_json::to_vec(message).map_err(|e| e.to_string())?;
+    let mut builder = WebPushMessageBuilder::new(&info).map_err(|e| e.to_string())?;
+    builder.set_payload(ContentEncoding::Aes128Gcm, &payload);
+    builder.set_vapid_signature(signature.build().map_err(|e| e.to_string())?);
+    let message = builder.build().map_err(|e| e.to_string())?;
+    let client = WebPushClient::new().map_err(|e| e.to_string())?;
+    futures::executor::block_on(client.send(message)).map_err(|e| delivery::Error {
+        // The browser dropped the subscription.
+        bounced: matches!(
+            e,
+            WebPushError::EndpointNotValid | WebPushError::EndpointNotFound
+        ),
+        message: e.to_string(),
+    })
+}
+
+// Sends the messages through the outbox.
+pub fn notify(config: &AppConfig, messages: Vec<(Target, Subscription, Message)>) {
+    if config.push.is_none() {
+        return;
+    }
+    outbox::enqueue_all(
+        messages
+            .into_iter()
+            .map(|(target, subscription, message)| {
+                let link = Link {
+                    base: base_url(config),
+                    draft: target.draft,
+                    member: target.member.clone(),
+                    page: Page::Ticket,
+                };
+                let notification = Notification::Push {
+                    subscription,
+                    message,
+                    link: Some(link),
+                };
+                (notification, Some(target))
+            })
+            .collect(),
+    );
+}
+
+// One message per subscription of every member of the draft, with the
+// body in the member's language.
+pub fn messages(
+    draft: &Draft,
+    id: usize,
+    kind: Kind,
+    url: impl Fn(&Member) -> String,
+    title: &str,
+    body: impl Fn(&Member) -> String,
+) -> Vec<(Target, Subscription, Message)> {
+    draft
+        .members
+        .iter()
+        .flat_map(|member| {
+            let url = url(member);
+            let body = body(member);
+            let target = Target {
+                draft: id,
+                member: member.name.clone(),
+                kind,
+            };
+            member.push.iter().map(move |subscription| {
+                (
+                    target.clone(),
+                    subscription.clone(),
+                    Message {
+                        title: title.to_string(),
+                        body: body.clone(),
+                        url: url.clone(),
+                    },
+                )
+            })
+        })
+        .collect()
+}
+
+pub fn drawn(draft: &Draft, id: usize, config: &AppConfig) -> Vec<(Target, Subscription, Message)> {
+    // A white elephant game has no tickets to look at.
+    if draft.mode == elephant::Mode::WhiteElephant {
+        return Vec::new();
+    }
+    let locale = config.default_locale();
+    messages(
+        draft,
+        id,
+        Kind::Ticket,
+        |_| outbox::LINK.to_string(),
+        &draft.title,
+        |member| member.locale(locale).text("push_drawn").to_string(),
+    )
+}
+
+// Empty without a configured base URL, so links are relative and the
+// worker resolves them against the origin of the instance.
+fn base_url(config: &AppConfig) -> String {
+    config
+        .base_url
+        .as_deref()
+        .unwrap_or("")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+pub fn ticket_url(config: &AppConfig, id: usize, member: &Member) -> String {
+    format!("{}/draft/{}/ticket/{}", base_url(config), id, member.token)
+}
+
+// What `outbox::LINK` stands for in the member's ticket notifications.
+pub fn ticket_link(config: &AppConfig, id: usize, member: &Member) -> Link {
+    Link {
+        base: base_url(config),
+        draft: id,
+        member: member.name.clone(),
+        page: Page::Ticket,
+    }
+}
+
+fn reminder(locale: Locale) -> String {
+    locale
+        .text("push_reminder")
+        .replace("{days}", &REMINDER_DAYS.to_string())
+}
+
+pub fn reminders(
+    draft: &Draft,
+    id: usize,
+    config: &AppConfig,
+) -> Vec<(Target, Subscription, Message)> {
+    let locale = config.default_locale();
+    messages(
+        draft,
+        id,
+        Kind::Reminder,
+        |_| outbox::LINK.to_string(),
+        &draft.title,
+        |member| reminder(member.locale(locale)),
+    )
+}
+
+// Sends the reminders of drafts whose exchange is close, once per draft.
+pub fn remind(drafts: &Drafts, config: &AppConfig) {
+    let now = Utc::now();
+    let mut pending = Vec::new();
+    for (id, entry) in drafts.active() {
+        let mut draft = entry.write();
+        if draft.reminded
+            || !draft.is_drawn()
+            || !draft.is_upcoming()
+            || draft.date.with_timezone(&Utc) - now > Duration::days(REMINDER_DAYS)
+        {
+            continue;
+        }
+        draft.reminded = true;
+        pending.extend(reminders(&draft, id, config));
+    }
+    notify(config, pending);
+}
+
+pub fn spawn(drafts: Drafts, config: &AppConfig) {
+    if config.push.is_none() {
+        return;
+    }
+    let config = config.clone();
+    let result = thread::Builder::new()
+        .name("reminders".to_string())
+        .spawn(move || loop {
+            remind(&drafts, &config);
+            thread::sleep(std::time::Duration::from_secs(3600));
+        });
+    if let Err(e) = result {
+        tracing::error!(error = %e, "could not start the reminders");
+    }
+}
+
+#[get("/push/worker.js")]
+pub fn worker() -> Content<&'static str> {
+    Content(
+        ContentType::JavaScript,
+        include_str!("../static/push-worker.js"),
+    )
+}
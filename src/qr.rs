@@ -0,0 +1,86 @@
+use crate::config::AppConfig;
+use crate::outbox::{Link, Page};
+use crate::Member;
+use qrcode::render::svg;
+use qrcode::{Color, QrCode};
+use rocket::request::{self, FromRequest, Request};
+use rocket::State;
+
+// Base URL for absolute links, taken from the app config or else from the
+// scheme and host the request was made to.
+pub struct Origin(String);
+
+impl Origin {
+    pub fn ticket_url(&self, id: usize, member: &Member) -> String {
+        format!("{}/draft/{}/ticket/{}", self.0, id, member.token)
+    }
+
+    pub fn draft_url(&self, key: &str) -> String {
+        format!("{}/draft/{}", self.0, key)
+    }
+
+    pub fn feed_url(&self) -> String {
+        format!("{}/feed.xml", self.0)
+    }
+
+    pub fn login_url(&self, id: usize, token: &str) -> String {
+        format!("{}/draft/{}/login/{}", self.0, id, token)
+    }
+
+    pub fn share_url(&self, id: usize, token: &str) -> String {
+        format!("{}/draft/{}/share/{}", self.0, id, token)
+    }
+
+    pub fn organizer_url(&self, id: usize, code: &str) -> String {
+        format!("{}/draft/{}/organizers/{}", self.0, id, code)
+    }
+
+    // What `outbox::LINK` stands for in a notification to the member.
+    pub fn link(&self, id: usize, member: &Member, page: Page) -> Link {
+        Link {
+            base: self.0.clone(),
+            draft: id,
+            member: member.name.clone(),
+            page,
+        }
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Origin {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        if let request::Outcome::Success(config) = request.guard::<State<AppConfig>>() {
+            if let Some(base_url) = &config.base_url {
+                return request::Outcome::Success(Origin(
+                    base_url.trim_end_matches('/').to_string(),
+                ));
+            }
+        }
+        let headers = request.headers();
+        let scheme = headers.get_one("X-Forwarded-Proto").unwrap_or("http");
+        let host = headers.get_one("Host").unwrap_or("localhost");
+        request::Outcome::Success(Origin(format!("{}://{}", scheme, host)))
+    }
+}
+
+pub fn svg(url: &str) -> Option<String> {
+    match QrCode::new(url.as_bytes()) {
+        Ok(code) => Some(code.render::<svg::Color>().min_dimensions(160, 160).build()),
+        Err(_) => None,
+    }
+}
+
+// Width of the code and its dark modules, row by row.
+pub fn modules(url: &str) -> Option<(usize, Vec<bool>)> {
+    match QrCode::new(url.as_bytes()) {
+        Ok(code) => Some((
+            code.width(),
+            code.to_colors()
+                .into_iter()
+                .map(|color| color == Color::Dark)
+                .collect(),
+        )),
+        Err(_) => None,
+    }
+}
@@ -0,0 +1,268 @@
+use crate::config::AppConfig;
+use crate::{
+    audit, elephant, mail, name_key, push, redraw_ticket, sanitize, Draft, Drafts, Organizer,
+};
+use chrono::{DateTime, Utc};
+use rocket::http::Status;
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub use api::reassign::{Ask, View};
+
+const MAX_REASON: usize = 500;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Pending,
+    Approved,
+    Denied,
+}
+
+// A member asking for another recipient, e.g. because they drew their ex.
+// The organizer decides on the reason alone: neither who asked nor whom
+// they drew is shown to them, approving redraws the ticket unseen.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Request {
+    id: u32,
+    // Name key of the member who asked.
+    giver: String,
+    reason: String,
+    created: DateTime<Utc>,
+    outcome: Outcome,
+}
+
+pub fn pending(draft: &Draft) -> Vec<View> {
+    draft
+        .reassign
+        .iter()
+        .filter(|request| request.outcome == Outcome::Pending)
+        .map(|request| View {
+            id: request.id,
+            reason: request.reason.clone(),
+            created: request.created,
+        })
+        .collect()
+}
+
+// Whether the ticket can be redrawn at all. Nobody can read the tickets in
+// the zero-knowledge mode, and a white elephant game has none.
+fn open(draft: &Draft) -> bool {
+    draft.is_drawn()
+        && !draft.is_archived()
+        && !draft.passphrases
+        && draft.mode != elephant::Mode::WhiteElephant
+}
+
+// The parts of the ticket page for requests, on the member's own link.
+pub fn context(draft: &Draft, key: &str, context: &mut HashMap<&str, String>) {
+    let member = match draft.member_by_token(key) {
+        Some(member) if open(draft) => member,
+        _ => return,
+    };
+    let giver = name_key(&member.name);
+    let outcome = draft
+        .reassign
+        .iter()
+        .find(|request| request.giver == giver)
+        .map(|request| request.outcome);
+    match outcome {
+        Some(Outcome::Pending) => {
+            context.insert("reassign_pending", String::new());
+        }
+        Some(Outcome::Denied) => {
+            context.insert("reassign_denied", String::new());
+            context.insert("reassign_key", key.to_string());
+        }
+        Some(Outcome::Approved) | None => {
+            context.insert("reassign_key", key.to_string());
+        }
+    };
+}
+
+// Files the request of the member with the token, replacing their decided
+// one. Only one can be pending at a time.
+fn ask(id: usize, key: &str, reason: &str, drafts: &Drafts) -> Result<(), Status> {
+    let reason = sanitize::text(reason);
+    if reason.is_empty() || reason.chars().count() > MAX_REASON {
+        return Err(Status::UnprocessableEntity);
+    }
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    let giver = draft
+        .member_by_token(key)
+        .map(|member| name_key(&member.name))
+        .ok_or(Status::NotFound)?;
+    if !open(&draft) {
+        return Err(Status::Conflict);
+    }
+    if draft
+        .reassign
+        .iter()
+        .any(|request| request.giver == giver && request.outcome == Outcome::Pending)
+    {
+        return Err(Status::Conflict);
+    }
+    draft.reassign.retain(|request| request.giver != giver);
+    let next = draft.reassign.iter().map(|request| request.id).max();
+    draft.reassign.push(Request {
+        id: next.map_or(1, |id| id + 1),
+        giver,
+        reason,
+        created: Utc::now(),
+        outcome: Outcome::Pending,
+    });
+    tracing::info!(draft = id, "reassignment requested");
+    Ok(())
+}
+
+#[post(
+    "/api/draft/<id>/ticket/<key>/reassign",
+    format = "json",
+    data = "<request>"
+)]
+pub fn api_ask(
+    id: usize,
+    key: String,
+    request: Json<Ask>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    ask(id, &key, &request.reason, &drafts)?;
+    Ok(Status::Accepted)
+}
+
+#[post("/draft/<id>/ticket/<key>/reassign", data = "<request>")]
+pub fn ask_reassign(
+    id: usize,
+    key: String,
+    request: Form<Ask>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    ask(id, &key, &request.reason, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}/ticket/{}", id, key)))
+}
+
+#[get("/api/draft/<id>/reassign")]
+pub fn api_list(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Json<Vec<View>>, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    Ok(Json(pending(&draft)))
+}
+
+// Approving redraws the ticket of the member who asked, which may change
+// a few other tickets too. Everyone with a new ticket is notified again.
+fn decide(
+    id: usize,
+    request: u32,
+    approve: bool,
+    organizer: &Organizer,
+    config: &AppConfig,
+    drafts: &Drafts,
+) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let _operation = entry.operation().ok_or(Status::Conflict)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    let index = draft
+        .reassign
+        .iter()
+        .position(|known| known.id == request && known.outcome == Outcome::Pending)
+        .ok_or(Status::NotFound)?;
+    if !approve {
+        draft.reassign[index].outcome = Outcome::Denied;
+        tracing::info!(draft = id, "reassignment denied");
+        return Ok(());
+    }
+    if !open(&draft) {
+        return Err(Status::Conflict);
+    }
+    let giver = draft
+        .find_member(&draft.reassign[index].giver)
+        .map(|member| member.name.clone())
+        .ok_or(Status::NotFound)?;
+    let changed = redraw_ticket(&mut draft, &giver)?;
+    draft.reassign[index].outcome = Outcome::Approved;
+    draft.record(
+        audit::Actor::Organizer,
+        audit::Action::Reassigned {
+            changed: changed.len(),
+        },
+    );
+    push::notify(
+        config,
+        push::drawn(&draft, id, config)
+            .into_iter()
+            .filter(|(target, _, _)| changed.contains(&target.member))
+            .collect(),
+    );
+    for member in draft
+        .members
+        .iter()
+        .filter(|member| changed.contains(&member.name))
+    {
+        mail::ticket(config, id, &draft, member);
+    }
+    tracing::info!(draft = id, changed = changed.len(), "reassignment approved");
+    Ok(())
+}
+
+#[post("/api/draft/<id>/reassign/<request>/approve")]
+pub fn api_approve(
+    id: usize,
+    request: u32,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    decide(id, request, true, &organizer, &config, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/api/draft/<id>/reassign/<request>/deny")]
+pub fn api_deny(
+    id: usize,
+    request: u32,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    decide(id, request, false, &organizer, &config, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/reassign/<request>/approve")]
+pub fn approve(
+    id: usize,
+    request: u32,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    decide(id, request, true, &organizer, &config, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+#[post("/draft/<id>/reassign/<request>/deny")]
+pub fn deny(
+    id: usize,
+    request: u32,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    decide(id, request, false, &organizer, &config, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
@@ -0,0 +1,57 @@
+use crate::config::AppConfig;
+use crate::{audit, avatar, series, Drafts};
+use chrono::{Duration, Utc};
+use std::thread;
+
+// Days before the expiry from which the draft page shows a warning.
+pub const WARNING_DAYS: i64 = 7;
+
+// Moves expired drafts to the trash and removes those that stayed in the
+// trash longer than the restore window. Returns how many were affected.
+pub fn sweep(drafts: &Drafts, config: &AppConfig) -> (usize, usize) {
+    let now = Utc::now();
+    let (mut trashed, mut purged) = (0, 0);
+    for (id, entry) in drafts.all() {
+        let mut draft = entry.write();
+        match draft.trashed {
+            Some(date) if date + Duration::days(config.trash_days) <= now => {
+                avatar::remove_all(&config.media_dir, &draft);
+                drafts.remove(id);
+                purged += 1;
+                tracing::info!(id, "trashed draft purged");
+            }
+            Some(_) => (),
+            None => {
+                if draft
+                    .expires(config.retention_days)
+                    .map_or(false, |date| date <= now)
+                {
+                    draft.trash(audit::Actor::System);
+                    trashed += 1;
+                    tracing::info!(id, "expired draft moved to the trash");
+                }
+            }
+        }
+    }
+    (trashed, purged)
+}
+
+pub fn spawn(drafts: Drafts, config: &AppConfig) {
+    let config = config.clone();
+    let interval = Duration::minutes(config.sweep_interval as i64)
+        .to_std()
+        .unwrap_or_else(|_| std::time::Duration::from_secs(3600));
+    let result = thread::Builder::new()
+        .name("retention".to_string())
+        .spawn(move || loop {
+            let (trashed, purged) = sweep(&drafts, &config);
+            if trashed > 0 || purged > 0 {
+                tracing::info!(trashed, purged, "retention sweep finished");
+            }
+            series::continue_all(&drafts, &config);
+            thread::sleep(interval);
+        });
+    if let Err(e) = result {
+        tracing::error!(error = %e, "could not start the retention sweep");
+    }
+}
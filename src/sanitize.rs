@@ -0,0 +1,33 @@
+use unicode_normalization::UnicodeNormalization;
+
+// Characters without a glyph that change how the text around them is
+// shown, e.g. reverse it or make two different names look the same.
+fn invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200b}'..='\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2060}'..='\u{2069}' | '\u{feff}'
+    )
+}
+
+// A single line entered by someone, e.g. a name or a title: in Unicode
+// NFC, without control and invisible characters and trimmed. The
+// templates escape HTML on top of that.
+pub fn line(value: &str) -> String {
+    value
+        .nfc()
+        .filter(|c| !c.is_control() && !invisible(*c))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+// Like `line`, but keeping the line breaks, e.g. of a comment.
+pub fn text(value: &str) -> String {
+    value
+        .replace("\r\n", "\n")
+        .nfc()
+        .filter(|c| (*c == '\n' || !c.is_control()) && !invisible(*c))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
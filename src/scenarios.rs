@@ -0,0 +1,54 @@
+// Pathological member sets the draw has to either solve or turn down right
+// away. The solver debug endpoints, `benches/matcher.rs` and the tests of
+// the matcher all run these.
+
+// Far beyond `max_members`, to see how the draw scales.
+pub const MAX_MEMBERS: usize = 20_000;
+
+pub struct Scenario {
+    pub name: &'static str,
+    // The team of every member.
+    pub teams: Vec<u32>,
+    // Whether a draw exists at all.
+    pub drawable: bool,
+}
+
+pub fn scenarios() -> Vec<Scenario> {
+    vec![
+        // Nobody to draw at all.
+        Scenario {
+            name: "single_member",
+            teams: vec![0],
+            drawable: false,
+        },
+        // Everyone in a team of their own, the most candidates possible.
+        Scenario {
+            name: "single_member_teams",
+            teams: (0..1000).collect(),
+            drawable: true,
+        },
+        // Everyone but one in the same team: all of them can only draw
+        // the one outside.
+        Scenario {
+            name: "excluded_member",
+            teams: (0..500).map(|index| (index > 0) as u32).collect(),
+            drawable: false,
+        },
+        Scenario {
+            name: "one_team",
+            teams: vec![0; 500],
+            drawable: false,
+        },
+        // Two teams of the same size, each has to draw all of the other.
+        Scenario {
+            name: "two_halves",
+            teams: (0..2000).map(|index| (index % 2) as u32).collect(),
+            drawable: true,
+        },
+        Scenario {
+            name: "thousands",
+            teams: (0..MAX_MEMBERS).map(|index| (index / 10) as u32).collect(),
+            drawable: true,
+        },
+    ]
+}
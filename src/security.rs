@@ -0,0 +1,62 @@
+use crate::config::AppConfig;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::{Request, Response};
+
+// The templates use inline scripts, styles and event handlers, avatars may
+// come from Gravatar and the draw ceremony connects to its own WebSocket
+// port.
+const CONTENT_SECURITY_POLICY: &str = "default-src 'self'; \
+     script-src 'self' 'unsafe-inline'; style-src 'self' 'unsafe-inline'; \
+     img-src 'self' data: https://www.gravatar.com; connect-src 'self' ws: wss:; \
+     worker-src 'self'; object-src 'none'; base-uri 'self'; form-action 'self'; \
+     frame-ancestors 'none'";
+const HSTS_MAX_AGE: u64 = 180 * 24 * 60 * 60;
+
+// Security headers for every response. With `strict_security` off, e.g.
+// during development over plain HTTP, HSTS is left out and the content
+// security policy only reports violations in the browser console.
+pub struct SecurityHeaders {
+    strict: bool,
+}
+
+impl SecurityHeaders {
+    pub fn new(config: &AppConfig) -> SecurityHeaders {
+        SecurityHeaders {
+            strict: config.strict_security,
+        }
+    }
+}
+
+impl Fairing for SecurityHeaders {
+    fn info(&self) -> Info {
+        Info {
+            name: "Security headers",
+            kind: Kind::Response,
+        }
+    }
+
+    fn on_response(&self, _: &Request, response: &mut Response) {
+        response.set_header(Header::new("X-Content-Type-Options", "nosniff"));
+        response.set_header(Header::new(
+            "Referrer-Policy",
+            "strict-origin-when-cross-origin",
+        ));
+        response.set_header(Header::new("X-Frame-Options", "DENY"));
+        if self.strict {
+            response.set_header(Header::new(
+                "Strict-Transport-Security",
+                format!("max-age={}; includeSubDomains", HSTS_MAX_AGE),
+            ));
+            response.set_header(Header::new(
+                "Content-Security-Policy",
+                CONTENT_SECURITY_POLICY,
+            ));
+        } else {
+            response.set_header(Header::new(
+                "Content-Security-Policy-Report-Only",
+                CONTENT_SECURITY_POLICY,
+            ));
+        }
+    }
+}
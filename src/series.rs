@@ -0,0 +1,402 @@
+use crate::config::AppConfig;
+use crate::i18n::{self, Locale};
+use crate::{
+    audit, generate_token, matcher, name_key, sanitize, Draft, DraftEntry, DraftError, Drafts,
+    Member, Organizer,
+};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use rocket::http::Status;
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use rocket_contrib::templates::Template;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub use api::series::{NeverDrawn, PairCount, SeriesRequest, Stats};
+
+// Past editions whose pairs weigh more than just having been drawn once.
+const HISTORY_EDITIONS: u32 = 3;
+// Random draws tried per pool for one without any weighed pair, before
+// solving for the lowest weight.
+const RANDOM_DRAWS: u32 = 10;
+const MAX_NAME_LENGTH: usize = 100;
+
+// Who gave to whom in an earlier edition.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Past {
+    pub edition: u32,
+    pub giver: String,
+    pub recipient: String,
+}
+
+// A draft repeating every `interval` months, e.g. the family Christmas.
+// All editions share the `id`. Once an edition is over, the next one is
+// created with the same members, teams and organizer.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Series {
+    pub id: String,
+    pub name: String,
+    pub interval: u32,
+    pub edition: u32,
+    // Whether the next edition was created already.
+    #[serde(default)]
+    pub continued: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<Past>,
+}
+
+impl Series {
+    // How much drawing each pair again is avoided: a pair of the last
+    // edition weighs `HISTORY_EDITIONS`, older ones less, but every past
+    // match counts at least once, so the pairs spread over the years.
+    pub fn weights(&self) -> HashMap<(String, String), u32> {
+        let mut weights = HashMap::new();
+        for past in &self.history {
+            let age = self.edition.saturating_sub(past.edition);
+            *weights
+                .entry((name_key(&past.giver), name_key(&past.recipient)))
+                .or_insert(0) += (HISTORY_EDITIONS + 1).saturating_sub(age).max(1);
+        }
+        weights
+    }
+}
+
+// Draws a pool with the lowest total weight of the pairs drawn, e.g. of
+// those a member had in a past edition. A random draw without any of
+// them is as light as it gets and keeps the draw uniform, so a few are
+// tried first; small pools may not allow one, then the lightest draw is
+// solved for, see `matcher::cheapest`.
+pub fn draw_weighted<F>(
+    weights: &HashMap<(String, String), u32>,
+    members: &[Member],
+    mut draw: F,
+) -> Result<Vec<Member>, DraftError>
+where
+    F: FnMut() -> Result<Vec<Member>, DraftError>,
+{
+    if weights.is_empty() {
+        return draw();
+    }
+    for _ in 0..RANDOM_DRAWS {
+        let drawn = draw()?;
+        let weighed = drawn.iter().any(|member| {
+            member.recipient().map_or(false, |recipient| {
+                weights.contains_key(&(name_key(&member.name), name_key(&recipient)))
+            })
+        });
+        if !weighed {
+            return Ok(drawn);
+        }
+    }
+    let indices = members
+        .iter()
+        .enumerate()
+        .map(|(index, member)| (name_key(&member.name), index))
+        .collect::<HashMap<String, usize>>();
+    let pairs = weights
+        .iter()
+        .filter_map(|((giver, recipient), weight)| {
+            Some(((*indices.get(giver)?, *indices.get(recipient)?), *weight))
+        })
+        .collect::<HashMap<(usize, usize), u32>>();
+    let teams = members
+        .iter()
+        .map(|member| member.team)
+        .collect::<Vec<u32>>();
+    let recipients = matcher::cheapest(
+        &teams,
+        |giver, recipient| pairs.get(&(giver, recipient)).copied().unwrap_or(0),
+        &mut rand::thread_rng(),
+    )
+    .ok_or(DraftError::NotEnoughPossibilities)?;
+    tracing::debug!("no draw without weighed pairs, took the lightest");
+    Ok(Draft::assign(members, recipients))
+}
+
+// The same local time `months` later, on the last day of the month if the
+// month is shorter.
+fn add_months(
+    date: DateTime<FixedOffset>,
+    timezone: Tz,
+    months: u32,
+) -> Option<DateTime<FixedOffset>> {
+    let local = date.with_timezone(&timezone).naive_local();
+    let total = local.year() * 12 + local.month0() as i32 + months as i32;
+    let (year, month) = (total / 12, total % 12 + 1);
+    let day = (1..=local.day())
+        .rev()
+        .find_map(|day| NaiveDate::from_ymd_opt(year, month, day))?;
+    let next = timezone
+        .from_local_datetime(&day.and_time(local.time()))
+        .earliest()?;
+    Some(next.with_timezone(&next.offset().fix()))
+}
+
+// The next edition of a past one, dated in the future.
+fn next_edition(draft: &Draft, series: &Series) -> Option<Draft> {
+    let mut date = draft.date;
+    while date <= Utc::now() {
+        date = add_months(date, draft.timezone, series.interval.max(1))?;
+    }
+    let mut history = series.history.clone();
+    history.extend(draft.members.iter().filter_map(|member| {
+        Some(Past {
+            edition: series.edition,
+            giver: member.name.clone(),
+            recipient: member.recipient()?,
+        })
+    }));
+    let edition = series.edition + 1;
+    let mut next = draft.cloned_for(date, draft.timezone);
+    next.organizer_token = draft.organizer_token.clone();
+    next.series = Some(Series {
+        id: series.id.clone(),
+        name: series.name.clone(),
+        interval: series.interval,
+        edition,
+        continued: false,
+        history,
+    });
+    Some(next)
+}
+
+// Creates the next edition of every series whose latest edition is over.
+// Runs with the retention sweep. Returns how many were created. Like any
+// other draft, the editions count against `max_drafts`: those that don't
+// fit are tried again with the next sweep.
+pub fn continue_all(drafts: &Drafts, config: &AppConfig) -> usize {
+    let mut created = 0;
+    for (id, entry) in drafts.active() {
+        let due = {
+            let draft = entry.read();
+            draft
+                .series
+                .as_ref()
+                .map_or(false, |series| !series.continued)
+                && draft.is_archived()
+        };
+        if !due {
+            continue;
+        }
+        // Counted before locking the draft, `active` reads every draft.
+        if config
+            .max_drafts
+            .map_or(false, |max| drafts.active().len() >= max)
+        {
+            tracing::warn!(id, "no room for the next edition of the series");
+            break;
+        }
+        let mut draft = entry.write();
+        let series = match &draft.series {
+            Some(series) if !series.continued && draft.is_archived() => series.clone(),
+            _ => continue,
+        };
+        match next_edition(&draft, &series) {
+            Some(mut next) => {
+                next.record(audit::Actor::System, audit::Action::Cloned { from: id });
+                if let Some(series) = draft.series.as_mut() {
+                    series.continued = true;
+                }
+                drop(draft);
+                let next_id = drafts.insert(next);
+                created += 1;
+                tracing::info!(id, next = next_id, "next edition of the series created");
+            }
+            None => tracing::warn!(id, "could not date the next edition of the series"),
+        }
+    }
+    created
+}
+
+fn start(
+    id: usize,
+    request: &SeriesRequest,
+    organizer: &Organizer,
+    drafts: &Drafts,
+) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    if draft.series.is_some() || draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    let name = sanitize::line(&request.name);
+    let interval = request.interval.unwrap_or(12);
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH || interval == 0 || interval > 120
+    {
+        return Err(Status::UnprocessableEntity);
+    }
+    draft.series = Some(Series {
+        id: generate_token(),
+        name,
+        interval,
+        edition: 1,
+        continued: false,
+        history: Vec::new(),
+    });
+    Ok(())
+}
+
+// Makes the draft the first edition of a new series.
+#[post("/api/draft/<id>/series", format = "json", data = "<request>")]
+pub fn api_start(
+    id: usize,
+    request: Json<SeriesRequest>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    start(id, &request, &organizer, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/series", data = "<request>")]
+pub fn start_series(
+    id: usize,
+    request: Form<SeriesRequest>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    start(id, &request, &organizer, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+#[derive(Serialize)]
+struct SeriesContext {
+    name: String,
+    drafts: Vec<DraftEntry>,
+}
+
+// All editions of a series, the latest first.
+#[get("/series/<series>")]
+pub fn show(series: String, locale: Locale, drafts: State<Drafts>) -> Option<Template> {
+    let mut editions = drafts
+        .active()
+        .iter()
+        .map(|(id, entry)| DraftEntry::new(*id, &entry.read()))
+        .filter(|entry| {
+            entry
+                .draft
+                .series
+                .as_ref()
+                .map_or(false, |s| s.id == series)
+        })
+        .collect::<Vec<DraftEntry>>();
+    if editions.is_empty() {
+        return None;
+    }
+    editions.sort_by(|a, b| b.draft.date.cmp(&a.draft.date));
+    let name = editions[0].draft.series.as_ref()?.name.clone();
+    Some(i18n::render(
+        "series",
+        locale,
+        SeriesContext {
+            name,
+            drafts: editions,
+        },
+    ))
+}
+
+fn stats(draft: &Draft, series: &Series) -> Stats {
+    let mut matches = series.history.clone();
+    matches.extend(draft.members.iter().filter_map(|member| {
+        Some(Past {
+            edition: series.edition,
+            giver: member.name.clone(),
+            recipient: member.recipient()?,
+        })
+    }));
+    let mut counts = BTreeMap::new();
+    for past in &matches {
+        *counts
+            .entry((past.giver.clone(), past.recipient.clone()))
+            .or_insert(0) += 1;
+    }
+    let drawn = counts
+        .keys()
+        .map(|(giver, recipient)| (name_key(giver), name_key(recipient)))
+        .collect::<HashSet<(String, String)>>();
+    let mut never = Vec::new();
+    let mut shares = Vec::new();
+    for giver in &draft.members {
+        let eligible = draft
+            .members
+            .iter()
+            .filter(|other| {
+                other.name != giver.name && other.team != giver.team && other.pool == giver.pool
+            })
+            .collect::<Vec<&Member>>();
+        let recipients = eligible
+            .iter()
+            .filter(|other| !drawn.contains(&(name_key(&giver.name), name_key(&other.name))))
+            .map(|other| other.name.clone())
+            .collect::<Vec<String>>();
+        let given = matches
+            .iter()
+            .filter(|past| name_key(&past.giver) == name_key(&giver.name))
+            .map(|past| name_key(&past.recipient))
+            .collect::<Vec<String>>();
+        let possible = given.len().min(eligible.len().max(1));
+        if possible > 0 {
+            let distinct = given.iter().collect::<HashSet<&String>>().len();
+            shares.push(distinct.min(possible) as f64 / possible as f64);
+        }
+        if !recipients.is_empty() {
+            never.push(NeverDrawn {
+                giver: giver.name.clone(),
+                recipients,
+            });
+        }
+    }
+    never.sort_by(|a, b| a.giver.cmp(&b.giver));
+    let mut pairs = counts
+        .into_iter()
+        .map(|((giver, recipient), count)| PairCount {
+            giver,
+            recipient,
+            count,
+        })
+        .collect::<Vec<PairCount>>();
+    pairs.sort_by(|a, b| b.count.cmp(&a.count));
+    let fairness = if shares.is_empty() {
+        1.0
+    } else {
+        shares.iter().sum::<f64>() / shares.len() as f64
+    };
+    Stats {
+        editions: series.edition,
+        pairs,
+        never,
+        fairness,
+    }
+}
+
+// Who drew whom over all editions of the series. It reveals the tickets,
+// so only the organizer gets it.
+#[get("/api/series/<series>/stats")]
+pub fn api_stats(
+    series: String,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Json<Stats>, Status> {
+    let latest = drafts
+        .active()
+        .into_iter()
+        .filter_map(|(_, entry)| {
+            let draft = entry.read();
+            let edition = draft.series.as_ref().filter(|s| s.id == series)?.edition;
+            Some((edition, draft.clone()))
+        })
+        .max_by_key(|(edition, _)| *edition)
+        .map(|(_, draft)| draft)
+        .ok_or(Status::NotFound)?;
+    if !organizer.owns(&latest) || latest.blind {
+        return Err(Status::Forbidden);
+    }
+    let series = latest.series.as_ref().ok_or(Status::NotFound)?;
+    Ok(Json(stats(&latest, series)))
+}
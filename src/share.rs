@@ -0,0 +1,111 @@
+use crate::i18n::{self, Locale};
+use crate::{generate_token, qr, Drafts, Organizer};
+use rocket::http::Status;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use rocket_contrib::templates::Template;
+use serde::Serialize;
+use std::collections::HashMap;
+
+pub use api::share::Shared;
+
+// The share link of the draft, only for its organizer.
+pub fn url(id: usize, token: Option<&str>, origin: &qr::Origin) -> Option<String> {
+    token.map(|token| origin.share_url(id, token))
+}
+
+// Gives the draft a new share link, so the previous one stops working, or
+// removes it.
+fn set(
+    id: usize,
+    enabled: bool,
+    organizer: &Organizer,
+    drafts: &Drafts,
+) -> Result<Option<String>, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    draft.share_token = if enabled {
+        Some(generate_token())
+    } else {
+        None
+    };
+    Ok(draft.share_token.clone())
+}
+
+#[post("/api/draft/<id>/share")]
+pub fn api_create(
+    id: usize,
+    organizer: Organizer,
+    origin: qr::Origin,
+    drafts: State<Drafts>,
+) -> Result<Json<Shared>, Status> {
+    let token = set(id, true, &organizer, &drafts)?.ok_or(Status::InternalServerError)?;
+    Ok(Json(Shared {
+        url: origin.share_url(id, &token),
+    }))
+}
+
+#[delete("/api/draft/<id>/share")]
+pub fn api_delete(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    set(id, false, &organizer, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/share")]
+pub fn create(id: usize, organizer: Organizer, drafts: State<Drafts>) -> Result<Redirect, Status> {
+    set(id, true, &organizer, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+#[post("/draft/<id>/share/delete")]
+pub fn delete(id: usize, organizer: Organizer, drafts: State<Drafts>) -> Result<Redirect, Status> {
+    set(id, false, &organizer, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+#[derive(Serialize)]
+struct ShareContext {
+    title: String,
+    date: String,
+    members: Vec<String>,
+    theme: Option<String>,
+}
+
+// What the draft looks like from outside: no assignments, tokens or
+// anything else only members get to see.
+#[get("/draft/<id>/share/<token>")]
+pub fn show(id: usize, token: String, locale: Locale, drafts: State<Drafts>) -> Template {
+    let context = drafts.get(id).and_then(|entry| {
+        let draft = entry.read();
+        if draft.share_token.as_deref() != Some(token.as_str()) {
+            return None;
+        }
+        let mut members = draft
+            .members
+            .iter()
+            .map(|member| member.name.clone())
+            .collect::<Vec<String>>();
+        members.sort();
+        Some(ShareContext {
+            title: draft.title.clone(),
+            date: locale.format_date(&draft.local_date(), true),
+            members,
+            theme: draft.theme.clone(),
+        })
+    });
+    match context {
+        Some(context) => i18n::render("draft_share", locale, context),
+        None => {
+            let context: HashMap<&str, &str> = HashMap::new();
+            i18n::render("draft_not_found", locale, context)
+        }
+    }
+}
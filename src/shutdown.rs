@@ -0,0 +1,95 @@
+use crate::{persistence, Drafts};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::uri::Origin;
+use rocket::http::{Method, Status};
+use rocket::{Data, Request};
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const UNAVAILABLE: &str = "/unavailable";
+
+#[derive(Default)]
+pub struct Lifecycle {
+    shutting_down: AtomicBool,
+    in_flight: AtomicUsize,
+}
+
+// Keeps the process from exiting while it is alive, used for requests and
+// any other work that has to finish before shutting down.
+pub struct Task(Arc<Lifecycle>);
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Lifecycle {
+    pub fn begin_task(self: &Arc<Self>) -> Task {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        Task(self.clone())
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    fn wait(&self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        while self.in_flight.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+pub struct LifecycleFairing(pub Arc<Lifecycle>);
+
+impl Fairing for LifecycleFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Lifecycle",
+            kind: Kind::Request,
+        }
+    }
+
+    // Rocket 0.4 cannot stop accepting connections, so once shutting down
+    // every new request is rerouted to a route answering 503. Otherwise the
+    // request holds a task, which is dropped with the request after the
+    // response has been written.
+    fn on_request(&self, request: &mut Request, _: &Data) {
+        if self.0.is_shutting_down() {
+            request.set_method(Method::Get);
+            request.set_uri(Origin::parse(UNAVAILABLE).unwrap());
+        } else {
+            request.local_cache(|| Some(self.0.begin_task()));
+        }
+    }
+}
+
+#[get("/unavailable")]
+pub fn unavailable() -> Status {
+    Status::ServiceUnavailable
+}
+
+pub fn install(lifecycle: Arc<Lifecycle>, drafts: Drafts, data_file: Option<String>) {
+    let result = ctrlc::set_handler(move || {
+        tracing::info!("shutting down, waiting for running requests");
+        lifecycle.shutting_down.store(true, Ordering::SeqCst);
+        lifecycle.wait(Duration::from_secs(10));
+        if let Some(path) = &data_file {
+            match persistence::save(path, drafts.snapshot()) {
+                Ok(()) => tracing::info!(path = path.as_str(), "saved drafts"),
+                Err(e) => {
+                    tracing::error!(path = path.as_str(), error = %e, "could not save drafts")
+                }
+            }
+        }
+        process::exit(0);
+    });
+    if let Err(e) = result {
+        tracing::error!(error = %e, "could not install shutdown handler");
+    }
+}
@@ -0,0 +1,119 @@
+use crate::config::AppConfig;
+use crate::{elephant, matcher, name_key, pool, Drafts, Organizer};
+use rocket::http::Status;
+use rocket::State;
+use rocket_contrib::json::Json;
+use std::collections::HashMap;
+
+pub use api::simulate::{Blocker, Simulation};
+
+const DEFAULT_RUNS: u32 = 1000;
+const MAX_RUNS: u32 = 10000;
+
+// Draws the draft `runs` times like the real draw would, without keeping
+// any of the tickets, to see how well its teams, pools and series
+// history go together.
+#[post("/api/draft/<id>/simulate?<runs>")]
+pub fn api_simulate(
+    id: usize,
+    runs: Option<u32>,
+    organizer: Organizer,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Json<Simulation>, Status> {
+    let runs = runs.unwrap_or(DEFAULT_RUNS);
+    if runs == 0 || runs > MAX_RUNS {
+        return Err(Status::UnprocessableEntity);
+    }
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read().clone();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    if draft.mode != elephant::Mode::SecretSanta || draft.members.len() < 2 {
+        return Err(Status::Conflict);
+    }
+    let weights = draft
+        .series
+        .as_ref()
+        .map(|series| series.weights())
+        .unwrap_or_default();
+    let mut blockers: HashMap<(&'static str, String), u32> = HashMap::new();
+    let pools = pool::group(&draft.members);
+    let mut infeasible = false;
+    for (pool, members) in &pools {
+        let teams = members
+            .iter()
+            .map(|member| member.team)
+            .collect::<Vec<u32>>();
+        if let Some(team) = matcher::infeasible_team(&teams) {
+            infeasible = true;
+            let subject = match pool {
+                Some(pool) => format!("{}: {}", pool, team),
+                None => team.to_string(),
+            };
+            *blockers.entry(("pool", subject)).or_insert(0) += runs;
+        }
+    }
+    let mut rng = rand::thread_rng();
+    let (mut succeeded, mut retries) = (0, 0);
+    for _ in 0..runs {
+        if infeasible {
+            break;
+        }
+        let mut success = true;
+        for members in pools.values() {
+            let teams = members
+                .iter()
+                .map(|member| member.team)
+                .collect::<Vec<u32>>();
+            let mut drawn = None;
+            for _ in 0..config.max_draw_attempts.max(1) {
+                match matcher::draw(&teams, &mut rng) {
+                    Ok(recipients) => {
+                        drawn = Some(recipients);
+                        break;
+                    }
+                    Err(stuck) => {
+                        retries += 1;
+                        let subject = members[stuck].team.to_string();
+                        *blockers.entry(("team", subject)).or_insert(0) += 1;
+                    }
+                }
+            }
+            let recipients = match drawn {
+                Some(recipients) => recipients,
+                None => {
+                    success = false;
+                    break;
+                }
+            };
+            for (giver, recipient) in recipients.iter().enumerate() {
+                let (giver, recipient) = (&members[giver].name, &members[*recipient].name);
+                if weights.contains_key(&(name_key(giver), name_key(recipient))) {
+                    let subject = format!("{} → {}", giver, recipient);
+                    *blockers.entry(("history", subject)).or_insert(0) += 1;
+                }
+            }
+        }
+        if success {
+            succeeded += 1;
+        }
+    }
+    let mut blocked_by = blockers
+        .into_iter()
+        .map(|((constraint, subject), count)| Blocker {
+            constraint: constraint.to_string(),
+            subject,
+            count,
+        })
+        .collect::<Vec<Blocker>>();
+    blocked_by.sort_by(|a, b| b.count.cmp(&a.count).then(a.subject.cmp(&b.subject)));
+    Ok(Json(Simulation {
+        runs,
+        succeeded,
+        success_rate: succeeded as f64 / runs as f64,
+        average_retries: retries as f64 / runs as f64,
+        blocked_by,
+    }))
+}
@@ -0,0 +1,108 @@
+use crate::config::AppConfig;
+use crate::scenarios::{self, MAX_MEMBERS};
+use crate::{Admin, Draft, DraftError, DrawStats, Member};
+use rocket::http::Status;
+use rocket::State;
+use rocket_contrib::json::Json;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+fn outcome(error: &DraftError) -> &'static str {
+    match error {
+        DraftError::NotEnoughPossibilities => "not_enough_possibilities",
+        DraftError::Infeasible { .. } => "infeasible",
+        _ => "other",
+    }
+}
+
+#[derive(Serialize)]
+pub struct Report {
+    scenario: &'static str,
+    members: usize,
+    // `drawn` or the `DraftError` the draw gave up with.
+    outcome: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(flatten)]
+    stats: DrawStats,
+    elapsed_ms: u128,
+}
+
+// Draws the members of the teams once, the way a pool of a draft is drawn.
+fn run(scenario: &'static str, teams: &[u32], max_attempts: u32) -> Report {
+    let members = teams
+        .iter()
+        .enumerate()
+        .map(|(index, team)| Member::new(format!("member {}", index), *team))
+        .collect::<Vec<Member>>();
+    let mut stats = DrawStats::default();
+    let start = Instant::now();
+    let result = Draft::draw_pool_counted(members, max_attempts, &mut stats);
+    let elapsed_ms = start.elapsed().as_millis();
+    Report {
+        scenario,
+        members: teams.len(),
+        outcome: result.as_ref().map_or_else(outcome, |_| "drawn"),
+        expected: None,
+        error: result.err().map(|e| e.to_string()),
+        stats,
+        elapsed_ms,
+    }
+}
+
+#[derive(Serialize)]
+pub struct Reports {
+    // Whether every scenario ended as expected.
+    passed: bool,
+    reports: Vec<Report>,
+}
+
+// Runs the pathological member sets against the matcher. A draw that
+// can't succeed has to be turned down before the first attempt, so
+// `attempts` stays 0 for those.
+#[get("/api/debug/solver")]
+pub fn api_scenarios(_admin: Admin, config: State<AppConfig>) -> Json<Reports> {
+    let reports = scenarios::scenarios()
+        .into_iter()
+        .map(|scenario| {
+            let mut report = run(scenario.name, &scenario.teams, config.max_draw_attempts);
+            report.expected = Some(if scenario.drawable {
+                "drawn"
+            } else {
+                "not_enough_possibilities"
+            });
+            report
+        })
+        .collect::<Vec<Report>>();
+    let passed = reports.iter().all(|report| {
+        Some(report.outcome) == report.expected
+            && (report.outcome == "drawn" || report.stats.attempts == 0)
+    });
+    if !passed {
+        tracing::warn!("solver scenarios failed");
+    }
+    Json(Reports { passed, reports })
+}
+
+#[derive(Deserialize)]
+pub struct Members {
+    // The team of every member.
+    teams: Vec<u32>,
+    max_attempts: Option<u32>,
+}
+
+// Draws a member set of one's own, e.g. from a test.
+#[post("/api/debug/solver", format = "json", data = "<members>")]
+pub fn api_draw(
+    members: Json<Members>,
+    _admin: Admin,
+    config: State<AppConfig>,
+) -> Result<Json<Report>, Status> {
+    if members.teams.is_empty() || members.teams.len() > MAX_MEMBERS {
+        return Err(Status::UnprocessableEntity);
+    }
+    let max_attempts = members.max_attempts.unwrap_or(config.max_draw_attempts);
+    Ok(Json(run("custom", &members.teams, max_attempts)))
+}
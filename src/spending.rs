@@ -0,0 +1,182 @@
+use crate::{Draft, Drafts, Member, Organizer};
+use rocket::http::Status;
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use std::collections::HashMap;
+
+pub use api::spending::{Figures, Setting, Spent, Summary};
+
+const MAX_AMOUNT: u32 = 100_000;
+// Fewer amounts than this would give away what single members spent.
+const MIN_RECORDED: usize = 3;
+
+// The budget of the gift the member gives. Without a readable ticket, as
+// in the zero-knowledge mode, the one of the draft.
+fn budget(draft: &Draft, giver: &Member) -> Option<u32> {
+    giver
+        .recipient()
+        .and_then(|name| draft.find_member(&name))
+        .and_then(|recipient| draft.budget_for(recipient))
+        .or(draft.budget)
+}
+
+pub fn summary(draft: &Draft) -> Summary {
+    let mut spent = draft
+        .members
+        .iter()
+        .filter_map(|member| Some((member.spent?, budget(draft, member))))
+        .collect::<Vec<(u32, Option<u32>)>>();
+    spent.sort_by_key(|(amount, _)| *amount);
+    let recorded = spent.len();
+    let figures = if recorded < MIN_RECORDED {
+        None
+    } else {
+        let amounts = spent
+            .iter()
+            .map(|(amount, _)| *amount)
+            .collect::<Vec<u32>>();
+        let total = amounts.iter().sum::<u32>();
+        Some(Figures {
+            total,
+            average: (total as f64 / recorded as f64).round() as u32,
+            median: amounts[recorded / 2],
+            lowest: amounts[0],
+            highest: amounts[recorded - 1],
+            over_budget: spent
+                .iter()
+                .filter(|(amount, budget)| budget.map_or(false, |budget| *amount > budget))
+                .count(),
+            under_budget: spent
+                .iter()
+                .filter(|(amount, budget)| budget.map_or(false, |budget| *amount * 2 < budget))
+                .count(),
+        })
+    };
+    Summary {
+        members: draft.members.len(),
+        recorded,
+        figures,
+    }
+}
+
+// The parts of the ticket page for the amount spent, on the member's own
+// link once drawn, if the organizer asks for it.
+pub fn context(draft: &Draft, key: &str, context: &mut HashMap<&str, String>) {
+    let member = match draft.member_by_token(key) {
+        Some(member) if draft.spending && draft.is_drawn() => member,
+        _ => return,
+    };
+    context.insert("spent_key", key.to_string());
+    if let Some(amount) = member.spent {
+        context.insert("spent", amount.to_string());
+    }
+}
+
+// Sets what the member with the token spent, none to remove it.
+fn record(id: usize, key: &str, amount: Option<u32>, drafts: &Drafts) -> Result<(), Status> {
+    if amount.map_or(false, |amount| amount > MAX_AMOUNT) {
+        return Err(Status::UnprocessableEntity);
+    }
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    let member = draft
+        .member_by_token(key)
+        .cloned()
+        .ok_or(Status::NotFound)?;
+    if !draft.spending || !draft.is_drawn() {
+        return Err(Status::Conflict);
+    }
+    let mut member = draft.members.take(&member).ok_or(Status::NotFound)?;
+    member.spent = amount;
+    draft.members.insert(member);
+    Ok(())
+}
+
+#[put(
+    "/api/draft/<id>/ticket/<key>/spent",
+    format = "json",
+    data = "<spent>"
+)]
+pub fn api_record(
+    id: usize,
+    key: String,
+    spent: Json<Spent>,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    record(id, &key, spent.amount, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/ticket/<key>/spent", data = "<spent>")]
+pub fn record_spent(
+    id: usize,
+    key: String,
+    spent: Form<Spent>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    record(id, &key, spent.amount, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}/ticket/{}", id, key)))
+}
+
+// Turning it off deletes the amounts recorded so far.
+fn enable(id: usize, enabled: bool, organizer: &Organizer, drafts: &Drafts) -> Result<(), Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    draft.spending = enabled;
+    if !enabled {
+        draft.members = draft
+            .members
+            .drain()
+            .map(|mut member| {
+                member.spent = None;
+                member
+            })
+            .collect();
+    }
+    Ok(())
+}
+
+#[put("/api/draft/<id>/spending", format = "json", data = "<setting>")]
+pub fn api_enable(
+    id: usize,
+    setting: Json<Setting>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    enable(id, setting.enabled, &organizer, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/spending", data = "<setting>")]
+pub fn set_spending(
+    id: usize,
+    setting: Form<Setting>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    enable(id, setting.enabled, &organizer, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
+
+// Only after the exchange, when all gifts are bought.
+#[get("/api/draft/<id>/spending")]
+pub fn api_summary(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Json<Summary>, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    if !draft.spending || !draft.is_archived() {
+        return Err(Status::Conflict);
+    }
+    Ok(Json(summary(&draft)))
+}
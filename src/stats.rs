@@ -0,0 +1,32 @@
+use crate::store::DraftStore;
+use crate::Drafts;
+use chrono::{Datelike, Utc};
+use rocket::State;
+use rocket_contrib::json::Json;
+
+pub use api::stats::Stats;
+
+pub fn collect(drafts: &DraftStore) -> Stats {
+    let now = Utc::now();
+    let mut stats = Stats::default();
+    for (_, entry) in drafts.active() {
+        let draft = entry.read();
+        stats.drafts += 1;
+        stats.members += draft.members.len();
+        if draft.is_drawn() {
+            stats.drawn += 1;
+        } else {
+            stats.open += 1;
+        }
+        let date = draft.local_date();
+        if draft.is_upcoming() && date.year() == now.year() && date.month() == now.month() {
+            stats.upcoming_this_month += 1;
+        }
+    }
+    stats
+}
+
+#[get("/api/stats")]
+pub fn api_stats(drafts: State<Drafts>) -> Json<Stats> {
+    Json(collect(&drafts))
+}
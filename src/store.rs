@@ -0,0 +1,322 @@
+use crate::Draft;
+use rocket::http::uri::{FromUriParam, Path, UriDisplay};
+use rocket::http::RawStr;
+use rocket::request::FromParam;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{
+    Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError,
+};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+pub type DraftId = usize;
+
+// A draft as named in a URL, by its id or its slug.
+pub enum Key {
+    Id(DraftId),
+    Slug(String),
+}
+
+impl<'a> FromParam<'a> for Key {
+    type Error = &'a RawStr;
+
+    fn from_param(param: &'a RawStr) -> Result<Key, Self::Error> {
+        match param.parse::<DraftId>() {
+            Ok(id) => Ok(Key::Id(id)),
+            Err(_) => param.url_decode().map(Key::Slug).map_err(|_| param),
+        }
+    }
+}
+
+impl UriDisplay<Path> for Key {
+    fn fmt(&self, f: &mut rocket::http::uri::Formatter<Path>) -> fmt::Result {
+        match self {
+            Key::Id(id) => f.write_value(id),
+            Key::Slug(slug) => f.write_value(slug),
+        }
+    }
+}
+
+impl FromUriParam<Path, DraftId> for Key {
+    type Target = DraftId;
+
+    fn from_uri_param(id: DraftId) -> DraftId {
+        id
+    }
+}
+
+// Lowercase ASCII words of the title joined by dashes, umlauts spelled
+// out and other accents dropped. Titles without any letters or only
+// digits, which would read as an id, get no slug.
+pub fn slugify(title: &str) -> Option<String> {
+    let mut slug = String::new();
+    for c in title.nfc().flat_map(char::to_lowercase) {
+        let spelled = match c {
+            'ä' => "ae",
+            'ö' => "oe",
+            'ü' => "ue",
+            'ß' => "ss",
+            c => {
+                // The letter without its accents, e.g. `e` of `é`.
+                let mut base = Some(c).into_iter().nfd().filter(|c| !is_combining_mark(*c));
+                match (base.next(), base.next()) {
+                    (Some(c), None) if c.is_ascii_alphanumeric() => {
+                        slug.push(c);
+                        continue;
+                    }
+                    _ => "-",
+                }
+            }
+        };
+        if spelled != "-" || !slug.ends_with('-') {
+            slug.push_str(spelled);
+        }
+    }
+    let slug = slug.trim_matches('-').to_string();
+    if slug.is_empty() || slug.parse::<DraftId>().is_ok() {
+        None
+    } else {
+        Some(slug)
+    }
+}
+
+// A single draft with its own lock. A handler panicking while holding it
+// leaves the draft as it was at that point instead of locking everyone
+// out, so poisoning is ignored.
+#[derive(Clone)]
+pub struct Entry(Arc<RwLock<Draft>>, Arc<Mutex<()>>);
+
+impl Entry {
+    fn new(draft: Draft) -> Entry {
+        Entry(Arc::new(RwLock::new(draft)), Arc::new(Mutex::new(())))
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, Draft> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, Draft> {
+        self.0.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Held by the organizer's operations on the assignments like draws, so
+    // a second click while one runs is turned away instead of waiting for
+    // the lock and acting on the outcome of the first. `None` while another
+    // one runs.
+    pub fn operation(&self) -> Option<MutexGuard<'_, ()>> {
+        match self.1.try_lock() {
+            Ok(guard) => Some(guard),
+            Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+}
+
+// The first of `slug`, `slug-2`, `slug-3` and so on no draft has yet.
+fn free_slug(slugs: &HashMap<String, DraftId>, slug: String) -> String {
+    (1..)
+        .map(|n| match n {
+            1 => slug.clone(),
+            n => format!("{}-{}", slug, n),
+        })
+        .find(|candidate| !slugs.contains_key(candidate))
+        .unwrap()
+}
+
+// The drafts and which of them has which slug. Slugs never change once a
+// draft is in, so they are looked up here without locking any draft.
+#[derive(Default)]
+struct Index {
+    entries: HashMap<DraftId, Entry>,
+    slugs: HashMap<String, DraftId>,
+}
+
+// All drafts by id. The map is only locked to look up, add or remove
+// drafts, so requests to different drafts don't wait for each other.
+#[derive(Default)]
+pub struct DraftStore {
+    index: RwLock<Index>,
+    next_id: AtomicUsize,
+}
+
+impl DraftStore {
+    // The drafts get their position as id, `None` marking deleted ones.
+    pub fn new(drafts: Vec<Option<Draft>>) -> DraftStore {
+        let store = DraftStore::default();
+        store.replace(drafts);
+        store
+    }
+
+    fn index(&self) -> RwLockReadGuard<'_, Index> {
+        self.index.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn index_mut(&self) -> RwLockWriteGuard<'_, Index> {
+        self.index.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Gives the draft a slug from its title, numbered if another draft has
+    // it already.
+    pub fn insert(&self, mut draft: Draft) -> DraftId {
+        let mut index = self.index_mut();
+        let slug = draft.slug.take().or_else(|| slugify(&draft.title));
+        draft.slug = slug.map(|slug| free_slug(&index.slugs, slug));
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        if let Some(slug) = &draft.slug {
+            index.slugs.insert(slug.clone(), id);
+        }
+        index.entries.insert(id, Entry::new(draft));
+        id
+    }
+
+    // The draft with the given id, unless it is in the trash.
+    pub fn get(&self, id: DraftId) -> Option<Entry> {
+        self.get_any(id)
+            .filter(|entry| entry.read().trashed.is_none())
+    }
+
+    // The draft with the given id, even if it is in the trash.
+    pub fn get_any(&self, id: DraftId) -> Option<Entry> {
+        self.index().entries.get(&id).cloned()
+    }
+
+    pub fn resolve(&self, key: &Key) -> Option<DraftId> {
+        match key {
+            Key::Id(id) => Some(*id),
+            Key::Slug(slug) => self.index().slugs.get(slug).copied(),
+        }
+    }
+
+    pub fn remove(&self, id: DraftId) -> bool {
+        let mut index = self.index_mut();
+        index.slugs.retain(|_, slug_id| *slug_id != id);
+        index.entries.remove(&id).is_some()
+    }
+
+    // All drafts including the trashed ones, ordered by id.
+    pub fn all(&self) -> Vec<(DraftId, Entry)> {
+        let mut entries = self
+            .index()
+            .entries
+            .iter()
+            .map(|(id, entry)| (*id, entry.clone()))
+            .collect::<Vec<(DraftId, Entry)>>();
+        entries.sort_by_key(|(id, _)| *id);
+        entries
+    }
+
+    // All drafts not in the trash, ordered by id.
+    pub fn active(&self) -> Vec<(DraftId, Entry)> {
+        self.all()
+            .into_iter()
+            .filter(|(_, entry)| entry.read().trashed.is_none())
+            .collect()
+    }
+
+    // Copy of all drafts at their id's position, as stored in backups.
+    pub fn snapshot(&self) -> Vec<Option<Draft>> {
+        let mut drafts = vec![None; self.next_id.load(Ordering::SeqCst)];
+        for (id, entry) in self.all() {
+            if let Some(slot) = drafts.get_mut(id) {
+                *slot = Some(entry.read().clone());
+            }
+        }
+        drafts
+    }
+
+    // A slug given twice, e.g. by an edited backup, stays with the first
+    // draft and the later ones are numbered.
+    pub fn replace(&self, drafts: Vec<Option<Draft>>) {
+        let mut index = self.index_mut();
+        self.next_id.store(drafts.len(), Ordering::SeqCst);
+        *index = Index::default();
+        for (id, draft) in drafts.into_iter().enumerate() {
+            if let Some(mut draft) = draft {
+                if let Some(slug) = draft.slug.take() {
+                    let slug = free_slug(&index.slugs, slug);
+                    index.slugs.insert(slug.clone(), id);
+                    draft.slug = Some(slug);
+                }
+                index.entries.insert(id, Entry::new(draft));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn draft(title: &str) -> Draft {
+        serde_json::from_value(json!({
+            "title": title,
+            "date": "2021-12-24T18:00:00+01:00",
+            "timezone": "Europe/Berlin",
+            "members": [],
+        }))
+        .unwrap()
+    }
+
+    fn slug(store: &DraftStore, id: DraftId) -> Option<String> {
+        store.get(id).unwrap().read().slug.clone()
+    }
+
+    #[test]
+    fn slugifies_titles() {
+        assert_eq!(slugify("Wichteln 2021").as_deref(), Some("wichteln-2021"));
+        assert_eq!(slugify("  Team -- Büro!  ").as_deref(), Some("team-buero"));
+        assert_eq!(slugify("Große Straße").as_deref(), Some("grosse-strasse"));
+        assert_eq!(slugify("2021"), None);
+        assert_eq!(slugify("!?"), None);
+    }
+
+    #[test]
+    fn slugifies_non_ascii_titles() {
+        assert_eq!(slugify("Noël à Zoë").as_deref(), Some("noel-a-zoe"));
+        // Decomposed umlauts are spelled out like composed ones.
+        assert_eq!(slugify("Fu\u{308}r").as_deref(), Some("fuer"));
+        assert_eq!(slugify("Crème brûlée 🎄").as_deref(), Some("creme-brulee"));
+        assert_eq!(slugify("Рождество"), None);
+    }
+
+    #[test]
+    fn numbers_taken_slugs() {
+        let store = DraftStore::default();
+        let first = store.insert(draft("Wichteln"));
+        let second = store.insert(draft("Wichteln!"));
+        let third = store.insert(draft("wichteln"));
+        assert_eq!(slug(&store, first).as_deref(), Some("wichteln"));
+        assert_eq!(slug(&store, second).as_deref(), Some("wichteln-2"));
+        assert_eq!(slug(&store, third).as_deref(), Some("wichteln-3"));
+        assert!(store.remove(second));
+        let fourth = store.insert(draft("Wichteln"));
+        assert_eq!(slug(&store, fourth).as_deref(), Some("wichteln-2"));
+    }
+
+    #[test]
+    fn numbers_slugs_given_twice_in_a_backup() {
+        let mut drafts = vec![Some(draft("Wichteln")), None, Some(draft("Wichteln"))];
+        for draft in drafts.iter_mut().flatten() {
+            draft.slug = Some("wichteln".to_string());
+        }
+        let store = DraftStore::new(drafts);
+        assert_eq!(slug(&store, 0).as_deref(), Some("wichteln"));
+        assert_eq!(slug(&store, 2).as_deref(), Some("wichteln-2"));
+        assert_eq!(store.insert(draft("Neu")), 3);
+    }
+
+    #[test]
+    fn resolves_ids_and_slugs() {
+        let store = DraftStore::default();
+        let id = store.insert(draft("Wichteln im Büro"));
+        assert_eq!(store.resolve(&Key::Id(id)), Some(id));
+        let slug = Key::Slug("wichteln-im-buero".to_string());
+        assert_eq!(store.resolve(&slug), Some(id));
+        assert_eq!(store.resolve(&Key::Slug("unknown".to_string())), None);
+        store.remove(id);
+        assert_eq!(store.resolve(&slug), None);
+    }
+}
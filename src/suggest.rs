@@ -0,0 +1,47 @@
+use crate::{name_key, Draft, Drafts, Organizer};
+use rocket::State;
+use rocket_contrib::json::Json;
+use std::collections::HashSet;
+
+pub use api::suggest::Suggestion;
+
+// Suggestions returned at most for one query.
+const LIMIT: usize = 10;
+
+// Members of the organizer's earlier drafts whose name contains `q`, those
+// starting with it first. A name used in several drafts is suggested once,
+// with the team and email of its latest draft.
+#[get("/api/members/suggest?<q>")]
+pub fn api_suggest(
+    q: String,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Json<Vec<Suggestion>> {
+    let query = name_key(&q);
+    let mut owned = drafts
+        .active()
+        .into_iter()
+        .map(|(_, entry)| entry.read().clone())
+        .filter(|draft| organizer.owns(draft))
+        .collect::<Vec<Draft>>();
+    owned.sort_by(|a, b| b.date.cmp(&a.date));
+    let mut seen = HashSet::new();
+    let mut suggestions = Vec::new();
+    for draft in &owned {
+        let mut members = draft.members.iter().collect::<Vec<_>>();
+        members.sort_by(|a, b| a.name.cmp(&b.name));
+        for member in members {
+            let key = name_key(&member.name);
+            if key.contains(&query) && seen.insert(key) {
+                suggestions.push(Suggestion {
+                    name: member.name.clone(),
+                    team: member.team,
+                    email: member.email.clone(),
+                });
+            }
+        }
+    }
+    suggestions.sort_by_key(|suggestion| !name_key(&suggestion.name).starts_with(&query));
+    suggestions.truncate(LIMIT);
+    Json(suggestions)
+}
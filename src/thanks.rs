@@ -0,0 +1,151 @@
+use crate::config::AppConfig;
+use crate::i18n::Locale;
+use crate::outbox::{self, Page};
+use crate::{mail, qr, sanitize, Draft, Drafts, Member};
+use chrono::{DateTime, Utc};
+use rocket::http::Status;
+use rocket::request::Form;
+use rocket::response::Redirect;
+use rocket::State;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const MAX_MESSAGE: usize = 1000;
+
+// A thank-you note a recipient wrote to their santa after the exchange.
+// Only the santa sees it, and the recipient only learns who that is if
+// the santa signs it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Note {
+    pub message: String,
+    pub time: DateTime<Utc>,
+    // The santa's name, once they signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub santa: Option<String>,
+}
+
+// Who drew the member. Unknown in the zero-knowledge mode, as the server
+// can't read the tickets.
+fn santa<'a>(draft: &'a Draft, member: &Member) -> Option<&'a Member> {
+    draft
+        .members
+        .iter()
+        .find(|giver| giver.recipient().as_deref() == Some(member.name.as_str()))
+}
+
+fn recipient<'a>(draft: &'a Draft, member: &Member) -> Option<&'a Member> {
+    draft.find_member(&member.recipient()?)
+}
+
+// The parts of the ticket page for notes, on the member's own link after
+// the exchange.
+pub fn context(draft: &Draft, key: &str, context: &mut HashMap<&str, String>) {
+    let member = match draft.member_by_token(key) {
+        Some(member) if draft.is_archived() && !draft.passphrases => member,
+        _ => return,
+    };
+    if santa(draft, member).is_some() {
+        context.insert("thanks_key", key.to_string());
+    }
+    if let Some(note) = &member.thanks {
+        context.insert("thanks_sent", note.message.clone());
+        if let Some(santa) = &note.santa {
+            context.insert("santa", santa.clone());
+        }
+    }
+    if let Some(note) = recipient(draft, member).and_then(|r| r.thanks.as_ref()) {
+        context.insert("thanks_received", note.message.clone());
+        match note.santa {
+            Some(_) => context.insert("thanks_signed", String::new()),
+            None => context.insert("sign_key", key.to_string()),
+        };
+    }
+}
+
+#[derive(FromForm)]
+pub struct NewNote {
+    message: String,
+}
+
+#[post("/draft/<id>/ticket/<key>/thanks", data = "<note>")]
+pub fn write(
+    id: usize,
+    key: String,
+    note: Form<NewNote>,
+    locale: Locale,
+    origin: qr::Origin,
+    config: State<AppConfig>,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    let message = sanitize::text(&note.message);
+    if message.is_empty() || message.chars().count() > MAX_MESSAGE {
+        return Err(Status::UnprocessableEntity);
+    }
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    let member = draft
+        .member_by_token(&key)
+        .cloned()
+        .ok_or(Status::NotFound)?;
+    if !draft.is_archived() || draft.passphrases {
+        return Err(Status::Conflict);
+    }
+    let giver = santa(&draft, &member).cloned().ok_or(Status::Conflict)?;
+    let mut member = draft.members.take(&member).ok_or(Status::NotFound)?;
+    // Rewriting the note keeps the signature.
+    let signed = member.thanks.take().and_then(|note| note.santa);
+    member.thanks = Some(Note {
+        message: message.clone(),
+        time: Utc::now(),
+        santa: signed,
+    });
+    draft.members.insert(member);
+    if let Some(email) = giver
+        .email
+        .as_ref()
+        .filter(|email| !email.trim().is_empty())
+    {
+        let locale = giver.locale(locale);
+        let message = mail::Message {
+            subject: locale
+                .text("mail_thanks_subject")
+                .replace("{title}", &draft.title),
+            body: locale
+                .text("mail_thanks_body")
+                .replace("{name}", &giver.name)
+                .replace("{message}", &message)
+                .replace("{url}", outbox::LINK),
+        };
+        let link = origin.link(id, &giver, Page::Ticket);
+        mail::send_with(
+            &config,
+            email.trim().to_string(),
+            message,
+            None,
+            None,
+            Some(link),
+        );
+    }
+    Ok(Redirect::to(format!("/draft/{}/ticket/{}", id, key)))
+}
+
+// The santa reveals themselves to the recipient who thanked them.
+#[post("/draft/<id>/ticket/<key>/thanks/sign")]
+pub fn sign(id: usize, key: String, drafts: State<Drafts>) -> Result<Redirect, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    let giver = draft
+        .member_by_token(&key)
+        .cloned()
+        .ok_or(Status::NotFound)?;
+    let recipient = recipient(&draft, &giver)
+        .filter(|recipient| recipient.thanks.is_some())
+        .cloned()
+        .ok_or(Status::Conflict)?;
+    let mut recipient = draft.members.take(&recipient).ok_or(Status::NotFound)?;
+    if let Some(note) = recipient.thanks.as_mut() {
+        note.santa = Some(giver.name.clone());
+    }
+    draft.members.insert(recipient);
+    Ok(Redirect::to(format!("/draft/{}/ticket/{}", id, key)))
+}
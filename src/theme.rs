@@ -0,0 +1,181 @@
+use crate::i18n::Locale;
+use crate::{Draft, Drafts, Organizer};
+use rocket::http::{ContentType, Status};
+use rocket::request::Form;
+use rocket::response::content::Content;
+use rocket::response::Redirect;
+use rocket::State;
+use rocket_contrib::json::Json;
+use serde::Serialize;
+
+pub use api::theme::ThemeRequest;
+
+// A look for the pages of a draft. The stylesheet is generated from the
+// colors, so a theme is a single entry here.
+#[derive(Debug)]
+pub struct Theme {
+    pub id: &'static str,
+    // Christmas imagery, as opposed to a look fitting any season.
+    pub festive: bool,
+    primary: &'static str,
+    light: &'static str,
+    // Behind the pages, over the banner image if there is one.
+    background: &'static str,
+    banner: Option<&'static str>,
+}
+
+pub const THEMES: &[Theme] = &[
+    Theme {
+        id: "christmas",
+        festive: true,
+        primary: "#f5708b",
+        light: "#feecf0",
+        background: "#2b1d24",
+        banner: Some("/img/background.jpg"),
+    },
+    Theme {
+        id: "winter",
+        festive: true,
+        primary: "#3e8ed0",
+        light: "#eff5fb",
+        background: "linear-gradient(160deg, #0f2c4a, #3b6f99)",
+        banner: None,
+    },
+    Theme {
+        id: "neutral",
+        festive: false,
+        primary: "#485fc7",
+        light: "#eff1fa",
+        background: "linear-gradient(160deg, #1f2937, #374151)",
+        banner: None,
+    },
+    Theme {
+        id: "corporate",
+        festive: false,
+        primary: "#00947e",
+        light: "#ebfffc",
+        background: "#263238",
+        banner: None,
+    },
+];
+
+// Drafts without a theme keep the original look.
+pub const DEFAULT: &str = "christmas";
+
+pub fn find(id: &str) -> Option<&'static Theme> {
+    THEMES.iter().find(|theme| theme.id == id)
+}
+
+pub fn of(draft: &Draft) -> &'static Theme {
+    draft
+        .theme
+        .as_deref()
+        .and_then(find)
+        .or_else(|| find(DEFAULT))
+        .expect("default theme")
+}
+
+// A theme as the organizer picks it, with its name in their language.
+#[derive(Serialize)]
+pub struct Choice {
+    id: &'static str,
+    name: &'static str,
+}
+
+pub fn choices(locale: Locale) -> Vec<Choice> {
+    THEMES
+        .iter()
+        .map(|theme| Choice {
+            id: theme.id,
+            name: locale.text(&format!("theme_{}", theme.id)),
+        })
+        .collect()
+}
+
+impl Theme {
+    // The theme as the API lists it.
+    fn api(&self) -> api::theme::Theme {
+        api::theme::Theme {
+            id: self.id.to_string(),
+            festive: self.festive,
+            primary: self.primary.to_string(),
+            light: self.light.to_string(),
+            background: self.background.to_string(),
+            banner: self.banner.map(str::to_string),
+        }
+    }
+
+    fn stylesheet(&self) -> String {
+        let background = match self.banner {
+            Some(banner) => format!(
+                "url('{}') center / cover no-repeat, {}",
+                banner, self.background
+            ),
+            None => self.background.to_string(),
+        };
+        format!(
+            "body {{ background: {background} !important; background-attachment: fixed !important; }}\n\
+             .navbar.is-primary, .button.is-primary, .tag.is-primary {{ background-color: {primary}; color: #fff; }}\n\
+             .button.is-primary.is-light {{ background-color: {light}; color: {primary}; }}\n\
+             .button.is-primary:hover, .button.is-primary.is-light:hover {{ filter: brightness(0.95); }}\n\
+             .navbar.is-primary .navbar-item:hover {{ background-color: rgba(0, 0, 0, 0.1); }}\n",
+            background = background,
+            primary = self.primary,
+            light = self.light,
+        )
+    }
+}
+
+#[get("/api/themes")]
+pub fn api_themes() -> Json<Vec<api::theme::Theme>> {
+    Json(THEMES.iter().map(Theme::api).collect())
+}
+
+// The stylesheet of a theme, e.g. `/theme/neutral.css`.
+#[get("/theme/<file>")]
+pub fn stylesheet(file: String) -> Option<Content<String>> {
+    let theme = find(file.strip_suffix(".css")?)?;
+    Some(Content(ContentType::CSS, theme.stylesheet()))
+}
+
+// Sets the theme of the draft, none for the default one.
+fn set(
+    id: usize,
+    theme: Option<&str>,
+    organizer: &Organizer,
+    drafts: &Drafts,
+) -> Result<(), Status> {
+    let theme = match theme.map(str::trim).filter(|theme| !theme.is_empty()) {
+        Some(theme) => Some(find(theme).ok_or(Status::UnprocessableEntity)?.id),
+        None => None,
+    };
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let mut draft = entry.write();
+    if !organizer.owns(&draft) {
+        return Err(Status::Forbidden);
+    }
+    draft.theme = theme.map(str::to_string);
+    Ok(())
+}
+
+#[put("/api/draft/<id>/theme", format = "json", data = "<request>")]
+pub fn api_set(
+    id: usize,
+    request: Json<ThemeRequest>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Status, Status> {
+    set(id, request.theme.as_deref(), &organizer, &drafts)?;
+    Ok(Status::NoContent)
+}
+
+#[post("/draft/<id>/theme", data = "<request>")]
+pub fn set_theme(
+    id: usize,
+    request: Form<ThemeRequest>,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Redirect, Status> {
+    set(id, request.theme.as_deref(), &organizer, &drafts)?;
+    Ok(Redirect::to(format!("/draft/{}", id)))
+}
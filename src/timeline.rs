@@ -0,0 +1,96 @@
+use crate::audit::{self, Action};
+use crate::i18n::Locale;
+use crate::Draft;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+
+#[derive(Serialize)]
+pub struct Item {
+    // In the draft's timezone.
+    time: String,
+    // Groups similar events for styling, e.g. "draw" or "ticket".
+    kind: &'static str,
+    text: String,
+}
+
+#[derive(Serialize)]
+pub struct Day {
+    date: String,
+    items: Vec<Item>,
+}
+
+// How far along the draft is, shown above the timeline.
+#[derive(Serialize)]
+pub struct Progress {
+    members: usize,
+    drawn: bool,
+    // Members who looked at their ticket at least once.
+    viewed: usize,
+    // Members who confirmed they got their gift.
+    received: usize,
+    comments: usize,
+}
+
+pub fn progress(draft: &Draft) -> Progress {
+    let viewed = draft
+        .audit
+        .iter()
+        .filter_map(|entry| match &entry.action {
+            Action::TicketViewed { name } => Some(name),
+            _ => None,
+        })
+        .collect::<HashSet<&String>>();
+    Progress {
+        members: draft.members.len(),
+        drawn: draft.is_drawn(),
+        viewed: viewed.len(),
+        received: draft
+            .members
+            .iter()
+            .filter(|member| member.received.is_some())
+            .count(),
+        comments: draft.comments.len(),
+    }
+}
+
+// The audit log and the comments of a draft, newest first and grouped by
+// day.
+pub fn build(draft: &Draft, locale: Locale) -> Vec<Day> {
+    let mut events = draft
+        .audit
+        .iter()
+        .map(|entry| {
+            (
+                entry.time,
+                entry.action.kind(),
+                audit::describe(entry, locale),
+            )
+        })
+        .collect::<Vec<(DateTime<Utc>, &'static str, String)>>();
+    events.extend(draft.comments.iter().map(|comment| {
+        let text = locale
+            .text("timeline_comment")
+            .replace("{name}", &comment.name);
+        (comment.time, "comment", text)
+    }));
+    events.sort_by(|a, b| b.0.cmp(&a.0));
+    let mut days: Vec<Day> = Vec::new();
+    for (time, kind, text) in events {
+        let local = time.with_timezone(&draft.timezone);
+        let date = locale.format_date(&local, false);
+        let item = Item {
+            time: local.format("%H:%M").to_string(),
+            kind,
+            text,
+        };
+        match days.last_mut() {
+            Some(day) if day.date == date => day.items.push(item),
+            _ => days.push(Day {
+                date,
+                items: vec![item],
+            }),
+        }
+    }
+    days
+}
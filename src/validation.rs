@@ -0,0 +1,97 @@
+use crate::config::AppConfig;
+use crate::i18n::Locale;
+use crate::{elephant, mail, pool};
+use crate::{matcher, Draft, DraftError};
+use chrono::Utc;
+use std::collections::{BTreeMap, HashSet};
+
+pub const MAX_NAME_LENGTH: usize = 100;
+
+// Every rule the draft breaks, in the order of the form fields.
+pub fn violations(draft: &Draft, config: &AppConfig) -> Vec<DraftError> {
+    let mut violations = Vec::new();
+    let title = draft.title.trim().chars().count();
+    if title == 0 {
+        violations.push(DraftError::TitleMissing);
+    } else if title > config.max_title_length {
+        violations.push(DraftError::TitleTooLong {
+            max: config.max_title_length,
+        });
+    }
+    if draft.date <= Utc::now() {
+        violations.push(DraftError::DateInPast);
+    }
+    if draft
+        .members
+        .iter()
+        .map(|member| member.name.as_str())
+        .chain(draft.pools.iter().map(|pool| pool.name.as_str()))
+        .any(|name| name.chars().count() > MAX_NAME_LENGTH)
+    {
+        violations.push(DraftError::NameTooLong {
+            max: MAX_NAME_LENGTH,
+        });
+    }
+    if draft.members.len() > config.max_members {
+        violations.push(DraftError::TooManyMembers {
+            max: config.max_members,
+        });
+    }
+    // A draft without members yet is filled later by an import. Teams
+    // don't matter for a white elephant game, otherwise every pool is
+    // checked on its own as it is drawn on its own.
+    if draft.mode == elephant::Mode::SecretSanta {
+        for (_, members) in pool::group(&draft.members) {
+            let teams = members
+                .iter()
+                .map(|member| member.team)
+                .collect::<Vec<u32>>();
+            let distinct = teams.iter().collect::<HashSet<&u32>>().len();
+            if distinct < config.min_teams {
+                violations.push(DraftError::TooFewTeams {
+                    min: config.min_teams,
+                });
+            } else if let Some(team) = matcher::infeasible_team(&teams) {
+                violations.push(DraftError::TeamTooLarge { team });
+            }
+        }
+    }
+    let budgets = draft.pools.iter().map(|pool| pool.budget);
+    let budgets = budgets.chain(draft.team_budgets.values().map(|budget| Some(*budget)));
+    if budgets
+        .chain(Some(draft.budget))
+        .any(|budget| budget == Some(0))
+    {
+        violations.push(DraftError::InvalidBudget);
+    }
+    if !draft.members.is_empty() {
+        if let Some(team) = draft
+            .team_budgets
+            .keys()
+            .find(|team| !draft.members.iter().any(|member| member.team == **team))
+        {
+            violations.push(DraftError::UnknownBudgetTeam { team: *team });
+        }
+    }
+    let mut pools = HashSet::new();
+    if let Some(pool) = draft.pools.iter().find(|pool| !pools.insert(&pool.name)) {
+        violations.push(DraftError::DuplicatePool {
+            name: pool.name.clone(),
+        });
+    }
+    if let Some(template) = mail::invalid(&draft.emails) {
+        violations.push(DraftError::InvalidEmailTemplate { template });
+    }
+    violations
+}
+
+// The first message for each field, keyed by the field's name.
+pub fn field_errors(errors: &[DraftError], locale: Locale) -> BTreeMap<&'static str, String> {
+    let mut fields = BTreeMap::new();
+    for error in errors {
+        fields
+            .entry(error.field())
+            .or_insert_with(|| error.localized(locale));
+    }
+    fields
+}
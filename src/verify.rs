@@ -0,0 +1,105 @@
+use crate::{elephant, name_key, Draft, Drafts, Organizer};
+use rocket::http::Status;
+use rocket::State;
+use rocket_contrib::json::Json;
+use std::collections::HashMap;
+
+pub use api::verify::{Report, Violation};
+
+// Checks that every member gives exactly once and receives exactly once,
+// never to themselves, to their own team or across pools. Swaps, repairs
+// and members leaving change the tickets after the draw, this catches
+// what they get wrong.
+pub fn check(draft: &Draft) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut givers = draft
+        .members
+        .iter()
+        .map(|member| (name_key(&member.name), 0))
+        .collect::<HashMap<String, usize>>();
+    let mut members = draft.members.iter().collect::<Vec<_>>();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    for giver in &members {
+        let name = match giver.recipient() {
+            Some(name) => name,
+            None => {
+                violations.push(Violation::NoRecipient {
+                    giver: giver.name.clone(),
+                });
+                continue;
+            }
+        };
+        let recipient = match draft.find_member(&name) {
+            Some(recipient) => recipient,
+            None => {
+                violations.push(Violation::UnknownRecipient {
+                    giver: giver.name.clone(),
+                    recipient: name,
+                });
+                continue;
+            }
+        };
+        *givers.entry(name_key(&recipient.name)).or_insert(0) += 1;
+        if recipient.name == giver.name {
+            violations.push(Violation::SelfGift {
+                giver: giver.name.clone(),
+            });
+        } else if recipient.team == giver.team {
+            violations.push(Violation::SameTeam {
+                giver: giver.name.clone(),
+                recipient: recipient.name.clone(),
+                team: giver.team,
+            });
+        }
+        if recipient.pool != giver.pool {
+            violations.push(Violation::OtherPool {
+                giver: giver.name.clone(),
+                recipient: recipient.name.clone(),
+            });
+        }
+    }
+    for member in &members {
+        match givers.get(&name_key(&member.name)).copied().unwrap_or(0) {
+            0 => violations.push(Violation::NoGiver {
+                name: member.name.clone(),
+            }),
+            1 => (),
+            count => violations.push(Violation::SeveralGivers {
+                name: member.name.clone(),
+                givers: count,
+            }),
+        }
+    }
+    violations
+}
+
+// The violations name who gives to whom, so like the export of the
+// assignments only the organizer gets them, and not in the blind mode.
+#[get("/api/draft/<id>/verify")]
+pub fn api_verify(
+    id: usize,
+    organizer: Organizer,
+    drafts: State<Drafts>,
+) -> Result<Json<Report>, Status> {
+    let entry = drafts.get(id).ok_or(Status::NotFound)?;
+    let draft = entry.read();
+    if !organizer.owns(&draft) || draft.blind {
+        return Err(Status::Forbidden);
+    }
+    // Nobody can read the tickets in the zero-knowledge mode, and a white
+    // elephant game has none. Members added since the draw make it look
+    // undrawn, so any ticket counts.
+    let drawn = draft.members.iter().any(|member| member.ticket.is_some());
+    if !drawn || draft.passphrases || draft.mode == elephant::Mode::WhiteElephant {
+        return Err(Status::Conflict);
+    }
+    let violations = check(&draft);
+    if !violations.is_empty() {
+        tracing::warn!(
+            id,
+            violations = violations.len(),
+            "draft failed verification"
+        );
+    }
+    Ok(Json(Report::new(violations)))
+}